@@ -0,0 +1,199 @@
+//! Every scheduler is owned by a task that runs operations on it one at a time and saves it
+//! shortly after it changes, so concurrent interactions can't interleave and saves land in order.
+//! Changes not yet saved are written when the scheduler is stopped.
+
+use crate::scheduler::Scheduler;
+use crate::storage::{self, Storage};
+
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use serenity::model::id::{GuildId, MessageId};
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{Duration, Instant};
+use tracing::{error, info_span, Instrument, Span};
+
+type Job = Box<dyn for<'a> FnOnce(&'a mut Scheduler) -> BoxFuture<'a, ()> + Send>;
+
+enum Command {
+    /// `caller` is the span the operation was sent from, so what it logs stays attributed
+    Run {
+        job: Job,
+        save: bool,
+        caller: Span,
+    },
+    Stop(oneshot::Sender<()>),
+    Take(oneshot::Sender<Scheduler>),
+}
+
+fn job<F>(f: F) -> Job
+where
+    F: for<'a> FnOnce(&'a mut Scheduler) -> BoxFuture<'a, ()> + Send + 'static,
+{
+    Box::new(f)
+}
+
+#[derive(Clone)]
+pub struct SchedulerHandle {
+    guild: Option<GuildId>,
+    tx: mpsc::UnboundedSender<Command>,
+}
+
+impl SchedulerHandle {
+    pub fn spawn(message_id: MessageId, scheduler: Scheduler, storage: Arc<dyn Storage>) -> Self {
+        let guild = scheduler.guild();
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(run(message_id, scheduler, storage, rx));
+        SchedulerHandle { guild, tx }
+    }
+
+    pub fn guild(&self) -> Option<GuildId> {
+        self.guild
+    }
+
+    async fn send<T, F>(&self, save: bool, f: F) -> Option<T>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a mut Scheduler) -> BoxFuture<'a, T> + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        let job = job(move |scheduler| {
+            Box::pin(async move {
+                let _ = reply.send(f(scheduler).await);
+            })
+        });
+        let caller = Span::current();
+        self.tx.send(Command::Run { job, save, caller }).ok()?;
+        // Fails if the scheduler was stopped or the operation panicked
+        result.await.ok()
+    }
+
+    /// Run `f` with exclusive access to the scheduler, saving it soon afterwards
+    pub async fn update<T, F>(&self, f: F) -> Option<T>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a mut Scheduler) -> BoxFuture<'a, T> + Send + 'static,
+    {
+        self.send(true, f).await
+    }
+
+    /// Run `f` on the scheduler without saving it
+    pub async fn view<T, F>(&self, f: F) -> Option<T>
+    where
+        T: Send + 'static,
+        F: for<'a> FnOnce(&'a Scheduler) -> BoxFuture<'a, T> + Send + 'static,
+    {
+        self.send(false, move |scheduler| f(scheduler)).await
+    }
+
+    /// Read a value out of the scheduler
+    pub async fn get<T, F>(&self, f: F) -> Option<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Scheduler) -> T + Send + 'static,
+    {
+        self.send(false, move |scheduler| {
+            let value = f(scheduler);
+            Box::pin(async move { value })
+        })
+        .await
+    }
+
+    /// Finish any queued operations and shut the scheduler's task down
+    pub async fn stop(&self) {
+        let (done, stopped) = oneshot::channel();
+        if self.tx.send(Command::Stop(done)).is_ok() {
+            stopped.await.ok();
+        }
+    }
+
+    /// Like `stop`, handing the scheduler back instead of dropping it
+    pub async fn take(&self) -> Option<Scheduler> {
+        let (done, taken) = oneshot::channel();
+        self.tx.send(Command::Take(done)).ok()?;
+        taken.await.ok()
+    }
+}
+
+/// Save a scheduler's changes at most this long after it was first changed, so a burst of
+/// operations is written once
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+
+async fn save(
+    message_id: MessageId,
+    scheduler: Scheduler,
+    storage: &Arc<dyn Storage>,
+) -> Scheduler {
+    async {
+        let (scheduler, result) = storage::save(storage, message_id, scheduler).await;
+        if let Err(e) = result {
+            error!("Cannot save scheduler {}: {}", message_id, e);
+        }
+        scheduler
+    }
+    .instrument(info_span!("save", message = message_id.get()))
+    .await
+}
+
+async fn run(
+    message_id: MessageId,
+    mut scheduler: Scheduler,
+    storage: Arc<dyn Storage>,
+    mut rx: mpsc::UnboundedReceiver<Command>,
+) {
+    // When the unsaved changes are due to be written, if there are any
+    let mut dirty: Option<Instant> = None;
+    loop {
+        let command = match dirty {
+            Some(due) => tokio::select! {
+                command = rx.recv() => command,
+                () = tokio::time::sleep_until(due) => {
+                    scheduler = save(message_id, scheduler, &storage).await;
+                    dirty = None;
+                    continue;
+                }
+            },
+            None => rx.recv().await,
+        };
+        // Every handle is gone, so nothing else will change it
+        let command = match command {
+            Some(command) => command,
+            None => break,
+        };
+        match command {
+            Command::Run { job, save, caller } => {
+                let span = info_span!(parent: &caller, "scheduler", message = message_id.get());
+                // A failed operation shouldn't take the scheduler down with it
+                if AssertUnwindSafe(job(&mut scheduler))
+                    .catch_unwind()
+                    .instrument(span.clone())
+                    .await
+                    .is_err()
+                {
+                    span.in_scope(|| error!("operation on scheduler {} failed", message_id));
+                }
+                if save && dirty.is_none() {
+                    dirty = Some(Instant::now() + SAVE_DELAY);
+                }
+            }
+            Command::Stop(done) => {
+                if dirty.is_some() {
+                    save(message_id, scheduler, &storage).await;
+                }
+                done.send(()).ok();
+                return;
+            }
+            Command::Take(done) => {
+                if dirty.is_some() {
+                    scheduler = save(message_id, scheduler, &storage).await;
+                }
+                done.send(scheduler).ok();
+                return;
+            }
+        }
+    }
+    if dirty.is_some() {
+        save(message_id, scheduler, &storage).await;
+    }
+}
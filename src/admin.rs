@@ -0,0 +1,235 @@
+//! Maintenance operations for the bot's operators. Each is shown as a dry run with a button to
+//! carry it out.
+
+use crate::actor::SchedulerHandle;
+use crate::error::{Error, Result};
+use crate::storage::{self, Storage};
+use crate::{limits, Schedulers};
+
+use chrono::{DateTime, Duration, Utc};
+use serenity::client::Context;
+use serenity::model::application::CommandDataOption;
+use serenity::model::id::{GuildId, MessageId};
+use std::sync::Arc;
+use tracing::error;
+
+#[derive(Clone, Copy)]
+pub enum Operation {
+    /// Close open schedulers created more than this many weeks ago
+    CloseOlderThan(u64),
+    Rerender(GuildId),
+    /// Delete archived schedulers, from one guild or all of them
+    PurgeArchives(Option<GuildId>),
+}
+
+impl Operation {
+    /// Parse the operation carried by a "Run" button
+    pub fn parse(action: &str, target: u64) -> Option<Self> {
+        match action {
+            "admin_close" => Some(Operation::CloseOlderThan(target)),
+            "admin_rerender" => Some(Operation::Rerender(GuildId::new(target))),
+            "admin_purge" => Some(Operation::PurgeArchives(
+                (target != 0).then_some(GuildId::new(target)),
+            )),
+            _ => None,
+        }
+    }
+
+    pub fn from_command(option: &CommandDataOption) -> Result<Self> {
+        let value = |name: &str| {
+            crate::subcommand_options(option)
+                .iter()
+                .find(|o| o.name == name)
+                .map(|o| &o.value)
+        };
+        let guild = |name: &str| -> Result<Option<GuildId>> {
+            value(name)
+                .map(|v| {
+                    let v = v.as_str().unwrap_or_default();
+                    v.parse()
+                        .map(GuildId::new)
+                        .map_err(|_| Error::user(format!("Not a guild id: {}", v)))
+                })
+                .transpose()
+        };
+        match option.name.as_str() {
+            "close" => {
+                let weeks = value("weeks")
+                    .and_then(|v| v.as_i64())
+                    .map(|weeks| weeks as u64)
+                    .expect("Cannot find weeks option");
+                Ok(Operation::CloseOlderThan(weeks))
+            }
+            "rerender" => guild("guild")?
+                .map(Operation::Rerender)
+                .ok_or_else(|| Error::user("A guild is required".to_owned())),
+            "purge_archives" => Ok(Operation::PurgeArchives(guild("guild")?)),
+            _ => Err(Error::Unexpected(format!("admin command {}", option.name))),
+        }
+    }
+
+    pub fn custom_id(&self) -> String {
+        match self {
+            Operation::CloseOlderThan(weeks) => format!("admin_close {}", weeks),
+            Operation::Rerender(guild) => format!("admin_rerender {}", guild),
+            Operation::PurgeArchives(guild) => {
+                format!("admin_purge {}", guild.map_or(0, |g| g.get()))
+            }
+        }
+    }
+
+    async fn targets(&self, schedulers: &Schedulers) -> Vec<(MessageId, SchedulerHandle, String)> {
+        let handles: Vec<(MessageId, SchedulerHandle)> = schedulers
+            .iter()
+            .map(|h| (*h.key(), h.value().clone()))
+            .collect();
+        let mut targets = vec![];
+        for (id, handle) in handles {
+            let selected = match self {
+                Operation::CloseOlderThan(weeks) => {
+                    let cutoff = Utc::now() - Duration::weeks(*weeks as i64);
+                    id.created_at().unix_timestamp() < cutoff.timestamp()
+                        && !handle.get(|s| s.is_closed()).await.unwrap_or(true)
+                }
+                Operation::Rerender(guild) => handle.guild() == Some(*guild),
+                Operation::PurgeArchives(_) => false,
+            };
+            if selected {
+                if let Some(title) = handle.get(|s| s.title().to_owned()).await {
+                    targets.push((id, handle, title));
+                }
+            }
+        }
+        targets
+    }
+
+    async fn archives(&self, storage: &Arc<dyn Storage>) -> Vec<(MessageId, String)> {
+        match *self {
+            Operation::PurgeArchives(guild) => {
+                storage::blocking(storage, move |storage| {
+                    storage
+                        .archived(guild)
+                        .into_iter()
+                        .map(|(id, s)| (id, s.title().to_owned()))
+                        .collect()
+                })
+                .await
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Describe what running the operation would do
+    pub async fn dry_run(&self, schedulers: &Schedulers, storage: &Arc<dyn Storage>) -> String {
+        let lines: Vec<String> = match self {
+            Operation::PurgeArchives(_) => self
+                .archives(storage)
+                .await
+                .into_iter()
+                .map(|(id, title)| format!("`{}` {}", id, title))
+                .collect(),
+            _ => self
+                .targets(schedulers)
+                .await
+                .into_iter()
+                .map(|(id, _, title)| format!("`{}` {}", id, title))
+                .collect(),
+        };
+        let verb = match self {
+            Operation::CloseOlderThan(_) => "close",
+            Operation::Rerender(_) => "re-render",
+            Operation::PurgeArchives(_) => "delete",
+        };
+        let header = format!("Would {} {}:", verb, lines.len());
+        limits::truncate(
+            &std::iter::once(header)
+                .chain(lines)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            limits::MESSAGE_LENGTH,
+        )
+    }
+
+    pub async fn run(
+        &self,
+        ctx: &Context,
+        schedulers: &Schedulers,
+        storage: &Arc<dyn Storage>,
+    ) -> String {
+        match self {
+            Operation::CloseOlderThan(_) => {
+                let targets = self.targets(schedulers).await;
+                for (_, handle, _) in targets.iter() {
+                    let ctx = ctx.clone();
+                    handle
+                        .update(move |s| Box::pin(async move { s.close(&ctx).await }))
+                        .await;
+                }
+                format!("Closed {}", targets.len())
+            }
+            Operation::Rerender(_) => {
+                let targets = self.targets(schedulers).await;
+                for (_, handle, _) in targets.iter() {
+                    let ctx = ctx.clone();
+                    handle
+                        .view(move |s| Box::pin(async move { s.update_message(&ctx).await }))
+                        .await;
+                }
+                format!("Re-rendered {}", targets.len())
+            }
+            Operation::PurgeArchives(_) => {
+                let archives = self.archives(storage).await;
+                let deleted = storage::blocking(storage, move |storage| {
+                    archives
+                        .iter()
+                        .filter(|(id, _)| match storage.delete_archived(*id) {
+                            Ok(()) => true,
+                            Err(e) => {
+                                error!("Cannot delete archived scheduler {}: {}", id, e);
+                                false
+                            }
+                        })
+                        .count()
+                })
+                .await;
+                format!("Deleted {}", deleted)
+            }
+        }
+    }
+}
+
+/// Storage health for `/admin storage`
+pub async fn storage_report(storage: &Arc<dyn Storage>) -> String {
+    let stats = storage::blocking(storage, |storage| storage.stats()).await;
+    let ago = |at: DateTime<Utc>| format!("<t:{}:R>", at.timestamp());
+    let lines = [
+        format!("Data files: {} ({} KiB)", stats.files, stats.bytes / 1024),
+        format!(
+            "Schedulers: {} live, {} archived",
+            stats.live, stats.archived
+        ),
+        format!(
+            "Oldest closed but not archived: {}",
+            stats.oldest_closed.map_or("none".to_owned(), |id| format!(
+                "`{}` <t:{}:R>",
+                id,
+                id.created_at().unix_timestamp()
+            ))
+        ),
+        format!(
+            "Last backup: {}",
+            stats.last_backup.map_or("never".to_owned(), ago)
+        ),
+        format!(
+            "Last failed write: {}",
+            stats
+                .last_failed_write
+                .map_or("none since startup".to_owned(), |(at, e)| format!(
+                    "{} {}",
+                    ago(at),
+                    e
+                ))
+        ),
+    ];
+    limits::truncate(&lines.join("\n"), limits::MESSAGE_LENGTH)
+}
@@ -0,0 +1,58 @@
+//! The message posted in a scheduler's channel when its date is finalized, from a template the
+//! owner or the guild can set
+
+use crate::limits;
+use crate::locale::{Phrase, Text};
+
+pub const DEFAULT_TEMPLATE: &str = "**{title}** is on for **{when}**! {attendees}\n{link}";
+/// Placeholders a template may use, filled in by the scheduler being finalized
+pub const PLACEHOLDERS: [&str; 7] = [
+    "title",
+    "date",
+    "time",
+    "when",
+    "attendees",
+    "venue",
+    "link",
+];
+/// Room left for what the placeholders expand to
+const MAX_TEMPLATE_LENGTH: usize = 500;
+
+/// Check that `template` only uses known placeholders and leaves room for what they expand to
+pub fn validate(template: &str) -> Result<(), Phrase> {
+    if template.len() > MAX_TEMPLATE_LENGTH {
+        return Err(Phrase::new(
+            Text::AnnouncementTooLong,
+            &[&MAX_TEMPLATE_LENGTH],
+        ));
+    }
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+        let name = &rest[start + 1..end];
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(Phrase::new(
+                Text::UnknownPlaceholder,
+                &[
+                    &format!("{{{}}}", name),
+                    &PLACEHOLDERS.map(|p| format!("{{{}}}", p)).join(" "),
+                ],
+            ));
+        }
+        rest = &rest[end + 1..];
+    }
+    Ok(())
+}
+
+/// `template` with each `{name}` replaced by its value
+pub fn render(template: &str, values: &[(&str, String)]) -> String {
+    let content = values
+        .iter()
+        .fold(template.to_owned(), |content, (name, value)| {
+            content.replace(&format!("{{{}}}", name), value)
+        });
+    limits::truncate(&content, limits::MESSAGE_LENGTH)
+}
@@ -0,0 +1,142 @@
+//! Read-only JSON about schedulers over HTTP, for dashboards that show availability outside
+//! Discord. Only counts are exposed, never who responded.
+
+use crate::actor::SchedulerHandle;
+use crate::scheduler::Scheduler;
+use crate::Schedulers;
+
+use axum::extract::{Extension, Path};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use serenity::model::id::{ChannelId, GuildId, MessageId};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{error, info};
+
+#[derive(Serialize)]
+struct Summary {
+    id: MessageId,
+    title: String,
+    guild: Option<GuildId>,
+    channel: ChannelId,
+    closed: bool,
+    responses: usize,
+}
+
+impl Summary {
+    fn of(id: MessageId, scheduler: &Scheduler) -> Self {
+        Summary {
+            id,
+            title: scheduler.title().to_owned(),
+            guild: scheduler.guild(),
+            channel: scheduler.channel_id(),
+            closed: scheduler.is_closed(),
+            responses: scheduler.response_count(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Availability {
+    date: String,
+    available: usize,
+    maybe: usize,
+}
+
+#[derive(Serialize)]
+struct Details {
+    #[serde(flatten)]
+    summary: Summary,
+    finalized: Option<String>,
+    /// Set while too few have responded for the tally to be shown, leaving `dates` empty
+    embargoed: bool,
+    dates: Vec<Availability>,
+}
+
+impl Details {
+    fn of(id: MessageId, scheduler: &Scheduler) -> Self {
+        let embargoed = scheduler.is_embargoed();
+        let dates = if embargoed {
+            vec![]
+        } else {
+            scheduler
+                .tally()
+                .into_iter()
+                .map(|(slot, firm, maybe)| Availability {
+                    date: slot.to_string(),
+                    available: firm,
+                    maybe,
+                })
+                .collect()
+        };
+        Details {
+            summary: Summary::of(id, scheduler),
+            finalized: scheduler.finalized().map(|slot| slot.to_string()),
+            embargoed,
+            dates,
+        }
+    }
+}
+
+/// For uptime checks: answering at all means the bot is up
+#[derive(Serialize)]
+struct Health {
+    schedulers: usize,
+}
+
+/// Serve `/health`, `/schedulers` and `/schedulers/:message_id` on `port` of localhost until the
+/// bot exits
+pub async fn serve(port: u16, schedulers: Arc<Schedulers>) {
+    let app = Router::new()
+        .route("/health", get(health))
+        .route("/schedulers", get(list))
+        .route("/schedulers/:message_id", get(show))
+        .layer(Extension(schedulers));
+    let address = SocketAddr::from(([127, 0, 0, 1], port));
+    info!("serving API on {}", address);
+    if let Err(e) = axum::Server::bind(&address)
+        .serve(app.into_make_service())
+        .await
+    {
+        error!("API server stopped: {}", e);
+    }
+}
+
+async fn health(Extension(schedulers): Extension<Arc<Schedulers>>) -> Json<Health> {
+    Json(Health {
+        schedulers: schedulers.len(),
+    })
+}
+
+async fn list(Extension(schedulers): Extension<Arc<Schedulers>>) -> Json<Vec<Summary>> {
+    let handles: Vec<(MessageId, SchedulerHandle)> = schedulers
+        .iter()
+        .map(|h| (*h.key(), h.value().clone()))
+        .collect();
+    let mut summaries = vec![];
+    for (id, handle) in handles {
+        if let Some(summary) = handle.get(move |s| Summary::of(id, s)).await {
+            summaries.push(summary);
+        }
+    }
+    summaries.sort_unstable_by_key(|s| s.id);
+    Json(summaries)
+}
+
+async fn show(
+    Path(message_id): Path<u64>,
+    Extension(schedulers): Extension<Arc<Schedulers>>,
+) -> Result<Json<Details>, StatusCode> {
+    let id = MessageId::new(message_id);
+    let handle = schedulers
+        .get(&id)
+        .map(|h| h.clone())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    handle
+        .get(move |s| Details::of(id, s))
+        .await
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
@@ -0,0 +1,156 @@
+//! Scheduler message edits held back briefly, so a burst of responses costs one API call
+
+use crate::limits;
+use crate::message_shim::MessageShim;
+use crate::render::{Field, Rendered};
+
+use serenity::builder::{
+    CreateActionRow, CreateAllowedMentions, CreateButton, CreateEmbed, CreateEmbedFooter,
+    EditMessage,
+};
+use serenity::client::Context;
+use serenity::model::id::{MessageId, RoleId, UserId};
+use serenity::prelude::TypeMapKey;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::error;
+
+/// How long an edit waits for later ones to replace it
+pub const WINDOW: Duration = Duration::from_secs(2);
+
+/// A rendered scheduler to put on `message`, pinging only `group` and `users`
+pub struct Edit {
+    pub message: MessageShim,
+    pub rendered: Rendered,
+    pub group: Option<RoleId>,
+    pub users: Vec<UserId>,
+}
+
+impl Edit {
+    /// Make the edit now, returning whether it went through
+    pub async fn apply(self, ctx: &Context) -> bool {
+        let Edit {
+            message,
+            rendered,
+            group,
+            users,
+        } = self;
+        let embeds = embeds(
+            rendered.title,
+            rendered.description,
+            rendered.footer,
+            rendered.fields,
+        );
+        let buttons: Vec<CreateButton> = rendered
+            .buttons
+            .into_iter()
+            .map(|button| {
+                CreateButton::new(button.custom_id)
+                    .style(button.style)
+                    .label(button.label)
+            })
+            .collect();
+        let components = if buttons.is_empty() {
+            vec![]
+        } else {
+            vec![CreateActionRow::Buttons(buttons)]
+        };
+        let edit = EditMessage::new()
+            .content(rendered.content)
+            .embeds(embeds)
+            .components(components)
+            .allowed_mentions(CreateAllowedMentions::new().roles(group).users(users))
+            .suppress_embeds(false);
+        message
+            .edit(ctx, edit)
+            .await
+            .map_err(|e| error!("Cannot edit message: {}", e))
+            .is_ok()
+    }
+}
+
+/// The title and description on the first embed and the footer on the last, with the fields
+/// spread across as many embeds as it takes
+fn embeds(
+    title: String,
+    description: String,
+    footer: Option<String>,
+    fields: Vec<Field>,
+) -> Vec<CreateEmbed> {
+    let mut embeds: Vec<CreateEmbed> = fields
+        .chunks(limits::EMBED_FIELDS)
+        .map(|fields| {
+            CreateEmbed::new().fields(
+                fields
+                    .iter()
+                    .map(|field| (field.name, field.value.clone(), field.inline)),
+            )
+        })
+        .collect();
+    if embeds.is_empty() {
+        embeds.push(CreateEmbed::new());
+    }
+    embeds[0] = embeds[0].clone().title(title).description(description);
+    if let Some(footer) = footer {
+        let last = embeds.pop().unwrap();
+        embeds.push(last.footer(CreateEmbedFooter::new(footer)));
+    }
+    embeds
+}
+
+/// Make `edits` now, returning whether the last one went through
+pub async fn apply_all(ctx: &Context, edits: Vec<Edit>) -> bool {
+    let mut edited = false;
+    for edit in edits {
+        edited = edit.apply(ctx).await;
+    }
+    edited
+}
+
+/// The latest edit waiting for each message
+#[derive(Clone, Default)]
+pub struct Debouncer {
+    pending: Arc<Mutex<HashMap<MessageId, (Context, Edit)>>>,
+}
+
+impl TypeMapKey for Debouncer {
+    type Value = Debouncer;
+}
+
+impl Debouncer {
+    pub async fn get(ctx: &Context) -> Option<Debouncer> {
+        ctx.data.read().await.get::<Debouncer>().cloned()
+    }
+
+    /// Make `edit` once `WINDOW` has passed, unless a later edit of the same message replaces it
+    pub fn queue(&self, ctx: &Context, edit: Edit) {
+        let id = edit.message.id();
+        let waiting = self
+            .pending
+            .lock()
+            .unwrap()
+            .insert(id, (ctx.clone(), edit))
+            .is_some();
+        // The edit already waiting will be sent as this one
+        if waiting {
+            return;
+        }
+        let debouncer = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(WINDOW).await;
+            let pending = debouncer.pending.lock().unwrap().remove(&id);
+            if let Some((ctx, edit)) = pending {
+                edit.apply(&ctx).await;
+            }
+        });
+    }
+
+    /// Make every waiting edit now
+    pub async fn flush(&self) {
+        let pending: Vec<_> = self.pending.lock().unwrap().drain().collect();
+        for (_, (ctx, edit)) in pending {
+            edit.apply(&ctx).await;
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! Why an interaction could not be handled. Handlers return these instead of panicking, and the
+//! user is told what went wrong in terms of who can do something about it.
+
+use crate::locale::{Locale, Phrase, Text};
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    /// Something the user asked for can't be done, e.g. a malformed date; shown to them as is
+    User(Phrase),
+    /// A scheduler or server is set up in a way that can't work, which its owner or a server
+    /// manager has to fix
+    Owner(Phrase),
+    /// Boxed, as serenity's errors are large enough to bloat every `Result`
+    Discord(Box<serenity::Error>),
+    /// The interaction refers to something that no longer exists
+    Missing(&'static str),
+    /// The scheduler's task stopped or the operation on it failed
+    Scheduler,
+    /// An interaction or component this version doesn't know about
+    Unexpected(String),
+    /// Data files could not be written
+    Io(std::io::Error),
+    Panicked,
+}
+
+pub type Result<T = ()> = std::result::Result<T, Error>;
+
+/// Who an error is for, which decides how it is phrased and logged
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tier {
+    User,
+    Owner,
+    /// Failures of the bot or Discord, logged as errors and reported with a reference
+    System,
+}
+
+impl Error {
+    pub fn user(msg: impl Into<Phrase>) -> Self {
+        Error::User(msg.into())
+    }
+
+    pub fn owner(msg: impl Into<Phrase>) -> Self {
+        Error::Owner(msg.into())
+    }
+
+    pub fn tier(&self) -> Tier {
+        match self {
+            Error::User(_) | Error::Missing(_) => Tier::User,
+            Error::Owner(_) => Tier::Owner,
+            Error::Discord(_)
+            | Error::Scheduler
+            | Error::Unexpected(_)
+            | Error::Io(_)
+            | Error::Panicked => Tier::System,
+        }
+    }
+
+    /// The ephemeral reply in `locale`, quoting `reference` where the logs need to be looked at
+    pub fn reply(&self, locale: Locale, reference: &str) -> String {
+        match self {
+            Error::User(msg) => locale.phrase(msg),
+            Error::Missing("scheduler" | "scheduler message") => {
+                locale.text(Text::MissingScheduler).to_owned()
+            }
+            Error::Missing("guild") => locale.text(Text::MissingServer).to_owned(),
+            Error::Missing(_) => locale.text(Text::MissingMessage).to_owned(),
+            Error::Owner(msg) => locale.fill(Text::NeedsFixing, &[&locale.phrase(msg)]),
+            _ => locale.fill(Text::SomethingWentWrong, &[&reference]),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::User(msg) => write!(f, "Refused: {}", msg),
+            Error::Owner(msg) => write!(f, "Misconfigured: {}", msg),
+            Error::Discord(e) => write!(f, "Discord error: {}", e),
+            Error::Missing(what) => write!(f, "Cannot find {}", what),
+            Error::Scheduler => write!(f, "Scheduler operation failed"),
+            Error::Unexpected(what) => write!(f, "Unexpected {}", what),
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Panicked => write!(f, "panicked"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serenity::Error> for Error {
+    fn from(e: serenity::Error) -> Self {
+        Error::Discord(Box::new(e))
+    }
+}
@@ -0,0 +1,145 @@
+//! Discord scheduled events mirroring a scheduler's final date
+
+#[cfg(any(test, feature = "test-harness"))]
+use crate::harness::{Call, Recorder};
+use crate::slot::Slot;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use serenity::builder::{CreateScheduledEvent, EditScheduledEvent};
+use serenity::client::Context;
+use serenity::model::guild::ScheduledEventType;
+use serenity::model::id::{ChannelId, GuildId, ScheduledEventId};
+use serenity::model::Timestamp;
+use tracing::error;
+
+/// How long an event on a time of day is shown to last
+const EVENT_HOURS: i64 = 3;
+
+/// A voice or stage channel sessions are held in
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Venue {
+    pub channel: ChannelId,
+    pub stage: bool,
+}
+
+impl Venue {
+    fn kind(&self) -> ScheduledEventType {
+        if self.stage {
+            ScheduledEventType::StageInstance
+        } else {
+            ScheduledEventType::Voice
+        }
+    }
+}
+
+pub struct Event {
+    pub name: String,
+    pub description: Option<String>,
+    /// Where the event is, shown on external events; the link to the scheduler
+    pub location: String,
+    /// The channel the event is in, instead of an external location
+    pub venue: Option<Venue>,
+    pub slot: Slot,
+    pub timezone: Tz,
+}
+
+impl Event {
+    /// When the event starts and ends; whole days last until midnight
+    pub fn bounds(&self) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let start = match self.slot.time {
+            Some(time) => self.slot.date.and_time(time),
+            None => self.slot.date.and_hms_opt(0, 0, 0).unwrap(),
+        };
+        let start = self.timezone.from_local_datetime(&start).earliest()?;
+        let start: DateTime<Utc> = start.with_timezone(&Utc);
+        let end = match self.slot.time {
+            Some(_) => start + Duration::hours(EVENT_HOURS),
+            None => start + Duration::days(1),
+        };
+        Some((start, end))
+    }
+
+    /// Like `bounds`, as Discord takes them
+    fn span(&self) -> Option<(Timestamp, Timestamp)> {
+        let (start, end) = self.bounds()?;
+        // Discord only takes events that start in the future
+        let start = start.max(Utc::now() + Duration::minutes(1));
+        Some((
+            Timestamp::from_unix_timestamp(start.timestamp()).ok()?,
+            Timestamp::from_unix_timestamp(end.timestamp()).ok()?,
+        ))
+    }
+}
+
+/// Create a scheduled event, returning its id
+pub async fn create(ctx: &Context, guild: GuildId, event: &Event) -> Option<ScheduledEventId> {
+    let (start, end) = event.span()?;
+    #[cfg(any(test, feature = "test-harness"))]
+    if let Some(recorder) = Recorder::get(ctx).await {
+        recorder.record(Call::CreateEvent {
+            guild,
+            name: event.name.clone(),
+        });
+        return Some(ScheduledEventId::new(1));
+    }
+    let mut builder = match event.venue {
+        Some(venue) => {
+            CreateScheduledEvent::new(venue.kind(), &event.name, start).channel_id(venue.channel)
+        }
+        None => CreateScheduledEvent::new(ScheduledEventType::External, &event.name, start)
+            .location(&event.location),
+    }
+    .end_time(end);
+    if let Some(description) = &event.description {
+        builder = builder.description(description);
+    }
+    guild
+        .create_scheduled_event(ctx, builder)
+        .await
+        .map(|e| e.id)
+        .map_err(|e| error!("Cannot create scheduled event: {}", e))
+        .ok()
+}
+
+/// Move an existing event to `event`'s date. Returns whether it still exists.
+pub async fn edit(ctx: &Context, guild: GuildId, id: ScheduledEventId, event: &Event) -> bool {
+    let (start, end) = match event.span() {
+        Some(span) => span,
+        None => return false,
+    };
+    #[cfg(any(test, feature = "test-harness"))]
+    if let Some(recorder) = Recorder::get(ctx).await {
+        recorder.record(Call::EditEvent { guild, id });
+        return true;
+    }
+    let builder = EditScheduledEvent::new()
+        .name(&event.name)
+        .start_time(start)
+        .end_time(end);
+    let builder = match event.venue {
+        Some(venue) => builder.kind(venue.kind()).channel_id(venue.channel),
+        None => builder
+            .kind(ScheduledEventType::External)
+            .location(&event.location),
+    };
+    guild
+        .edit_scheduled_event(ctx, id, builder)
+        .await
+        .map_err(|e| error!("Cannot edit scheduled event: {}", e))
+        .is_ok()
+}
+
+pub async fn delete(ctx: &Context, guild: GuildId, id: ScheduledEventId) {
+    #[cfg(any(test, feature = "test-harness"))]
+    if let Some(recorder) = Recorder::get(ctx).await {
+        recorder.record(Call::DeleteEvent { guild, id });
+        return;
+    }
+    guild
+        .delete_scheduled_event(ctx, id)
+        .await
+        .map_err(|e| error!("Cannot delete scheduled event: {}", e))
+        .ok();
+}
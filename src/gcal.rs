@@ -0,0 +1,166 @@
+//! Copies of finalized dates in a Google Calendar, turned on by setting GOOGLE_CLIENT_ID,
+//! GOOGLE_CLIENT_SECRET, GOOGLE_REFRESH_TOKEN and GOOGLE_CALENDAR_ID. Failures are only logged, so
+//! schedulers close the same way with or without it.
+
+use crate::events::Event;
+use crate::slot::Slot;
+
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::env;
+use std::time::Duration;
+use tracing::error;
+
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const CALENDARS_URL: &str = "https://www.googleapis.com/calendar/v3/calendars";
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The event inserted for a scheduler's final date
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    pub id: String,
+    /// Opens the event in Google Calendar
+    pub link: String,
+    pub slot: Slot,
+}
+
+struct Credentials {
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+    calendar_id: String,
+}
+
+impl Credentials {
+    fn from_env() -> Option<Self> {
+        let var = |name| {
+            env::var(name)
+                .ok()
+                .filter(|value: &String| !value.is_empty())
+        };
+        Some(Credentials {
+            client_id: var("GOOGLE_CLIENT_ID")?,
+            client_secret: var("GOOGLE_CLIENT_SECRET")?,
+            refresh_token: var("GOOGLE_REFRESH_TOKEN")?,
+            calendar_id: var("GOOGLE_CALENDAR_ID")?,
+        })
+    }
+
+    /// Trade the refresh token for a short-lived access token
+    async fn access_token(&self, client: &Client) -> reqwest::Result<String> {
+        #[derive(Deserialize)]
+        struct Token {
+            access_token: String,
+        }
+        let token: Token = client
+            .post(TOKEN_URL)
+            .form(&[
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("refresh_token", self.refresh_token.as_str()),
+                ("grant_type", "refresh_token"),
+            ])
+            .timeout(TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(token.access_token)
+    }
+
+    /// The events of the calendar, or one of them
+    fn events_url(&self, id: Option<&str>) -> Url {
+        let mut url = Url::parse(CALENDARS_URL).expect("Cannot parse calendar URL");
+        {
+            let mut segments = url.path_segments_mut().expect("Cannot extend calendar URL");
+            segments.push(&self.calendar_id).push("events");
+            segments.extend(id);
+        }
+        url
+    }
+}
+
+/// Insert `event` into the configured calendar; `None` if there is none or it fails
+pub async fn insert(event: &Event) -> Option<CalendarEvent> {
+    let credentials = Credentials::from_env()?;
+    match try_insert(&credentials, event).await {
+        Ok(inserted) => Some(inserted),
+        Err(e) => {
+            error!("Cannot add event to Google Calendar: {}", e);
+            None
+        }
+    }
+}
+
+async fn try_insert(credentials: &Credentials, event: &Event) -> reqwest::Result<CalendarEvent> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Inserted {
+        id: String,
+        html_link: String,
+    }
+    let client = Client::new();
+    let token = credentials.access_token(&client).await?;
+    let inserted: Inserted = client
+        .post(credentials.events_url(None))
+        .bearer_auth(token)
+        .json(&body(event))
+        .timeout(TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(CalendarEvent {
+        id: inserted.id,
+        link: inserted.html_link,
+        slot: event.slot,
+    })
+}
+
+/// Remove an event inserted before, once its date is no longer final
+pub async fn delete(event: &CalendarEvent) {
+    let credentials = match Credentials::from_env() {
+        Some(credentials) => credentials,
+        None => return,
+    };
+    let client = Client::new();
+    let deleted = async {
+        let token = credentials.access_token(&client).await?;
+        client
+            .delete(credentials.events_url(Some(&event.id)))
+            .bearer_auth(token)
+            .timeout(TIMEOUT)
+            .send()
+            .await?
+            .error_for_status()
+    }
+    .await;
+    if let Err(e) = deleted {
+        error!("Cannot delete Google Calendar event: {}", e);
+    }
+}
+
+/// The event as Google Calendar takes it; whole days become all-day events
+fn body(event: &Event) -> Value {
+    let (start, end) = match (event.slot.time, event.bounds()) {
+        (Some(_), Some((start, end))) => (
+            json!({ "dateTime": start.to_rfc3339() }),
+            json!({ "dateTime": end.to_rfc3339() }),
+        ),
+        // All-day events end on the following day
+        _ => (
+            json!({ "date": event.slot.date.to_string() }),
+            json!({ "date": event.slot.date.succ_opt().unwrap().to_string() }),
+        ),
+    };
+    json!({
+        "summary": event.name,
+        "description": event.description,
+        "location": event.location,
+        "start": start,
+        "end": end,
+    })
+}
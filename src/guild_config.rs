@@ -0,0 +1,730 @@
+use crate::announce;
+use crate::error::{self, Error};
+use crate::limits;
+use crate::locale::{DateFormat, Locale, Phrase, Text};
+use crate::scheduler;
+use crate::slot;
+use crate::storage;
+use crate::{DATA_DIR, DEFAULT_WEEKS, MAX_WEEKS};
+
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use serenity::builder::{
+    CreateActionRow, CreateAllowedMentions, CreateButton, CreateInputText,
+    CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, CreateModal,
+};
+use serenity::client::Context;
+use serenity::model::application::ComponentInteraction;
+use serenity::model::application::ModalInteraction;
+use serenity::model::application::{ActionRowComponent, ButtonStyle, InputTextStyle};
+use serenity::model::guild::{Guild, Member};
+use serenity::model::id::{GuildId, RoleId};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::{error, info};
+
+const GUILD_DIR: &str = "guilds";
+// Guilds joined longer ago than this were already onboarded before a restart
+const ONBOARDING_WINDOW_SECS: i64 = 5 * 60;
+/// Days a guild's data is kept after the bot is removed from it, unless it set its own retention
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct GuildConfig {
+    #[serde(default)]
+    pub timezone: Option<Tz>,
+    /// Roles allowed to create schedulers besides server managers; empty means everyone
+    #[serde(default)]
+    pub allowed_roles: HashSet<RoleId>,
+    /// Days to keep data for once the bot is removed from the guild; `None` keeps it for
+    /// [`DEFAULT_RETENTION_DAYS`]
+    #[serde(default)]
+    pub retention_days: Option<u32>,
+    /// When the bot was removed from the guild; its data is purged once retention runs out
+    #[serde(default)]
+    pub removed_at: Option<DateTime<Utc>>,
+    /// Local time range during which notifications are held back
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    /// Language for dates; Discord's server locale is used when unset
+    #[serde(default)]
+    pub locale: Option<Locale>,
+    /// How dates are written; each language's usual forms are used when unset
+    #[serde(default)]
+    pub date_format: Option<DateFormat>,
+    /// Weekdays offered when a scheduler doesn't say
+    #[serde(default)]
+    pub default_days: Option<HashSet<Weekday>>,
+    /// Weeks offered when a scheduler doesn't say
+    #[serde(default)]
+    pub default_weeks: Option<i64>,
+    /// Group for schedulers that don't name one
+    #[serde(default)]
+    pub default_group: Option<RoleId>,
+    /// Roles that may change these settings besides server managers
+    #[serde(default)]
+    pub admin_roles: HashSet<RoleId>,
+    /// Template for finalize announcements of schedulers that don't set their own
+    #[serde(default)]
+    pub announcement: Option<String>,
+    /// URL sent JSON when responses come in, dates are blacked out or a scheduler closes
+    #[serde(default)]
+    pub webhook: Option<String>,
+    /// Scheduler options saved by name with `/schedule template`
+    #[serde(default)]
+    pub templates: HashMap<String, Template>,
+}
+
+/// Options for `/schedule from-template`; whatever it leaves out falls back to the defaults
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Template {
+    /// Put in front of the description of each scheduler
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub days: Option<HashSet<Weekday>>,
+    #[serde(default)]
+    pub weeks: Option<i64>,
+    #[serde(default)]
+    pub group: Option<RoleId>,
+    #[serde(default)]
+    pub timezone: Option<Tz>,
+    #[serde(default)]
+    pub threshold: Option<usize>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// Parse a range like "23:00-08:00"
+    pub fn parse(s: &str) -> Option<Self> {
+        let (start, end) = s.split_once(['-', '–'])?;
+        let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M").ok();
+        Some(QuietHours {
+            start: parse(start)?,
+            end: parse(end)?,
+        })
+    }
+
+    fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            // The range wraps around midnight
+            time >= self.start || time < self.end
+        }
+    }
+
+    /// When the quiet hours containing `now` end, or `None` if `now` is outside of them
+    pub fn end_after<Z: TimeZone>(&self, now: DateTime<Z>) -> Option<DateTime<Utc>> {
+        let time = now.time();
+        if !self.contains(time) {
+            return None;
+        }
+        let mut date = now.naive_local().date();
+        if time >= self.end {
+            date = date.succ_opt().unwrap();
+        }
+        let end = now
+            .timezone()
+            .from_local_datetime(&date.and_time(self.end))
+            .earliest()
+            // The end falls into a DST gap; an hour later is past it
+            .unwrap_or_else(|| now + Duration::hours(1));
+        Some(end.with_timezone(&Utc))
+    }
+}
+
+impl std::fmt::Display for QuietHours {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Setting {
+    Timezone,
+    AllowedRoles,
+    Retention,
+    QuietHours,
+    Locale,
+    DateFormat,
+    DefaultDays,
+    DefaultWeeks,
+    DefaultGroup,
+    AdminRoles,
+    Announcement,
+    Webhook,
+}
+
+const SETTINGS: [Setting; 12] = [
+    Setting::Timezone,
+    Setting::AllowedRoles,
+    Setting::Retention,
+    Setting::QuietHours,
+    Setting::Locale,
+    Setting::DateFormat,
+    Setting::DefaultDays,
+    Setting::DefaultWeeks,
+    Setting::DefaultGroup,
+    Setting::AdminRoles,
+    Setting::Announcement,
+    Setting::Webhook,
+];
+
+impl Setting {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "setup_timezone" => Some(Setting::Timezone),
+            "setup_roles" => Some(Setting::AllowedRoles),
+            "setup_retention" => Some(Setting::Retention),
+            "setup_quiet" => Some(Setting::QuietHours),
+            "setup_locale" => Some(Setting::Locale),
+            "setup_date_format" => Some(Setting::DateFormat),
+            "setup_days" => Some(Setting::DefaultDays),
+            "setup_weeks" => Some(Setting::DefaultWeeks),
+            "setup_group" => Some(Setting::DefaultGroup),
+            "setup_admins" => Some(Setting::AdminRoles),
+            "setup_announcement" => Some(Setting::Announcement),
+            "setup_webhook" => Some(Setting::Webhook),
+            _ => None,
+        }
+    }
+
+    fn custom_id(&self) -> &'static str {
+        match self {
+            Setting::Timezone => "setup_timezone",
+            Setting::AllowedRoles => "setup_roles",
+            Setting::Retention => "setup_retention",
+            Setting::QuietHours => "setup_quiet",
+            Setting::Locale => "setup_locale",
+            Setting::DateFormat => "setup_date_format",
+            Setting::DefaultDays => "setup_days",
+            Setting::DefaultWeeks => "setup_weeks",
+            Setting::DefaultGroup => "setup_group",
+            Setting::AdminRoles => "setup_admins",
+            Setting::Announcement => "setup_announcement",
+            Setting::Webhook => "setup_webhook",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Setting::Timezone => "Timezone",
+            Setting::AllowedRoles => "Allowed roles",
+            Setting::Retention => "Data retention",
+            Setting::QuietHours => "Quiet hours",
+            Setting::Locale => "Language",
+            Setting::DateFormat => "Date format",
+            Setting::DefaultDays => "Default days",
+            Setting::DefaultWeeks => "Default weeks",
+            Setting::DefaultGroup => "Default group",
+            Setting::AdminRoles => "Admin roles",
+            Setting::Announcement => "Announcement",
+            Setting::Webhook => "Webhook",
+        }
+    }
+
+    fn prompt(&self) -> &'static str {
+        match self {
+            Setting::Timezone => "IANA timezone, e.g. Europe/Berlin",
+            Setting::AllowedRoles => "Role names or IDs, comma separated",
+            Setting::Retention => "Days to keep data after removal (blank = 30)",
+            Setting::QuietHours => "Local time range, e.g. 23:00-08:00 (blank = none)",
+            Setting::Locale => "Language code, e.g. de (blank = server locale)",
+            Setting::DateFormat => "Pattern like %a %d.%m. or discord (blank = default)",
+            Setting::DefaultDays => "Weekdays, e.g. Mon+Wed+Fri (blank = Sat+Sun)",
+            Setting::DefaultWeeks => "Number of weeks (blank = 10)",
+            Setting::DefaultGroup => "Role name or ID (blank = none)",
+            Setting::AdminRoles => "Role names or IDs, comma separated",
+            Setting::Announcement => "Text using {title} {when} (blank = default)",
+            Setting::Webhook => "https:// URL to send events to (blank = none)",
+        }
+    }
+}
+
+fn guild_dir() -> PathBuf {
+    let mut path: PathBuf = DATA_DIR.into();
+    path.push(GUILD_DIR);
+    path
+}
+
+fn file_path(guild: GuildId) -> PathBuf {
+    let mut path = guild_dir();
+    path.push(guild.get().to_string());
+    path.set_extension("json");
+    path
+}
+
+pub fn load_all() -> HashMap<GuildId, GuildConfig> {
+    let dir = guild_dir();
+    if !dir.is_dir() {
+        std::fs::create_dir(&dir).expect("Cannot create guild config dir");
+    }
+    let mut configs = HashMap::new();
+    for f in std::fs::read_dir(dir).expect("Cannot read guild config dir") {
+        let path = f.unwrap().path();
+        // Skips whatever a crash mid-write left behind
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let id: u64 = path
+            .file_stem()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse()
+            .expect("Cannot parse file name");
+        let file = File::open(&path).expect("Cannot open file");
+        let config = serde_json::from_reader(file).expect("Cannot parse guild config");
+        configs.insert(GuildId::new(id), config);
+    }
+    info!("{} guild configs loaded", configs.len());
+    configs
+}
+
+pub fn write_file(guild: GuildId, config: &GuildConfig) -> std::io::Result<()> {
+    storage::write_json(&file_path(guild), config)
+}
+
+pub fn delete_file(guild: GuildId) -> std::io::Result<()> {
+    std::fs::remove_file(file_path(guild))
+}
+
+/// Whether the bot joined `guild` just now, rather than it being loaded on startup
+pub fn is_new_guild(guild: &Guild) -> bool {
+    Utc::now().timestamp() - guild.joined_at.unix_timestamp() < ONBOARDING_WINDOW_SECS
+}
+
+pub fn setup_buttons(guild: GuildId) -> Vec<CreateActionRow> {
+    SETTINGS
+        .chunks(limits::BUTTONS_PER_ROW)
+        .map(|row| {
+            CreateActionRow::Buttons(
+                row.iter()
+                    .map(|setting| {
+                        CreateButton::new(format!("{} {}", setting.custom_id(), guild))
+                            .style(ButtonStyle::Secondary)
+                            .label(setting.label())
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// Post the setup message in the system channel, falling back to a DM to the guild owner
+pub async fn send_setup(ctx: &Context, guild: &Guild) {
+    let content = "Thanks for adding me! Use `/schedule` to create a scheduler. \
+        Server managers can configure defaults here, or later with `/schedconfig`:";
+    if let Some(channel) = guild.system_channel_id {
+        let sent = channel
+            .send_message(
+                ctx,
+                CreateMessage::new()
+                    .content(content)
+                    .components(setup_buttons(guild.id)),
+            )
+            .await;
+        match sent {
+            Ok(_) => return,
+            Err(e) => error!("Cannot post setup message in {}: {}", guild.id, e),
+        }
+    }
+    let dm = match guild.owner_id.create_dm_channel(ctx).await {
+        Ok(dm) => dm,
+        Err(e) => {
+            error!("Cannot create DM channel: {}", e);
+            return;
+        }
+    };
+    dm.send_message(
+        ctx,
+        CreateMessage::new()
+            .content(format!("{} ({})", content, guild.name))
+            .components(setup_buttons(guild.id)),
+    )
+    .await
+    .map_err(|e| error!("Cannot send setup DM: {}", e))
+    .ok();
+}
+
+/// Only server managers and the admin roles may change settings; in DMs the recipient is the
+/// guild owner
+pub fn can_configure(member: Option<&Member>, admin_roles: &HashSet<RoleId>) -> bool {
+    match member {
+        Some(member) => {
+            member
+                .permissions
+                .is_some_and(|p| p.manage_guild() || p.administrator())
+                || member.roles.iter().any(|r| admin_roles.contains(r))
+        }
+        None => true,
+    }
+}
+
+pub async fn prompt_setting(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    setting: Setting,
+    guild: GuildId,
+    admin_roles: &HashSet<RoleId>,
+) -> error::Result {
+    if !can_configure(component.member.as_ref(), admin_roles) {
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content("Only server managers can change settings"),
+                ),
+            )
+            .await?;
+        return Ok(());
+    }
+    component
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Modal(
+                CreateModal::new(
+                    format!("{} {}", setting.custom_id(), guild),
+                    setting.label(),
+                )
+                .components(vec![CreateActionRow::InputText(
+                    CreateInputText::new(InputTextStyle::Short, setting.prompt(), "value")
+                        .required(false),
+                )]),
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+pub fn modal_value(modal: &ModalInteraction, id: &str) -> Option<String> {
+    modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|component| match component {
+            ActionRowComponent::InputText(input) if input.custom_id == id => {
+                Some(input.value.as_deref().unwrap_or_default().trim().to_owned())
+            }
+            _ => None,
+        })
+}
+
+/// The roles named in a comma separated list of names, IDs or mentions
+async fn find_roles(ctx: &Context, guild: GuildId, value: &str) -> Result<HashSet<RoleId>, String> {
+    let roles = guild
+        .roles(ctx)
+        .await
+        .map_err(|e| format!("Cannot fetch roles: {}", e))?;
+    let mut found = HashSet::new();
+    for name in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let id = name.trim_start_matches("<@&").trim_end_matches('>');
+        let role = roles
+            .values()
+            .find(|r| r.id.to_string() == id || r.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("Unknown role: {}", name))?;
+        found.insert(role.id);
+    }
+    Ok(found)
+}
+
+fn mention_roles(roles: &HashSet<RoleId>) -> String {
+    roles
+        .iter()
+        .map(|r| format!("<@&{}>", r))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn format_days(days: &HashSet<Weekday>) -> String {
+    let mut days: Vec<&Weekday> = days.iter().collect();
+    days.sort_by_key(|d| d.num_days_from_monday());
+    days.iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+impl GuildConfig {
+    /// Whether `member` may create schedulers: anyone unless roles are configured, and server
+    /// managers regardless
+    pub fn can_create(&self, member: Option<&Member>) -> bool {
+        self.allowed_roles.is_empty() || can_configure(member, &self.allowed_roles)
+    }
+
+    /// Why a member can't create schedulers
+    pub fn create_denied(&self) -> Phrase {
+        Phrase::new(Text::CreateDenied, &[&mention_roles(&self.allowed_roles)])
+    }
+
+    /// The current settings, one per line
+    pub fn summary(&self) -> String {
+        let or = |value: Option<String>, default: &str| value.unwrap_or_else(|| default.to_owned());
+        let roles = |roles: &HashSet<RoleId>, default: &str| {
+            or((!roles.is_empty()).then(|| mention_roles(roles)), default)
+        };
+        [
+            format!(
+                "Timezone: {}",
+                or(self.timezone.map(|tz| tz.name().to_owned()), "UTC")
+            ),
+            format!(
+                "Language: {}",
+                or(self.locale.map(|l| l.name().to_owned()), "server locale")
+            ),
+            format!(
+                "Date format: {}",
+                or(self.date_format.as_ref().map(|f| f.to_string()), "default")
+            ),
+            format!(
+                "Default days: {}",
+                or(
+                    self.default_days.as_ref().map(format_days),
+                    &format_days(&scheduler::default_days())
+                )
+            ),
+            format!(
+                "Default weeks: {}",
+                self.default_weeks.unwrap_or(DEFAULT_WEEKS)
+            ),
+            format!(
+                "Default group: {}",
+                or(self.default_group.map(|r| format!("<@&{}>", r)), "none")
+            ),
+            format!("Allowed roles: {}", roles(&self.allowed_roles, "everyone")),
+            format!(
+                "Admin roles: {}",
+                roles(&self.admin_roles, "server managers only")
+            ),
+            format!(
+                "Data retention: {}",
+                or(
+                    self.retention_days.map(|d| format!("{} days", d)),
+                    &format!("{} days", DEFAULT_RETENTION_DAYS)
+                )
+            ),
+            format!(
+                "Quiet hours: {}",
+                or(self.quiet_hours.map(|q| q.to_string()), "none")
+            ),
+            format!(
+                "Announcement: {}",
+                or(self.announcement.clone(), announce::DEFAULT_TEMPLATE)
+            ),
+            // The URL itself is as good as a password for whoever receives it
+            format!(
+                "Webhook: {}",
+                if self.webhook.is_some() {
+                    "set"
+                } else {
+                    "none"
+                }
+            ),
+        ]
+        .join("\n")
+    }
+
+    /// Apply a submitted settings modal, returning a description of the change or an error
+    async fn apply(
+        &mut self,
+        ctx: &Context,
+        guild: GuildId,
+        setting: Setting,
+        value: &str,
+    ) -> Result<String, String> {
+        match setting {
+            Setting::Timezone => {
+                if value.is_empty() {
+                    self.timezone = None;
+                    return Ok("Timezone cleared".to_owned());
+                }
+                let tz = Tz::from_str(value).map_err(|_| format!("Unknown timezone: {}", value))?;
+                self.timezone = Some(tz);
+                Ok(format!("Timezone set to {}", tz.name()))
+            }
+            Setting::AllowedRoles => {
+                self.allowed_roles = find_roles(ctx, guild, value).await?;
+                if self.allowed_roles.is_empty() {
+                    Ok("Everyone may create schedulers".to_owned())
+                } else {
+                    Ok(format!(
+                        "Schedulers may be created by {}",
+                        mention_roles(&self.allowed_roles)
+                    ))
+                }
+            }
+            Setting::Retention => {
+                if value.is_empty() {
+                    self.retention_days = None;
+                    return Ok(format!(
+                        "Data will be kept for {} days after the bot is removed",
+                        DEFAULT_RETENTION_DAYS
+                    ));
+                }
+                let days = value
+                    .parse()
+                    .map_err(|_| format!("Not a number of days: {}", value))?;
+                self.retention_days = Some(days);
+                Ok(format!(
+                    "Data will be kept for {} days after the bot is removed",
+                    days
+                ))
+            }
+            Setting::QuietHours => {
+                if value.is_empty() {
+                    self.quiet_hours = None;
+                    return Ok("Quiet hours cleared".to_owned());
+                }
+                let quiet_hours = QuietHours::parse(value)
+                    .ok_or_else(|| format!("Not a time range like 23:00-08:00: {}", value))?;
+                self.quiet_hours = Some(quiet_hours);
+                Ok(format!(
+                    "Notifications will be held back between {}",
+                    quiet_hours
+                ))
+            }
+            Setting::Locale => {
+                if value.is_empty() {
+                    self.locale = None;
+                    return Ok("Dates will follow the server locale".to_owned());
+                }
+                let locale = Locale::parse(value)
+                    .ok_or_else(|| format!("Unsupported language: {}", value))?;
+                self.locale = Some(locale);
+                Ok(format!("Dates will be shown in {}", locale.name()))
+            }
+            Setting::DateFormat => {
+                if value.is_empty() {
+                    self.date_format = None;
+                    return Ok("Dates will be written the usual way".to_owned());
+                }
+                let format = DateFormat::parse(value)
+                    .ok_or_else(|| format!("Not a date pattern: {}", value))?;
+                let done = format!("Dates will be written as {}", format);
+                self.date_format = Some(format);
+                Ok(done)
+            }
+            Setting::DefaultDays => {
+                if value.is_empty() {
+                    self.default_days = None;
+                    return Ok(format!(
+                        "Schedulers will offer {} by default",
+                        format_days(&scheduler::default_days())
+                    ));
+                }
+                let days = slot::parse_days(value)
+                    .ok_or_else(|| format!("Not weekdays like Mon+Wed+Fri: {}", value))?;
+                let text = format!("Schedulers will offer {} by default", format_days(&days));
+                self.default_days = Some(days);
+                Ok(text)
+            }
+            Setting::DefaultWeeks => {
+                if value.is_empty() {
+                    self.default_weeks = None;
+                    return Ok(format!(
+                        "Schedulers will run for {} weeks by default",
+                        DEFAULT_WEEKS
+                    ));
+                }
+                let weeks = value
+                    .parse()
+                    .ok()
+                    .filter(|w| (1..=MAX_WEEKS as i64).contains(w))
+                    .ok_or_else(|| {
+                        format!("Not a number of weeks up to {}: {}", MAX_WEEKS, value)
+                    })?;
+                self.default_weeks = Some(weeks);
+                Ok(format!(
+                    "Schedulers will run for {} weeks by default",
+                    weeks
+                ))
+            }
+            Setting::DefaultGroup => {
+                let roles = find_roles(ctx, guild, value).await?;
+                if roles.len() > 1 {
+                    return Err("Only one role can be the default group".to_owned());
+                }
+                self.default_group = roles.into_iter().next();
+                Ok(match self.default_group {
+                    Some(role) => format!("Schedulers will be for <@&{}> by default", role),
+                    None => "Schedulers will be open to everyone by default".to_owned(),
+                })
+            }
+            Setting::AdminRoles => {
+                self.admin_roles = find_roles(ctx, guild, value).await?;
+                if self.admin_roles.is_empty() {
+                    Ok("Only server managers may change settings".to_owned())
+                } else {
+                    Ok(format!(
+                        "Settings may also be changed by {}",
+                        mention_roles(&self.admin_roles)
+                    ))
+                }
+            }
+            Setting::Announcement => {
+                if value.is_empty() {
+                    self.announcement = None;
+                    return Ok("Finalized dates will be announced with the default text".to_owned());
+                }
+                announce::validate(value).map_err(|e| e.to_string())?;
+                self.announcement = Some(value.to_owned());
+                Ok(format!("Finalized dates will be announced as: {}", value))
+            }
+            Setting::Webhook => {
+                if value.is_empty() {
+                    self.webhook = None;
+                    return Ok("Events will no longer be sent to a webhook".to_owned());
+                }
+                if !value.starts_with("https://") {
+                    return Err(format!("Not an https:// URL: {}", value));
+                }
+                self.webhook = Some(value.to_owned());
+                Ok("Responses, blackouts and closing will be sent to the webhook".to_owned())
+            }
+        }
+    }
+
+    pub async fn handle_modal(
+        &mut self,
+        ctx: &Context,
+        modal: &ModalInteraction,
+        setting: Setting,
+        guild: GuildId,
+    ) -> error::Result {
+        let value = modal_value(modal, "value").unwrap_or_default();
+        let content = self
+            .apply(ctx, guild, setting, &value)
+            .await
+            .map_err(Error::owner)?;
+        write_file(guild, self)?;
+        modal
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(content)
+                        .allowed_mentions(CreateAllowedMentions::new()),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,576 @@
+//! In-memory stand-ins for Discord, so flows can be tested without a token. A context from
+//! [`context`] records outgoing messages, notifications and events instead of sending them, and
+//! talks to a local fake of Discord's gateway and REST API for everything else, so interactions
+//! built with [`Recorder::command`] and [`Recorder::click`] can go through the whole handler.
+
+// Most of this is only used by tests; the rest is checked from the code that talks to Discord
+#![cfg_attr(not(test), allow(dead_code))]
+
+use crate::guild_config::GuildConfig;
+use crate::message_shim::MessageShim;
+use crate::notify::{Notification, Notifier};
+use crate::webhook::Payload;
+
+use axum::body::Bytes;
+use axum::handler::Handler;
+use axum::http::{Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router};
+use futures::{SinkExt, StreamExt};
+use serde_json::json;
+use serenity::cache::Cache;
+use serenity::client::Context;
+use serenity::gateway::{
+    Shard, ShardManager, ShardManagerOptions, ShardMessenger, ShardRunner, ShardRunnerOptions,
+};
+use serenity::http::{Http, HttpBuilder};
+use serenity::json::Value;
+use serenity::model::application::Interaction;
+use serenity::model::gateway::{GatewayIntents, ShardInfo};
+use serenity::model::id::{
+    ApplicationId, ChannelId, GuildId, MessageId, ScheduledEventId, ShardId,
+};
+use serenity::prelude::{RwLock, TypeMap, TypeMapKey};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_tungstenite::tungstenite::Message as Frame;
+
+/// The channel every harness message and interaction is in
+pub const CHANNEL: ChannelId = ChannelId::new(1);
+const APPLICATION: ApplicationId = ApplicationId::new(1);
+/// How long a click waits for something to pick it up before it is sent again
+const CLICK_RETRY: Duration = Duration::from_millis(200);
+/// How long to wait for the code under test before giving up on it
+const PATIENCE: Duration = Duration::from_secs(10);
+
+// Tests only look at some of what is recorded
+#[allow(dead_code)]
+#[derive(Clone)]
+pub enum Call {
+    /// The JSON body a message edit would have sent
+    EditMessage {
+        message: MessageId,
+        body: Value,
+    },
+    Notify(Notification),
+    CreateEvent {
+        guild: GuildId,
+        name: String,
+    },
+    EditEvent {
+        guild: GuildId,
+        id: ScheduledEventId,
+    },
+    DeleteEvent {
+        guild: GuildId,
+        id: ScheduledEventId,
+    },
+    Webhook {
+        url: String,
+        payload: Payload,
+    },
+    /// A request to the fake REST API, by its path below the API version
+    Http {
+        method: Method,
+        path: String,
+        body: Value,
+    },
+}
+
+/// Everything sent through a harness context, in order
+#[derive(Clone, Default)]
+pub struct Recorder {
+    calls: Arc<Mutex<Vec<Call>>>,
+    interactions: Arc<Mutex<Interactions>>,
+}
+
+/// What the fake REST API knows about the interactions of a recorder
+#[derive(Default)]
+struct Interactions {
+    /// Interactions that were answered, which Discord only allows once
+    acknowledged: HashSet<String>,
+    /// The latest message sent in reply to each interaction, by token
+    replies: HashMap<String, MessageId>,
+    /// The message each component interaction came from, by token
+    sources: HashMap<String, MessageId>,
+    /// Interactions whose handler fetched their reply, by token
+    fetched: HashSet<String>,
+}
+
+impl TypeMapKey for Recorder {
+    type Value = Recorder;
+}
+
+impl Recorder {
+    /// The recorder of `ctx`, if it is a harness context
+    pub async fn get(ctx: &Context) -> Option<Recorder> {
+        ctx.data.read().await.get::<Recorder>().cloned()
+    }
+
+    pub fn record(&self, call: Call) {
+        self.calls.lock().unwrap().push(call);
+    }
+
+    /// The body of the most recent edit to `message`
+    pub fn last_edit(&self, message: MessageId) -> Option<Value> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find_map(|call| match call {
+                Call::EditMessage { message: m, body } if *m == message => Some(body.clone()),
+                _ => None,
+            })
+    }
+
+    /// How many times `message` was edited
+    pub fn edit_count(&self, message: MessageId) -> usize {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|call| matches!(call, Call::EditMessage { message: m, .. } if *m == message))
+            .count()
+    }
+
+    pub fn notifications(&self) -> Vec<Notification> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|call| match call {
+                Call::Notify(notification) => Some(notification.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The content of every reply sent for the interaction with `token`, in order
+    pub fn replies(&self, token: &str) -> Vec<String> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|call| match call {
+                Call::Http { path, body, .. } if path.contains(token) => {
+                    let content = body.get("data").unwrap_or(body).get("content");
+                    content.and_then(Value::as_str).map(str::to_owned)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// A `/name` command from `user`, with `options` as Discord sends them
+    pub fn command(&self, name: &str, options: Value, user: u64) -> Interaction {
+        let id = next_id();
+        interaction(json!({
+            "id": id.to_string(),
+            "application_id": APPLICATION.to_string(),
+            "type": 2,
+            "data": {"id": "1", "name": name, "type": 1, "options": options},
+            "channel_id": CHANNEL.to_string(),
+            "user": user_json(user),
+            "token": format!("token{}", id),
+            "version": 1,
+            "locale": "en-US",
+            "entitlements": [],
+            "attachment_size_limit": 8388608,
+        }))
+    }
+
+    /// A click by `user` on the button `custom_id` of `message`
+    pub fn click(&self, message: MessageId, custom_id: &str, user: u64) -> Interaction {
+        interaction(self.click_json(message, None, custom_id, user))
+    }
+
+    /// A click by `user` on the button `custom_id` of the reply to the interaction with `token`
+    pub fn click_reply(&self, token: &str, custom_id: &str, user: u64) -> Interaction {
+        interaction(self.reply_click_json(token, custom_id, user))
+    }
+
+    /// Click a button of the reply to the interaction with `token` while its handler waits for
+    /// clicks. Those arrive through the gateway rather than the handler, so the click is sent
+    /// once the reply was fetched, and again until it is answered in case it came too early.
+    pub async fn click_waiting(&self, token: &str, custom_id: &str, user: u64) {
+        self.wait_for(|i| i.fetched.contains(token)).await;
+        let click = self.reply_click_json(token, custom_id, user);
+        let id = click["id"].as_str().unwrap().to_owned();
+        let event = json!({"op": 0, "s": next_id(), "t": "INTERACTION_CREATE", "d": click});
+        let deadline = tokio::time::Instant::now() + PATIENCE;
+        while tokio::time::Instant::now() < deadline {
+            discord().events.send(event.to_string()).unwrap();
+            tokio::time::sleep(CLICK_RETRY).await;
+            if self.interactions.lock().unwrap().acknowledged.contains(&id) {
+                return;
+            }
+        }
+        panic!("Nothing took the click on {}", custom_id);
+    }
+
+    async fn wait_for(&self, done: impl Fn(&Interactions) -> bool) {
+        let deadline = tokio::time::Instant::now() + PATIENCE;
+        while !done(&self.interactions.lock().unwrap()) {
+            assert!(tokio::time::Instant::now() < deadline, "Gave up waiting");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    fn reply_click_json(&self, token: &str, custom_id: &str, user: u64) -> Value {
+        let (reply, source) = {
+            let interactions = self.interactions.lock().unwrap();
+            let reply = *interactions.replies.get(token).expect("Nothing replied");
+            (reply, interactions.sources.get(token).copied())
+        };
+        self.click_json(reply, source, custom_id, user)
+    }
+
+    fn click_json(
+        &self,
+        message: MessageId,
+        reference: Option<MessageId>,
+        custom_id: &str,
+        user: u64,
+    ) -> Value {
+        let id = next_id();
+        let token = format!("token{}", id);
+        let mut message = message_json(message, CHANNEL, &Value::Null);
+        if let Some(reference) = reference {
+            message["message_reference"] =
+                json!({"message_id": reference.to_string(), "channel_id": CHANNEL.to_string()});
+        }
+        let source = MessageId::new(message["id"].as_str().unwrap().parse().unwrap());
+        self.interactions
+            .lock()
+            .unwrap()
+            .sources
+            .insert(token.clone(), source);
+        json!({
+            "id": id.to_string(),
+            "application_id": APPLICATION.to_string(),
+            "type": 3,
+            "data": {"custom_id": custom_id, "component_type": 2},
+            "channel_id": CHANNEL.to_string(),
+            "user": user_json(user),
+            "token": token,
+            "version": 1,
+            "message": message,
+            "locale": "en-US",
+            "entitlements": [],
+            "attachment_size_limit": 8388608,
+        })
+    }
+
+    /// What was posted to webhooks, as JSON
+    pub fn webhooks(&self) -> Vec<Value> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|call| match call {
+                Call::Webhook { payload, .. } => serde_json::to_value(payload).ok(),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// A context whose Discord calls go to `recorder`. Nothing reaches the network as long as the
+/// code under test only uses the recorded paths.
+pub fn context(recorder: &Recorder) -> Context {
+    guild_context(recorder, HashMap::new())
+}
+
+/// A context like [`context`]'s, for guilds set up with `configs`
+pub fn guild_context(recorder: &Recorder, configs: HashMap<GuildId, GuildConfig>) -> Context {
+    let mut data = TypeMap::new();
+    data.insert::<Recorder>(recorder.clone());
+    data.insert::<Notifier>(Notifier::in_memory(Arc::new(RwLock::new(configs))));
+    let discord = discord();
+    let index = {
+        let mut recorders = discord.recorders.lock().unwrap();
+        recorders.push(recorder.clone());
+        recorders.len() - 1
+    };
+    let http = HttpBuilder::new("")
+        .proxy(format!("http://{}/{}", discord.api, index))
+        .application_id(APPLICATION)
+        .ratelimiter_disabled(true)
+        .build();
+    Context {
+        data: Arc::new(RwLock::new(data)),
+        shard: discord.messenger.clone(),
+        shard_id: ShardId(0),
+        http: Arc::new(http),
+        cache: Arc::new(Cache::new()),
+    }
+}
+
+fn next_id() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1_000_000);
+    NEXT.fetch_add(1, Ordering::Relaxed)
+}
+
+fn interaction(json: Value) -> Interaction {
+    serde_json::from_value(json).expect("Cannot build interaction")
+}
+
+fn user_json(id: u64) -> Value {
+    json!({
+        "id": id.to_string(),
+        "username": format!("user{}", id),
+        "discriminator": "0000",
+        "avatar": null,
+    })
+}
+
+/// A message as the REST API returns it, with the content of `body` if it sets any
+fn message_json(id: MessageId, channel: ChannelId, body: &Value) -> Value {
+    json!({
+        "id": id.to_string(),
+        "channel_id": channel.to_string(),
+        "author": user_json(APPLICATION.get()),
+        "content": body.get("content").cloned().unwrap_or(json!("")),
+        "timestamp": "2024-01-01T00:00:00Z",
+        "edited_timestamp": null,
+        "tts": false,
+        "mention_everyone": false,
+        "mentions": [],
+        "mention_roles": [],
+        "attachments": [],
+        "embeds": [],
+        "pinned": false,
+        "type": 0,
+    })
+}
+
+/// The fake Discord every harness context talks to
+struct Discord {
+    messenger: ShardMessenger,
+    /// Gateway events for the shard, as JSON
+    events: UnboundedSender<String>,
+    api: SocketAddr,
+    /// The recorders of harness contexts, which REST paths start with the index of
+    recorders: Mutex<Vec<Recorder>>,
+}
+
+/// Serenity only hands out messengers for shard runners, and a shard only exists once it has
+/// connected, so this runs one against a local gateway, which only ever says hello and passes on
+/// the events tests send. It and the REST API get a runtime of their own, as each test has its
+/// own runtime and this outlives them.
+fn discord() -> &'static Discord {
+    static DISCORD: OnceLock<Discord> = OnceLock::new();
+    DISCORD.get_or_init(|| {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Cannot start harness runtime");
+            runtime.block_on(async move {
+                let (events, receiver) = tokio::sync::mpsc::unbounded_channel();
+                let mut runner = shard(receiver).await;
+                let api = serve_api();
+                tx.send(Discord {
+                    messenger: ShardMessenger::new(&runner),
+                    events,
+                    api,
+                    recorders: Mutex::default(),
+                })
+                .unwrap_or_else(|_| panic!("Cannot start harness"));
+                runner.run().await.expect("Harness shard stopped");
+            });
+        });
+        rx.recv().expect("Cannot connect harness shard")
+    })
+}
+
+async fn shard(mut events: tokio::sync::mpsc::UnboundedReceiver<String>) -> ShardRunner {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let ws_url = Arc::new(serenity::prelude::Mutex::new(format!(
+        "ws://{}",
+        listener.local_addr().unwrap()
+    )));
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let connection = tokio_tungstenite::accept_async(stream).await.unwrap();
+        let (mut sink, mut stream) = connection.split();
+        // Heartbeats are due once an hour, so a test never sees one go unanswered
+        let hello = json!({"op": 10, "d": {"heartbeat_interval": 3_600_000}});
+        sink.send(Frame::Text(hello.to_string())).await.unwrap();
+        loop {
+            tokio::select! {
+                Some(event) = events.recv() => {
+                    sink.send(Frame::Text(event)).await.unwrap();
+                }
+                frame = stream.next() => match frame {
+                    Some(Ok(Frame::Text(text))) if text.contains("\"op\":1") => {
+                        let ack = json!({"op": 11});
+                        sink.send(Frame::Text(ack.to_string())).await.unwrap();
+                    }
+                    Some(Ok(_)) => (),
+                    _ => break,
+                },
+            }
+        }
+    });
+    let data = Arc::new(RwLock::new(TypeMap::new()));
+    let http = Arc::new(Http::new(""));
+    let cache = Arc::new(Cache::new());
+    let info = ShardInfo {
+        id: ShardId(0),
+        total: 1,
+    };
+    let shard = Shard::new(ws_url.clone(), "", info, GatewayIntents::empty(), None)
+        .await
+        .expect("Cannot connect to harness gateway");
+    let (manager, _) = ShardManager::new(ShardManagerOptions {
+        data: data.clone(),
+        event_handlers: vec![],
+        raw_event_handlers: vec![],
+        shard_index: 0,
+        shard_init: 0,
+        shard_total: 1,
+        ws_url,
+        cache: cache.clone(),
+        http: http.clone(),
+        intents: GatewayIntents::empty(),
+        presence: None,
+    });
+    ShardRunner::new(ShardRunnerOptions {
+        data,
+        event_handlers: vec![],
+        raw_event_handlers: vec![],
+        manager,
+        shard,
+        cache,
+        http,
+    })
+}
+
+/// Serve the fake REST API on the current runtime, returning where
+fn serve_api() -> SocketAddr {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = axum::Server::from_tcp(listener).unwrap().serve(
+        Router::new()
+            .fallback(api.into_service())
+            .into_make_service(),
+    );
+    tokio::spawn(server);
+    address
+}
+
+/// Record a request and answer it the way Discord would, as far as the code under test cares.
+/// Messages get fresh ids, except that an interaction's reply keeps the one it was first given.
+async fn api(method: Method, uri: Uri, body: Bytes) -> Response {
+    let (index, path) = uri
+        .path()
+        .trim_start_matches('/')
+        .split_once("/api/v10/")
+        .expect("Unexpected path");
+    let recorder = discord().recorders.lock().unwrap()[index.parse::<usize>().unwrap()].clone();
+    let body: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+    recorder.record(Call::Http {
+        method: method.clone(),
+        path: path.to_owned(),
+        body: body.clone(),
+    });
+    let mut interactions = recorder.interactions.lock().unwrap();
+    let segments: Vec<&str> = path.split('/').collect();
+    match (method, segments.as_slice()) {
+        (Method::POST, ["interactions", id, token, "callback"]) => {
+            if interactions.acknowledged.insert(id.to_string()) {
+                // Messages and deferrals leave a reply to click on
+                if matches!(body["type"].as_u64(), Some(4 | 5)) {
+                    interactions
+                        .replies
+                        .insert(token.to_string(), MessageId::new(next_id()));
+                }
+                StatusCode::NO_CONTENT.into_response()
+            } else {
+                let error =
+                    json!({"code": 40060, "message": "Interaction has already been acknowledged."});
+                (StatusCode::BAD_REQUEST, Json(error)).into_response()
+            }
+        }
+        (Method::DELETE, _) | (Method::PUT, _) => StatusCode::NO_CONTENT.into_response(),
+        (_, ["webhooks", _, token, "messages", "@original"]) => {
+            interactions.fetched.insert(token.to_string());
+            let id = *interactions
+                .replies
+                .entry(token.to_string())
+                .or_insert_with(|| MessageId::new(next_id()));
+            Json(message_json(id, CHANNEL, &body)).into_response()
+        }
+        (Method::POST, ["webhooks", _, token]) => {
+            let id = MessageId::new(next_id());
+            interactions.replies.insert(token.to_string(), id);
+            Json(message_json(id, CHANNEL, &body)).into_response()
+        }
+        (Method::PATCH, ["webhooks", .., id]) => {
+            let id = MessageId::new(id.parse().unwrap());
+            Json(message_json(id, CHANNEL, &body)).into_response()
+        }
+        (Method::POST, ["channels", channel, "messages"]) => {
+            let channel = ChannelId::new(channel.parse().unwrap());
+            Json(message_json(MessageId::new(next_id()), channel, &body)).into_response()
+        }
+        (Method::PATCH, ["channels", channel, "messages", id]) => {
+            let channel = ChannelId::new(channel.parse().unwrap());
+            let id = MessageId::new(id.parse().unwrap());
+            Json(message_json(id, channel, &body)).into_response()
+        }
+        _ => {
+            let error = json!({"code": 10000, "message": "Unknown"});
+            (StatusCode::NOT_FOUND, Json(error)).into_response()
+        }
+    }
+}
+
+/// A scheduler message that was never posted
+pub fn message(id: u64) -> MessageShim {
+    MessageShim::new(CHANNEL, MessageId::new(id))
+}
+
+/// Every string in `value`, joined, for checking what a rendered message says
+pub fn text(value: &Value) -> String {
+    let mut strings = vec![];
+    collect_strings(value, &mut strings);
+    strings.join("\n")
+}
+
+fn collect_strings(value: &Value, strings: &mut Vec<String>) {
+    match value {
+        Value::String(s) => strings.push(s.clone()),
+        Value::Array(values) => values.iter().for_each(|v| collect_strings(v, strings)),
+        Value::Object(map) => map.values().for_each(|v| collect_strings(v, strings)),
+        _ => (),
+    }
+}
+
+/// The custom ids of the buttons in a rendered message
+pub fn buttons(value: &Value) -> Vec<String> {
+    let mut ids = vec![];
+    collect_ids(value, &mut ids);
+    ids
+}
+
+fn collect_ids(value: &Value, ids: &mut Vec<String>) {
+    match value {
+        Value::Array(values) => values.iter().for_each(|v| collect_ids(v, ids)),
+        Value::Object(map) => {
+            if let Some(Value::String(id)) = map.get("custom_id") {
+                ids.push(id.clone());
+            }
+            map.values().for_each(|v| collect_ids(v, ids));
+        }
+        _ => (),
+    }
+}
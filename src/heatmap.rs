@@ -0,0 +1,94 @@
+//! A PNG grid of who can make which date, easier to scan than long lists of results. Text is
+//! drawn with a system font found through fontconfig, so hosts need fontconfig and a sans-serif
+//! font installed, e.g. `fontconfig` and `fonts-dejavu-core` on Debian; without one the heatmap
+//! is left out.
+
+use plotters::prelude::*;
+use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+use image::codecs::png::PngEncoder;
+use image::{ColorType, ImageEncoder};
+use tracing::error;
+
+pub const FILENAME: &str = "availability.png";
+
+const CELL: i32 = 24;
+/// Room for responder names on the left and dates along the top
+const NAMES_WIDTH: i32 = 200;
+const DATES_HEIGHT: i32 = 110;
+const FONT: (&str, u32) = ("sans-serif", 14);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    Available,
+    Maybe,
+    Unavailable,
+    BlackedOut,
+}
+
+impl Cell {
+    fn color(self) -> RGBColor {
+        match self {
+            Cell::Available => RGBColor(67, 160, 71),
+            Cell::Maybe => RGBColor(253, 216, 53),
+            Cell::Unavailable => RGBColor(236, 239, 241),
+            Cell::BlackedOut => RGBColor(96, 96, 96),
+        }
+    }
+}
+
+/// Draw a row per responder and a column per date; `None` if there is nothing to draw or it
+/// fails
+pub fn render(dates: &[String], rows: &[(String, Vec<Cell>)]) -> Option<Vec<u8>> {
+    if dates.is_empty() || rows.is_empty() {
+        return None;
+    }
+    let width = (NAMES_WIDTH + CELL * dates.len() as i32) as u32;
+    let height = (DATES_HEIGHT + CELL * rows.len() as i32) as u32;
+    let mut pixels = vec![0; (width * height * 3) as usize];
+    if let Err(e) = draw(&mut pixels, (width, height), dates, rows) {
+        error!("Cannot draw heatmap: {}", e);
+        return None;
+    }
+    let mut png = Vec::new();
+    PngEncoder::new(&mut png)
+        .write_image(&pixels, width, height, ColorType::Rgb8)
+        .ok()?;
+    Some(png)
+}
+
+fn draw(
+    pixels: &mut [u8],
+    size: (u32, u32),
+    dates: &[String],
+    rows: &[(String, Vec<Cell>)],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = BitMapBackend::with_buffer(pixels, size).into_drawing_area();
+    root.fill(&WHITE)?;
+    let date_style = FONT
+        .into_font()
+        .transform(FontTransform::Rotate270)
+        .color(&BLACK)
+        .pos(Pos::new(HPos::Left, VPos::Center));
+    for (i, date) in dates.iter().enumerate() {
+        let x = NAMES_WIDTH + CELL * i as i32 + CELL / 2;
+        root.draw_text(date, &date_style, (x, DATES_HEIGHT - 4))?;
+    }
+    let name_style = FONT
+        .into_font()
+        .color(&BLACK)
+        .pos(Pos::new(HPos::Right, VPos::Center));
+    for (row, (name, cells)) in rows.iter().enumerate() {
+        let y = DATES_HEIGHT + CELL * row as i32;
+        root.draw_text(name, &name_style, (NAMES_WIDTH - 6, y + CELL / 2))?;
+        for (column, cell) in cells.iter().enumerate() {
+            let x = NAMES_WIDTH + CELL * column as i32;
+            root.draw(&Rectangle::new(
+                [(x + 1, y + 1), (x + CELL - 1, y + CELL - 1)],
+                cell.color().filled(),
+            ))?;
+        }
+    }
+    root.present()?;
+    Ok(())
+}
@@ -0,0 +1,49 @@
+//! Turnout statistics over past schedulers
+
+use crate::slot::Slot;
+
+use chrono::{Datelike, NaiveTime, Weekday};
+use std::collections::HashMap;
+
+#[derive(Default)]
+struct Turnout {
+    dates: usize,
+    available: usize,
+    respondents: usize,
+}
+
+impl Turnout {
+    fn ratio(&self) -> f64 {
+        self.available as f64 / self.respondents as f64
+    }
+}
+
+/// Weekdays, at the times they were offered at if any, ranked by the share of respondents who
+/// were available on them, given past `(slot, available, respondents)` turnouts
+pub fn suggest_days(turnouts: impl IntoIterator<Item = (Slot, usize, usize)>) -> Vec<String> {
+    let mut stats: HashMap<(Weekday, Option<NaiveTime>), Turnout> = HashMap::new();
+    for (slot, available, respondents) in turnouts {
+        let turnout = stats.entry((slot.date.weekday(), slot.time)).or_default();
+        turnout.dates += 1;
+        turnout.available += available;
+        turnout.respondents += respondents;
+    }
+    let mut stats: Vec<((Weekday, Option<NaiveTime>), Turnout)> = stats.into_iter().collect();
+    stats.sort_by(|(_, a), (_, b)| b.ratio().total_cmp(&a.ratio()));
+    stats
+        .into_iter()
+        .map(|((weekday, time), turnout)| {
+            let when = match time {
+                Some(time) => format!("{} {}", weekday, time.format("%H:%M")),
+                None => weekday.to_string(),
+            };
+            format!(
+                "`{}:` {:.0}% turnout, {:.1} available on average ({} dates)",
+                when,
+                turnout.ratio() * 100.0,
+                turnout.available as f64 / turnout.dates as f64,
+                turnout.dates
+            )
+        })
+        .collect()
+}
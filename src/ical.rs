@@ -0,0 +1,83 @@
+//! iCalendar files of a scheduler's dates, for importing into calendar apps
+
+use crate::events::Event;
+
+use chrono::{DateTime, Duration, Utc};
+use serenity::model::id::MessageId;
+
+const PRODUCT: &str = "-//discord-scheduler//EN";
+/// Longest content line in bytes; longer ones are folded onto continuation lines
+const LINE_LENGTH: usize = 75;
+
+fn utc(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape commas, semicolons, backslashes and newlines in a text value
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold `line` at character boundaries, continuing with a leading space
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > LINE_LENGTH {
+            folded += "\r\n ";
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded + "\r\n"
+}
+
+/// A calendar with one event per entry of `events`. `scheduler` keeps the event UIDs stable, so
+/// importing again updates them instead of adding duplicates.
+pub fn calendar(scheduler: MessageId, events: &[Event]) -> String {
+    let stamp = utc(Utc::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        format!("PRODID:{}", PRODUCT),
+        "CALSCALE:GREGORIAN".to_owned(),
+    ];
+    for event in events {
+        let (start, end) = match event.bounds() {
+            Some(bounds) => bounds,
+            None => continue,
+        };
+        lines.push("BEGIN:VEVENT".to_owned());
+        lines.push(format!(
+            "UID:{}-{}@discord-scheduler",
+            scheduler,
+            event.slot.to_string().replace(' ', "T")
+        ));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        match event.slot.time {
+            Some(_) => {
+                lines.push(format!("DTSTART:{}", utc(start)));
+                lines.push(format!("DTEND:{}", utc(end)));
+            }
+            // Whole days are floating dates, the same day wherever the calendar is
+            None => {
+                let date = event.slot.date;
+                lines.push(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+                let next = date + Duration::days(1);
+                lines.push(format!("DTEND;VALUE=DATE:{}", next.format("%Y%m%d")));
+            }
+        }
+        lines.push(format!("SUMMARY:{}", escape(&event.name)));
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape(description)));
+        }
+        lines.push(format!("URL:{}", event.location));
+        lines.push("END:VEVENT".to_owned());
+    }
+    lines.push("END:VCALENDAR".to_owned());
+    lines.iter().map(|line| fold(line)).collect()
+}
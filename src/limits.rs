@@ -0,0 +1,115 @@
+//! Discord API limits that rendered messages must stay within.
+//!
+//! Discord counts characters rather than bytes, so measuring in bytes errs on the safe side.
+
+pub const MESSAGE_LENGTH: usize = 2000;
+pub const EMBED_TITLE_LENGTH: usize = 256;
+pub const EMBED_DESCRIPTION_LENGTH: usize = 4096;
+pub const EMBED_FIELD_VALUE_LENGTH: usize = 1024;
+/// Shared by the titles, descriptions, field names and values and footers of all of a message's embeds
+pub const EMBED_TOTAL_LENGTH: usize = 6000;
+pub const EMBED_FIELDS: usize = 25;
+pub const THREAD_NAME_LENGTH: usize = 100;
+/// Kept short so notes fit alongside the rest of the details
+pub const NOTE_LENGTH: usize = 200;
+pub const ACTION_ROWS: usize = 5;
+/// Short enough to fit in a button's custom id along with the scheduler's
+pub const GUEST_NAME_LENGTH: usize = 50;
+pub const INPUT_LABEL_LENGTH: usize = 45;
+pub const BUTTONS_PER_ROW: usize = 5;
+pub const SELECT_OPTIONS: usize = 25;
+/// Templates a guild can save; not a Discord limit, but keeps guild configs small
+pub const TEMPLATES: usize = 25;
+/// Upload size allowed everywhere; boosted guilds raise this, but DMs never do
+pub const UPLOAD_SIZE: usize = 8 * 1024 * 1024;
+
+const ELLIPSIS: &str = "…";
+
+/// Cut `text` down to at most `limit` bytes, marking the cut with an ellipsis
+pub fn truncate(text: &str, limit: usize) -> String {
+    if text.len() <= limit {
+        return text.to_owned();
+    }
+    let mut end = limit.saturating_sub(ELLIPSIS.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}{}", &text[..end], ELLIPSIS)
+}
+
+/// Join lines into chunks of at most `limit` bytes, truncating any line that is too long by itself
+pub fn chunk_lines(lines: impl IntoIterator<Item = String>, limit: usize) -> Vec<String> {
+    let mut chunks = vec![];
+    let mut chunk = String::new();
+    for line in lines {
+        let line = truncate(&line, limit - 1);
+        if chunk.len() + line.len() + 1 > limit {
+            chunks.push(chunk);
+            chunk = String::new();
+        }
+        chunk += &line;
+        chunk.push('\n');
+    }
+    if !chunk.is_empty() || chunks.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// Join groups of lines into chunks of at most `limit` bytes, only splitting a group that doesn't
+/// fit in a chunk of its own
+pub fn chunk_groups(groups: impl IntoIterator<Item = Vec<String>>, limit: usize) -> Vec<String> {
+    let mut chunks: Vec<String> = vec![];
+    for group in groups {
+        let mut split = chunk_lines(group, limit).into_iter();
+        if let (Some(last), Some(first)) = (chunks.last_mut(), split.as_slice().first()) {
+            if split.len() == 1 && last.len() + first.len() <= limit {
+                *last += first;
+                split.next();
+            }
+        }
+        chunks.extend(split);
+    }
+    if chunks.is_empty() {
+        chunks.push(String::new());
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn groups_share_a_chunk_while_they_fit() {
+        let chunks = chunk_groups([group(&["a", "b"]), group(&["c"])], 10);
+        assert_eq!(chunks, ["a\nb\nc\n"]);
+    }
+
+    #[test]
+    fn groups_that_dont_fit_start_a_new_chunk() {
+        let chunks = chunk_groups([group(&["aaa"]), group(&["bbb", "cc"])], 8);
+        assert_eq!(chunks, ["aaa\n", "bbb\ncc\n"]);
+    }
+
+    #[test]
+    fn groups_too_long_for_a_chunk_are_split() {
+        let chunks = chunk_groups([group(&["a"]), group(&["bbb", "ccc", "ddd"])], 8);
+        assert_eq!(chunks, ["a\n", "bbb\nccc\n", "ddd\n"]);
+    }
+
+    #[test]
+    fn no_groups_make_one_empty_chunk() {
+        assert_eq!(chunk_groups([], 8), [""]);
+    }
+
+    #[test]
+    fn long_lines_are_truncated_on_a_char_boundary() {
+        let chunks = chunk_groups([group(&["ääää"])], 8);
+        assert_eq!(chunks, ["ää…\n"]);
+    }
+}
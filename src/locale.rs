@@ -0,0 +1,1674 @@
+//! Interface text, weekday and month names for the languages the bot speaks, since chrono only
+//! knows English
+
+use crate::slot::Slot;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Es,
+    Fr,
+    It,
+    Nl,
+    Pt,
+}
+
+impl Locale {
+    /// Parse a Discord locale like "de" or "pt-BR"; only the language is used
+    pub fn parse(s: &str) -> Option<Self> {
+        let language = s.split(['-', '_']).next()?;
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Some(Locale::En),
+            "de" => Some(Locale::De),
+            "es" => Some(Locale::Es),
+            "fr" => Some(Locale::Fr),
+            "it" => Some(Locale::It),
+            "nl" => Some(Locale::Nl),
+            "pt" => Some(Locale::Pt),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::En => "English",
+            Locale::De => "Deutsch",
+            Locale::Es => "Español",
+            Locale::Fr => "Français",
+            Locale::It => "Italiano",
+            Locale::Nl => "Nederlands",
+            Locale::Pt => "Português",
+        }
+    }
+
+    /// Abbreviated weekday names, starting on Monday
+    fn weekdays(&self) -> [&'static str; 7] {
+        match self {
+            Locale::En => ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+            Locale::De => ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+            Locale::Es => ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+            Locale::Fr => ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+            Locale::It => ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+            Locale::Nl => ["ma", "di", "wo", "do", "vr", "za", "zo"],
+            Locale::Pt => ["seg", "ter", "qua", "qui", "sex", "sáb", "dom"],
+        }
+    }
+
+    /// Abbreviated month names
+    fn months(&self) -> [&'static str; 12] {
+        match self {
+            Locale::En => [
+                "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+            ],
+            Locale::De => [
+                "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+            ],
+            Locale::Es => [
+                "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic",
+            ],
+            Locale::Fr => [
+                "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+                "nov.", "déc.",
+            ],
+            Locale::It => [
+                "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic",
+            ],
+            Locale::Nl => [
+                "jan", "feb", "mrt", "apr", "mei", "jun", "jul", "aug", "sep", "okt", "nov", "dec",
+            ],
+            Locale::Pt => [
+                "jan", "fev", "mar", "abr", "mai", "jun", "jul", "ago", "set", "out", "nov", "dez",
+            ],
+        }
+    }
+
+    pub fn weekday(&self, date: NaiveDate) -> &'static str {
+        self.weekdays()[date.weekday().num_days_from_monday() as usize]
+    }
+
+    pub fn month(&self, date: NaiveDate) -> &'static str {
+        self.months()[date.month0() as usize]
+    }
+
+    /// Weekday and ISO date, e.g. "Sat 2024-07-13"
+    fn long_date(&self, date: NaiveDate) -> String {
+        format!("{} {}", self.weekday(date), date.format("%Y-%m-%d"))
+    }
+
+    /// Weekday, day and month, e.g. "Sat Jul 13" or "Sa 13. Jul"
+    fn short_date(&self, date: NaiveDate) -> String {
+        let (weekday, month, day) = (self.weekday(date), self.month(date), date.day());
+        match self {
+            Locale::En => format!("{} {} {:02}", weekday, month, day),
+            Locale::De => format!("{} {}. {}", weekday, day, month),
+            _ => format!("{} {} {}", weekday, day, month),
+        }
+    }
+
+    /// A slot with its weekday and ISO date, e.g. "Sat 2024-07-13 18:00"
+    pub fn long_slot(&self, slot: Slot) -> String {
+        with_time(self.long_date(slot.date), slot)
+    }
+
+    /// A slot with its weekday, day and month, e.g. "Sat Jul 13 18:00"
+    pub fn short_slot(&self, slot: Slot) -> String {
+        with_time(self.short_date(slot.date), slot)
+    }
+
+    /// `date` formatted with a strftime pattern, with %a and %b in this language
+    fn format(&self, date: NaiveDate, pattern: &str) -> String {
+        let pattern = pattern
+            .replace("%a", self.weekday(date))
+            .replace("%b", self.month(date));
+        date.format(&pattern).to_string()
+    }
+}
+
+/// How a guild wants dates written instead of the language's usual forms
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateFormat {
+    /// A strftime pattern like "%a %d.%m."
+    Pattern(String),
+    /// Discord timestamps, which each reader sees in their own language and timezone
+    Discord,
+}
+
+impl DateFormat {
+    /// Parse "discord" or a strftime pattern
+    pub fn parse(s: &str) -> Option<Self> {
+        if s.eq_ignore_ascii_case("discord") {
+            return Some(DateFormat::Discord);
+        }
+        let valid = s.contains('%') && !StrftimeItems::new(s).any(|item| item == Item::Error);
+        valid.then(|| DateFormat::Pattern(s.to_owned()))
+    }
+}
+
+impl Display for DateFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateFormat::Pattern(pattern) => write!(f, "`{}`", pattern),
+            DateFormat::Discord => write!(f, "Discord timestamps"),
+        }
+    }
+}
+
+/// Everything that decides how a scheduler writes its dates
+#[derive(Clone)]
+pub struct DateStyle {
+    pub locale: Locale,
+    pub format: Option<DateFormat>,
+    /// The timezone of timed slots, for Discord timestamps
+    pub timezone: Tz,
+}
+
+impl DateStyle {
+    /// A slot in full, e.g. "Sat 2024-07-13 18:00"
+    pub fn long(&self, slot: Slot) -> String {
+        match &self.format {
+            Some(DateFormat::Pattern(pattern)) => {
+                with_time(self.locale.format(slot.date, pattern), slot)
+            }
+            _ => self.locale.long_slot(slot),
+        }
+    }
+
+    /// A slot where space is short, like buttons and menus, e.g. "Sat Jul 13 18:00"
+    pub fn short(&self, slot: Slot) -> String {
+        match &self.format {
+            Some(DateFormat::Pattern(pattern)) => {
+                with_time(self.locale.format(slot.date, pattern), slot)
+            }
+            _ => self.locale.short_slot(slot),
+        }
+    }
+
+    /// A Discord timestamp for the slot, when the guild wants those
+    pub fn timestamp(&self, slot: Slot) -> Option<String> {
+        if self.format != Some(DateFormat::Discord) {
+            return None;
+        }
+        Some(match slot.timestamp(self.timezone) {
+            Some(timestamp) => format!("<t:{}:f>", timestamp),
+            None => {
+                // Noon keeps the date the same for readers up to 12 hours away
+                let noon = Slot {
+                    time: Some(NaiveTime::from_hms_opt(12, 0, 0).unwrap()),
+                    ..slot
+                };
+                let timestamp = noon.timestamp(self.timezone).unwrap_or_else(|| {
+                    slot.date
+                        .and_hms_opt(12, 0, 0)
+                        .unwrap()
+                        .and_utc()
+                        .timestamp()
+                });
+                format!("<t:{}:D>", timestamp)
+            }
+        })
+    }
+
+    /// A slot in message text: a Discord timestamp, or the date in a code span
+    pub fn code(&self, slot: Slot) -> String {
+        self.timestamp(slot)
+            .unwrap_or_else(|| format!("`{}`", self.long(slot)))
+    }
+
+    /// A slot in exported files, which stay machine-readable unless the guild set a pattern
+    pub fn export(&self, slot: Slot) -> String {
+        match &self.format {
+            Some(DateFormat::Pattern(_)) => self.long(slot),
+            _ => slot.to_string(),
+        }
+    }
+}
+
+/// A piece of interface text; languages without a translation fall back to English
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Text {
+    Interested,
+    NotInterested,
+    ScheduleIt,
+    ImIn,
+    CantMakeIt,
+    AddResponse,
+    MyResponse,
+    ShowDetails,
+    Close,
+    CantMakeItAnymore,
+    JoinWaitlist,
+    Closed,
+    FinalResults,
+    BestDates,
+    Previous,
+    Page,
+    Next,
+    SelectAll,
+    ClearAll,
+    QuickSelect,
+    Back,
+    Submit,
+    StartOver,
+    PickDates,
+    PickDatesTentative,
+    ResponseSubmitted,
+    ResponseTimedOut,
+    NotResponded,
+    YourResponse,
+    IfNeeded,
+    Tentative,
+    NoDates,
+    PleaseWait,
+    Restarting,
+    MissingScheduler,
+    MissingServer,
+    MissingMessage,
+    NeedsFixing,
+    SomethingWentWrong,
+    SchedulerGone,
+    CreateDenied,
+    DescriptionEmpty,
+    DescriptionTooLong,
+    BodyTooLong,
+    AnnouncementTooLong,
+    UnknownPlaceholder,
+    UnknownTimezone,
+    TimezoneSet,
+    WeekdaysFormat,
+    DatesFormat,
+    DaysFormat,
+    TimesFormat,
+    SlotFormat,
+    PostAtFormat,
+    DeadlineFormat,
+    HideResultsFormat,
+    LayoutFormat,
+    GiveWeekdaysOrDates,
+    DatePassed,
+    DatesPassed,
+    TimePassed,
+    DeadlinePassed,
+    TooManySlots,
+    TooManyWeeks,
+    OnlyWeeklyHaveWeeks,
+    NothingToChange,
+    CalendarEmpty,
+    CalendarShown,
+    CalendarSaved,
+    NoPastSchedulers,
+    BestDaysByTurnout,
+    TemplatesPerServer,
+    TooManyTemplates,
+    TemplateSaved,
+    NoSuchTemplate,
+    AddOwnAvailability,
+    AddMyAvailability,
+    NoSchedulerToEdit,
+    NoSchedulerToWatch,
+    NoSchedulerToExport,
+    NoSchedulerToCancel,
+    NoSchedulerToClose,
+    NoOpenScheduler,
+    NoSourceScheduler,
+    MissingFirst,
+    MissingSecond,
+    FirstHidingResults,
+    SecondHidingResults,
+    SchedulerClosed,
+    SchedulerAlreadyClosed,
+    AlreadyInChannel,
+    NotAScheduler,
+    NotTakingResponses,
+    NoOpenSchedulers,
+    IntersectHeader,
+    IntersectNone,
+    NotRespondedAnywhere,
+    AvailabilityOf,
+    MovedTo,
+    TooManyResponses,
+    MatrixExplained,
+    NobodyCanMakeIt,
+    IcsExplained,
+    Cancelled,
+    Cancel,
+    Dates,
+    PostScheduled,
+    PostCancelled,
+    PostGone,
+    Conflicts,
+    NotePrompt,
+    AddNote,
+    BlackoutReasonPrompt,
+    GiveReasons,
+    NoBlackoutsToExplain,
+    SelfConstraint,
+    Linked,
+    Unlinked,
+    TitleFor,
+    RespondForThem,
+    AnsweringFor,
+    GuestsNeedName,
+    RosterTooLarge,
+    ClosedReply,
+    FinalizePrompt,
+    Yes,
+    CancelWithoutResults,
+    SettingsPerServer,
+    ManagersOnly,
+    OperatorsOnly,
+    OwnerOnlyLink,
+    OwnerOnlyEdit,
+    OwnerOnlyRebind,
+    OwnerOnlyCrossPost,
+    OwnerOnlyAlerts,
+    OwnerOnlyAddOwners,
+    OwnerOnlyRequire,
+    OwnerOnlyExport,
+    ResultsHidden,
+    OwnerOnlyCancel,
+    OwnerOnlyClose,
+    OwnerOnlyRespondForOthers,
+    OwnerOnlyTakeDatesOff,
+    OwnerOnlySchedule,
+    OwnerOnlyRoster,
+    OwnerOnlyGuests,
+    OwnerOnlyBlackoutReasons,
+    OwnerOnlyRemind,
+    OwnerOnlyPings,
+    OwnerOnlyNotify,
+    OwnerOnlyRecency,
+    OwnerOnlyRank,
+    OwnerOnlyPick,
+    OwnerOnlyDigest,
+    NobodyMissing,
+    Reminded,
+    ReminderQueued,
+    ReminderLine,
+    EditPingsOn,
+    EditPingsOff,
+    NotifyOn,
+    NotifyOff,
+    RecencyOn,
+    RecencyOff,
+    HasClosed,
+    DigestInterested,
+    NoResponsesReceived,
+    DigestBestDates,
+    ThresholdMet,
+    EveryoneResponded,
+    NotRespondedList,
+    FinalizeBest,
+    Extend,
+    Reopen,
+    Finalize,
+    Finalized,
+    NoDatesToFinalize,
+    Extended,
+    CannotExtend,
+    Reopened,
+    AlertReached,
+    RespondedTo,
+    ChangedResponse,
+    SameDates,
+    NoDatesPicked,
+    Maybe,
+    SpotOpened,
+    FellThrough,
+    FirmUpAsk,
+    ConfirmTentative,
+    DropTentative,
+    NobodyCouldMakeIt,
+    BestDateIs,
+    BestDatesAre,
+    ClosedWith,
+    TimesInYourTimezone,
+    TimesInSetYours,
+    TimesIn,
+    InvitedOnly,
+    RoleOnly,
+    AlertsDatesOnly,
+    AlertSet,
+    AlertAlreadyMet,
+    NoFinalToWait,
+    AlreadyAttending,
+    LeftWaitlist,
+    FinalNotFull,
+    OnWaitlist,
+    NoFinalToWithdraw,
+    NotOnFinal,
+    Withdrawn,
+    WithdrawReopened,
+    BestSoFar,
+    ExpectedShare,
+    Someone,
+    ActivityResponded,
+    ActivityWithdrew,
+    OwnerBlackedOut,
+    RemovedDates,
+    AddedDates,
+    WaitingForResponses,
+    InterestedField,
+    RespondedField,
+    UnavailableDates,
+    Stats,
+    Notes,
+    RecentActivity,
+    Results,
+    MoreDatesUnder,
+    FinalDate,
+    ConfirmedDate,
+    ScheduledIn,
+    FromInterestCheck,
+    PreviousScheduler,
+    NextScheduler,
+    RespondWithCommand,
+    ClosesAt,
+    ResultsHiddenUntil,
+    AddBlackoutDates,
+    EditRoster,
+    RemindNonResponders,
+    StopEditPings,
+    EditPings,
+    ListInOrder,
+    ListNewestFirst,
+    AnswerForGuest,
+    StopResponseDms,
+    DmOnResponses,
+    RankDates,
+    RespondedCount,
+    LastUpdated,
+    EnteredBy,
+    NotesHeading,
+    WaitlistCount,
+    CalendarConflicts,
+    Note,
+    NoteLabel,
+    NotePlaceholder,
+    RespondBeforeNote,
+    NoteRemoved,
+    NoteSaved,
+    Confirmed,
+    TakenOff,
+    NothingToConfirm,
+    RankedByEarliest,
+    RankedByRequired,
+    RankedByMaybe,
+    Earliest,
+    RequiredPlayers,
+    FewestMaybes,
+    RequiredAvailable,
+    NoTies,
+    PickDate,
+    DateNotOffered,
+    ClosedWithFinal,
+    PickTopDates,
+    ChoiceNumber,
+}
+
+impl Locale {
+    pub fn text(&self, text: Text) -> &'static str {
+        match self {
+            Locale::De => german(text),
+            Locale::Fr => french(text),
+            _ => english(text),
+        }
+    }
+
+    /// `text` with each `{n}` replaced by the nth of `args`, in a single pass so that the values
+    /// are never filled in themselves
+    pub fn fill(&self, text: Text, args: &[&(dyn Display + Sync)]) -> String {
+        let mut filled = String::new();
+        let mut rest = self.text(text);
+        while let Some(start) = rest.find('{') {
+            filled += &rest[..start];
+            let placeholder = rest[start + 1..]
+                .split_once('}')
+                .and_then(|(index, after)| {
+                    let arg = args.get(index.parse::<usize>().ok()?)?;
+                    Some((arg, after))
+                });
+            match placeholder {
+                Some((arg, after)) => {
+                    filled += &arg.to_string();
+                    rest = after;
+                }
+                None => {
+                    filled.push('{');
+                    rest = &rest[start + 1..];
+                }
+            }
+        }
+        filled + rest
+    }
+
+    pub fn phrase(&self, phrase: &Phrase) -> String {
+        match phrase {
+            Phrase::Text(text, args) => {
+                let args: Vec<&(dyn Display + Sync)> = args.iter().map(|arg| arg as _).collect();
+                self.fill(*text, &args)
+            }
+            Phrase::Verbatim(text) => text.clone(),
+        }
+    }
+}
+
+/// Interface text with its values, put into words once the reader's language is known
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Phrase {
+    Text(Text, Vec<String>),
+    /// Text that is only written in English, like the settings
+    Verbatim(String),
+}
+
+impl Phrase {
+    pub fn new(text: Text, args: &[&(dyn Display + Sync)]) -> Self {
+        Phrase::Text(text, args.iter().map(|arg| arg.to_string()).collect())
+    }
+}
+
+impl From<Text> for Phrase {
+    fn from(text: Text) -> Self {
+        Phrase::Text(text, vec![])
+    }
+}
+
+impl From<String> for Phrase {
+    fn from(text: String) -> Self {
+        Phrase::Verbatim(text)
+    }
+}
+
+impl fmt::Display for Phrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&Locale::En.phrase(self))
+    }
+}
+
+fn english(text: Text) -> &'static str {
+    match text {
+        Text::Interested => "I'm interested",
+        Text::NotInterested => "Not interested",
+        Text::ScheduleIt => "Schedule it",
+        Text::ImIn => "I'm in",
+        Text::CantMakeIt => "Can't make it",
+        Text::AddResponse => "Add response",
+        Text::MyResponse => "My response",
+        Text::ShowDetails => "Show details",
+        Text::Close => "Close",
+        Text::CantMakeItAnymore => "Can't make it anymore",
+        Text::JoinWaitlist => "Join/leave waitlist",
+        Text::Closed => "Closed",
+        Text::FinalResults => "Final results",
+        Text::BestDates => "Best dates",
+        Text::Previous => "Previous",
+        Text::Page => "Page",
+        Text::Next => "Next",
+        Text::SelectAll => "Select all",
+        Text::ClearAll => "Clear all",
+        Text::QuickSelect => "Quick select",
+        Text::Back => "Back",
+        Text::Submit => "Submit",
+        Text::StartOver => "Start over",
+        Text::PickDates => "Click a date once if you can make it, twice if only if needed",
+        Text::PickDatesTentative => {
+            "Click a date once if you can make it, twice if you aren't sure yet and three times \
+            if only if needed"
+        }
+        Text::ResponseSubmitted => "Response submitted",
+        Text::ResponseTimedOut => "Response timed out",
+        Text::NotResponded => "You haven't responded yet",
+        Text::YourResponse => "Your response",
+        Text::IfNeeded => "if needed",
+        Text::Tentative => "tentative",
+        Text::NoDates => "You can't make any of the dates",
+        Text::PleaseWait => "Please wait...",
+        Text::Restarting => "Bot restarting, please resubmit",
+        Text::MissingScheduler => "Cannot find that scheduler; it may have been deleted",
+        Text::MissingServer => "Cannot find that server; it may have been deleted",
+        Text::MissingMessage => "Cannot find that message; it may have been deleted",
+        Text::NeedsFixing => "This needs fixing in the settings: {0}",
+        Text::SomethingWentWrong => "Something went wrong — ref {0}",
+        Text::SchedulerGone => "This scheduler no longer exists",
+        Text::CreateDenied => "Only {0} and server managers can create schedulers",
+        Text::DescriptionEmpty => "Description cannot be empty",
+        Text::DescriptionTooLong => "Description is too long; the limit is {0} characters",
+        Text::BodyTooLong => "Body is too long; the limit is {0} characters",
+        Text::AnnouncementTooLong => "Announcement is too long; the limit is {0} characters",
+        Text::UnknownPlaceholder => "Unknown placeholder {0}; announcements can use {1}",
+        Text::UnknownTimezone => "Unknown timezone: {0}",
+        Text::TimezoneSet => "Your timezone is set to {0}",
+        Text::WeekdaysFormat => "Weekdays look like Mon or Mon+Wed",
+        Text::DatesFormat => "Dates look like 2024-06-01,2024-06-08",
+        Text::DaysFormat => "Days look like Mon+Wed+Fri or Sat+Sun",
+        Text::TimesFormat => "Times look like 18:00+20:00",
+        Text::SlotFormat => {
+            "Dates look like 2024-07-13, optionally with a time like 2024-07-13 18:00"
+        }
+        Text::PostAtFormat => "Post times look like 2024-07-15 09:00",
+        Text::DeadlineFormat => "Deadlines look like 2024-07-15 or 2024-07-15 18:00",
+        Text::HideResultsFormat => "Hide results takes a count or a percentage",
+        Text::LayoutFormat => {
+            "Layout takes sections from responded, results, stats, notes and activity"
+        }
+        Text::GiveWeekdaysOrDates => "Give weekdays, dates or both",
+        Text::DatePassed => "That date has already passed",
+        Text::DatesPassed => "Those dates have already passed",
+        Text::TimePassed => "That time has already passed",
+        Text::DeadlinePassed => "That deadline has already passed",
+        Text::TooManySlots => "At most {0} slots fit; try fewer weeks, dates, days or times",
+        Text::TooManyWeeks => "At most {0} slots fit; try fewer weeks",
+        Text::OnlyWeeklyHaveWeeks => "Only weekly date schedulers have weeks",
+        Text::NothingToChange => "Nothing to change",
+        Text::CalendarEmpty => "Your personal calendar is empty",
+        Text::CalendarShown => "You're unavailable on {0}",
+        Text::CalendarSaved => {
+            "You're unavailable on {0}; schedulers leave those dates unpicked when you respond"
+        }
+        Text::NoPastSchedulers => "There are no past schedulers for this group yet",
+        Text::BestDaysByTurnout => "**Best days by past turnout**",
+        Text::TemplatesPerServer => "Templates are saved per server",
+        Text::TooManyTemplates => "A server can save at most {0} templates",
+        Text::TemplateSaved => "Saved template **{0}**; use it with `/schedule from-template`",
+        Text::NoSuchTemplate => "No template is called {0}",
+        Text::AddOwnAvailability => {
+            "Add your own availability; dates you can't make come off the poll"
+        }
+        Text::AddMyAvailability => "Add my availability",
+        Text::NoSchedulerToEdit => "Cannot find an open scheduler to edit",
+        Text::NoSchedulerToWatch => "Cannot find an open scheduler to watch",
+        Text::NoSchedulerToExport => "Cannot find a scheduler to export",
+        Text::NoSchedulerToCancel => "Cannot find an open scheduler to cancel",
+        Text::NoSchedulerToClose => "Cannot find an open scheduler to close",
+        Text::NoOpenScheduler => "Cannot find an open scheduler",
+        Text::NoSourceScheduler => "Cannot find the scheduler to take dates from",
+        Text::MissingFirst => "Cannot find the first scheduler",
+        Text::MissingSecond => "Cannot find the second scheduler",
+        Text::FirstHidingResults => "The first scheduler is still hiding results",
+        Text::SecondHidingResults => "The second scheduler is still hiding results",
+        Text::SchedulerClosed => "That scheduler is closed",
+        Text::SchedulerAlreadyClosed => "That scheduler is already closed",
+        Text::AlreadyInChannel => "That scheduler is already in this channel",
+        Text::NotAScheduler => "That message isn't a scheduler",
+        Text::NotTakingResponses => "That scheduler isn't taking responses",
+        Text::NoOpenSchedulers => "No open schedulers",
+        Text::IntersectHeader => "Dates among the top {0} of both **{1}** and **{2}**",
+        Text::IntersectNone => "{0}: none",
+        Text::NotRespondedAnywhere => "<@{0}> hasn't responded to any open schedulers here",
+        Text::AvailabilityOf => "**Availability of <@{0}>**",
+        Text::MovedTo => "Moved to {0}",
+        Text::TooManyResponses => "Too many responses to fit in a file",
+        Text::MatrixExplained => "One row per respondent: 1 is available, x is blacked out",
+        Text::NobodyCanMakeIt => "Nobody can make any of the dates yet",
+        Text::IcsExplained => "Open the file to add the dates to your calendar",
+        Text::Cancelled => "Cancelled",
+        Text::Cancel => "Cancel",
+        Text::Dates => "Dates",
+        Text::PostScheduled => "This scheduler will be posted here {0}",
+        Text::PostCancelled => "Cancelled; it won't be posted",
+        Text::PostGone => "It was already posted or cancelled",
+        Text::Conflicts => {
+            "{0} of the dates you changed were also changed elsewhere while you were answering; \
+            they now have your answer"
+        }
+        Text::NotePrompt => "Anything to add, like when you need to leave?",
+        Text::AddNote => "Add note",
+        Text::BlackoutReasonPrompt => "Let responders know why dates are blacked out?",
+        Text::GiveReasons => "Give reasons",
+        Text::NoBlackoutsToExplain => "There are no blackout dates to give reasons for",
+        Text::SelfConstraint => "A scheduler cannot constrain itself",
+        Text::Linked => "Linked: {0} dates blacked out so far",
+        Text::Unlinked => "Unlinked",
+        Text::TitleFor => "**{0}** for <@{1}>",
+        Text::RespondForThem => "Respond for them",
+        Text::AnsweringFor => "Answering for *{0}*",
+        Text::GuestsNeedName => "Guests need a name",
+        Text::RosterTooLarge => "That file is too large for a roster",
+        Text::ClosedReply => "Closed!",
+        Text::FinalizePrompt => "Finalize?",
+        Text::Yes => "Yes",
+        Text::CancelWithoutResults => "Cancel without results",
+        Text::SettingsPerServer => "Settings are per server",
+        Text::ManagersOnly => "Only server managers can change settings",
+        Text::OperatorsOnly => "Only operators can run maintenance",
+        Text::OwnerOnlyLink => "Only the owner of both schedulers can link them",
+        Text::OwnerOnlyEdit => "Only owner can edit",
+        Text::OwnerOnlyRebind => "Only owner can rebind",
+        Text::OwnerOnlyCrossPost => "Only owner can cross-post",
+        Text::OwnerOnlyAlerts => "Only owner can set alerts",
+        Text::OwnerOnlyAddOwners => "Only owner can add owners",
+        Text::OwnerOnlyRequire => "Only owner can choose required players",
+        Text::OwnerOnlyExport => "Only owner can export responses",
+        Text::ResultsHidden => "Results are hidden until more have responded",
+        Text::OwnerOnlyCancel => "Only owner can cancel",
+        Text::OwnerOnlyClose => "Only owner can close",
+        Text::OwnerOnlyRespondForOthers => "Only owner can respond for others",
+        Text::OwnerOnlyTakeDatesOff => "Only owner can take dates off",
+        Text::OwnerOnlySchedule => "Only owner can schedule",
+        Text::OwnerOnlyRoster => "Only owner can edit the roster",
+        Text::OwnerOnlyGuests => "Only owner can answer for guests",
+        Text::OwnerOnlyBlackoutReasons => "Only owner can give blackout reasons",
+        Text::OwnerOnlyRemind => "Only owner can send reminders",
+        Text::OwnerOnlyPings => "Only owner can change pings",
+        Text::OwnerOnlyNotify => "Only owner can get notified of responses",
+        Text::OwnerOnlyRecency => "Only owner can reorder respondents",
+        Text::OwnerOnlyRank => "Only owner can rank dates",
+        Text::OwnerOnlyPick => "Only owner can pick the date",
+        Text::OwnerOnlyDigest => "Only owner can act on the results",
+        Text::NobodyMissing => "Nobody expected is missing a response",
+        Text::Reminded => "Reminded {0} players",
+        Text::ReminderQueued => "Reminder to {0} players will be sent after quiet hours",
+        Text::ReminderLine => "Reminder: please respond to **{0}** {1}",
+        Text::EditPingsOn => "Edits will mention everyone again",
+        Text::EditPingsOff => "Only the first post mentions everyone now",
+        Text::NotifyOn => "You'll get a DM whenever someone responds or changes their response",
+        Text::NotifyOff => "You'll no longer get a DM about each response",
+        Text::RecencyOn => "Respondents are now listed newest first",
+        Text::RecencyOff => "Respondents are now listed in a fixed order",
+        Text::HasClosed => "**{0}** has closed: {1}",
+        Text::DigestInterested => "Interested: {0}",
+        Text::NoResponsesReceived => "No responses were received.",
+        Text::DigestBestDates => "Best dates ({0} available): {1}",
+        Text::ThresholdMet => "{0} dates had at least {1} available.",
+        Text::EveryoneResponded => "Everyone expected responded.",
+        Text::NotRespondedList => "Not responded: {0}",
+        Text::FinalizeBest => "Finalize best",
+        Text::Extend => "Extend",
+        Text::Reopen => "Reopen",
+        Text::Finalize => "Finalize",
+        Text::Finalized => "Finalized {0}",
+        Text::NoDatesToFinalize => "There are no dates to finalize",
+        Text::Extended => "Extended by one week and reopened",
+        Text::CannotExtend => "Cannot extend any further",
+        Text::Reopened => "Reopened",
+        Text::AlertReached => "**{0}**: {1} available on {2}\n{3}",
+        Text::RespondedTo => "<@{0}> responded to **{1}**: {2}\n{3}",
+        Text::ChangedResponse => "<@{0}> changed their response to **{1}**: {2}\n{3}",
+        Text::SameDates => "same dates",
+        Text::NoDatesPicked => "no dates",
+        Text::Maybe => "{0} (maybe)",
+        Text::SpotOpened => "A spot opened up on {0} for **{1}**, so you're now attending: {2}",
+        Text::FellThrough => {
+            "**{0}** on {1} fell through ({2}/{3} can still make it), so it has been reopened \
+            without that date: {4}\n{5}"
+        }
+        Text::FirmUpAsk => "You marked {0} as tentative for **{1}**: {2}\nCan you make it?",
+        Text::ConfirmTentative => "Yes, confirm",
+        Text::DropTentative => "No, take them off",
+        Text::NobodyCouldMakeIt => "nobody could make any of the dates",
+        Text::BestDateIs => "the best date is {0} ({1} available)",
+        Text::BestDatesAre => "the best dates are {0} ({1} available)",
+        Text::ClosedWith => "**{0}** has closed: {1}\n{2}",
+        Text::TimesInYourTimezone => "Times are in your timezone, {0}",
+        Text::TimesInSetYours => "Times are in {0}; set your own with /timezone",
+        Text::TimesIn => "Times are in {0}",
+        Text::InvitedOnly => "Only invited players may respond",
+        Text::RoleOnly => "Only <@&{0}> may respond",
+        Text::AlertsDatesOnly => "Only date schedulers have alerts",
+        Text::AlertSet => "You'll get a DM once a date has {0} available",
+        Text::AlertAlreadyMet => "A date already has that many available; sent you a DM",
+        Text::NoFinalToWait => "There is no final date to wait for",
+        Text::AlreadyAttending => "You're already attending",
+        Text::LeftWaitlist => "You've left the waitlist",
+        Text::FinalNotFull => "The final date isn't full",
+        Text::OnWaitlist => "You're #{0} on the waitlist; you'll get a DM if a spot opens up",
+        Text::NoFinalToWithdraw => "There is no final date to withdraw from",
+        Text::NotOnFinal => "You weren't available on the final date",
+        Text::Withdrawn => "You've been taken off the final date",
+        Text::WithdrawReopened => {
+            "Too few can make the final date, so the scheduler has been reopened"
+        }
+        Text::BestSoFar => "Best so far: {0} ({1} available)",
+        Text::ExpectedShare => "{0}/{1} expected ({2}%)",
+        Text::Someone => "Someone",
+        Text::ActivityResponded => "{0} responded",
+        Text::ActivityWithdrew => "{0} can't make it anymore",
+        Text::OwnerBlackedOut => "Owner blacked out {0}",
+        Text::RemovedDates => "removed {0}",
+        Text::AddedDates => "added {0}",
+        Text::WaitingForResponses => "Waiting for more responses ({0}/{1})…",
+        Text::InterestedField => "Interested",
+        Text::RespondedField => "Responded",
+        Text::UnavailableDates => "Unavailable dates",
+        Text::Stats => "Stats",
+        Text::Notes => "Notes",
+        Text::RecentActivity => "Recent activity",
+        Text::Results => "Results",
+        Text::MoreDatesUnder => "*More dates under {0}*",
+        Text::FinalDate => "Final date: **{0}**",
+        Text::ConfirmedDate => "Date: **{0}**",
+        Text::ScheduledIn => "Scheduled: {0}",
+        Text::FromInterestCheck => "From interest check: {0}",
+        Text::PreviousScheduler => "Previous: {0}",
+        Text::NextScheduler => "Next: {0}",
+        Text::RespondWithCommand => "Respond with `/respond`, giving it a link to this message",
+        Text::ClosesAt => "Closes <t:{0}:R>",
+        Text::ResultsHiddenUntil => "Results are hidden until more have responded ({0}/{1})",
+        Text::AddBlackoutDates => "Add blackout dates",
+        Text::EditRoster => "Edit roster",
+        Text::RemindNonResponders => "Remind non-responders",
+        Text::StopEditPings => "Stop pinging on edits",
+        Text::EditPings => "Ping on edits",
+        Text::ListInOrder => "List respondents in order",
+        Text::ListNewestFirst => "List newest responses first",
+        Text::AnswerForGuest => "Answer for a guest",
+        Text::StopResponseDms => "Stop response DMs",
+        Text::DmOnResponses => "DM me on responses",
+        Text::RankDates => "Rank dates",
+        Text::RespondedCount => "**Responded ({0})**",
+        Text::LastUpdated => "**Last updated**",
+        Text::EnteredBy => "{0} (entered by <@{1}>)",
+        Text::NotesHeading => "**Notes**",
+        Text::WaitlistCount => "**Waitlist ({0})**",
+        Text::CalendarConflicts => "**Conflicts with personal calendars**",
+        Text::Note => "Note",
+        Text::NoteLabel => "Anything the owner should know",
+        Text::NotePlaceholder => "Can only stay until 10pm",
+        Text::RespondBeforeNote => "Respond before adding a note",
+        Text::NoteRemoved => "Note removed",
+        Text::NoteSaved => "Note saved",
+        Text::Confirmed => "Confirmed",
+        Text::TakenOff => "Taken off",
+        Text::NothingToConfirm => "Nothing left to confirm",
+        Text::RankedByEarliest => "**Rank dates** by earliest",
+        Text::RankedByRequired => "**Rank dates** by required players",
+        Text::RankedByMaybe => "**Rank dates** by fewest maybes",
+        Text::Earliest => "Earliest",
+        Text::RequiredPlayers => "Required players",
+        Text::FewestMaybes => "Fewest maybes",
+        Text::RequiredAvailable => "{0}, {1}/{2} required",
+        Text::NoTies => "No dates are tied",
+        Text::PickDate => "Pick {0}",
+        Text::DateNotOffered => "That date is no longer offered",
+        Text::ClosedWithFinal => "Closed with {0} as the final date",
+        Text::PickTopDates => "Pick your top {0} dates, favorite first",
+        Text::ChoiceNumber => "Choice #{0}",
+    }
+}
+
+fn german(text: Text) -> &'static str {
+    match text {
+        Text::Interested => "Interessiert",
+        Text::NotInterested => "Nicht interessiert",
+        Text::ScheduleIt => "Termin finden",
+        Text::ImIn => "Bin dabei",
+        Text::CantMakeIt => "Kann nicht",
+        Text::AddResponse => "Antworten",
+        Text::MyResponse => "Meine Antwort",
+        Text::ShowDetails => "Details anzeigen",
+        Text::Close => "Schließen",
+        Text::CantMakeItAnymore => "Kann doch nicht",
+        Text::JoinWaitlist => "Warteliste an/ab",
+        Text::Closed => "Geschlossen",
+        Text::FinalResults => "Endergebnis",
+        Text::BestDates => "Beste Termine",
+        Text::Previous => "Zurück",
+        Text::Page => "Seite",
+        Text::Next => "Weiter",
+        Text::SelectAll => "Alle auswählen",
+        Text::ClearAll => "Alle abwählen",
+        Text::QuickSelect => "Schnellauswahl",
+        Text::Back => "Zurück",
+        Text::Submit => "Absenden",
+        Text::StartOver => "Neu anfangen",
+        Text::PickDates => "Einmal klicken, wenn du kannst, zweimal, wenn nur zur Not",
+        Text::PickDatesTentative => {
+            "Einmal klicken, wenn du kannst, zweimal, wenn du noch unsicher bist, dreimal, wenn \
+            nur zur Not"
+        }
+        Text::ResponseSubmitted => "Antwort gesendet",
+        Text::ResponseTimedOut => "Zeit für die Antwort abgelaufen",
+        Text::NotResponded => "Du hast noch nicht geantwortet",
+        Text::YourResponse => "Deine Antwort",
+        Text::IfNeeded => "zur Not",
+        Text::Tentative => "unsicher",
+        Text::NoDates => "Du kannst an keinem der Termine",
+        Text::PleaseWait => "Bitte warten...",
+        Text::Restarting => "Der Bot startet neu, bitte erneut absenden",
+        Text::MissingScheduler => {
+            "Diese Terminumfrage wurde nicht gefunden; vielleicht wurde sie gelöscht"
+        }
+        Text::MissingServer => "Dieser Server wurde nicht gefunden; vielleicht wurde er gelöscht",
+        Text::MissingMessage => {
+            "Diese Nachricht wurde nicht gefunden; vielleicht wurde sie gelöscht"
+        }
+        Text::NeedsFixing => "Das muss in den Einstellungen behoben werden: {0}",
+        Text::SomethingWentWrong => "Etwas ist schiefgelaufen — Ref. {0}",
+        Text::SchedulerGone => "Diese Terminumfrage gibt es nicht mehr",
+        Text::CreateDenied => "Nur {0} und Server-Verwaltende können Terminumfragen erstellen",
+        Text::DescriptionEmpty => "Die Beschreibung darf nicht leer sein",
+        Text::DescriptionTooLong => "Die Beschreibung ist zu lang; erlaubt sind {0} Zeichen",
+        Text::BodyTooLong => "Der Text ist zu lang; erlaubt sind {0} Zeichen",
+        Text::AnnouncementTooLong => "Die Ankündigung ist zu lang; erlaubt sind {0} Zeichen",
+        Text::UnknownPlaceholder => "Unbekannter Platzhalter {0}; Ankündigungen können {1} nutzen",
+        Text::UnknownTimezone => "Unbekannte Zeitzone: {0}",
+        Text::TimezoneSet => "Deine Zeitzone ist jetzt {0}",
+        Text::WeekdaysFormat => "Wochentage sehen so aus: Mon oder Mon+Wed",
+        Text::DatesFormat => "Daten sehen so aus: 2024-06-01,2024-06-08",
+        Text::DaysFormat => "Tage sehen so aus: Mon+Wed+Fri oder Sat+Sun",
+        Text::TimesFormat => "Uhrzeiten sehen so aus: 18:00+20:00",
+        Text::SlotFormat => {
+            "Daten sehen so aus: 2024-07-13, wahlweise mit Uhrzeit wie 2024-07-13 18:00"
+        }
+        Text::PostAtFormat => "Zeitpunkte sehen so aus: 2024-07-15 09:00",
+        Text::DeadlineFormat => "Fristen sehen so aus: 2024-07-15 oder 2024-07-15 18:00",
+        Text::HideResultsFormat => {
+            "Ergebnisse verbergen braucht eine Anzahl oder einen Prozentsatz"
+        }
+        Text::LayoutFormat => {
+            "Das Layout nimmt Abschnitte aus responded, results, stats, notes und activity"
+        }
+        Text::GiveWeekdaysOrDates => "Gib Wochentage, Daten oder beides an",
+        Text::DatePassed => "Dieses Datum ist schon vorbei",
+        Text::DatesPassed => "Diese Daten sind schon vorbei",
+        Text::TimePassed => "Dieser Zeitpunkt ist schon vorbei",
+        Text::DeadlinePassed => "Diese Frist ist schon vorbei",
+        Text::TooManySlots => {
+            "Es passen höchstens {0} Termine; versuche weniger Wochen, Daten, Tage oder Uhrzeiten"
+        }
+        Text::TooManyWeeks => "Es passen höchstens {0} Termine; versuche weniger Wochen",
+        Text::OnlyWeeklyHaveWeeks => "Nur wöchentliche Terminumfragen haben Wochen",
+        Text::NothingToChange => "Nichts zu ändern",
+        Text::CalendarEmpty => "Dein persönlicher Kalender ist leer",
+        Text::CalendarShown => "Du kannst nicht an: {0}",
+        Text::CalendarSaved => {
+            "Du kannst nicht an: {0}; Terminumfragen lassen diese Termine beim Antworten frei"
+        }
+        Text::NoPastSchedulers => "Für diese Gruppe gibt es noch keine vergangenen Terminumfragen",
+        Text::BestDaysByTurnout => "**Beste Tage nach bisheriger Beteiligung**",
+        Text::TemplatesPerServer => "Vorlagen werden pro Server gespeichert",
+        Text::TooManyTemplates => "Ein Server kann höchstens {0} Vorlagen speichern",
+        Text::TemplateSaved => {
+            "Vorlage **{0}** gespeichert; nutze sie mit `/schedule from-template`"
+        }
+        Text::NoSuchTemplate => "Keine Vorlage heißt {0}",
+        Text::AddOwnAvailability => {
+            "Trag deine eigene Verfügbarkeit ein; Termine, an denen du nicht kannst, fallen aus \
+            der Umfrage"
+        }
+        Text::AddMyAvailability => "Meine Verfügbarkeit eintragen",
+        Text::NoSchedulerToEdit => "Keine offene Terminumfrage zum Bearbeiten gefunden",
+        Text::NoSchedulerToWatch => "Keine offene Terminumfrage zum Beobachten gefunden",
+        Text::NoSchedulerToExport => "Keine Terminumfrage zum Exportieren gefunden",
+        Text::NoSchedulerToCancel => "Keine offene Terminumfrage zum Absagen gefunden",
+        Text::NoSchedulerToClose => "Keine offene Terminumfrage zum Schließen gefunden",
+        Text::NoOpenScheduler => "Keine offene Terminumfrage gefunden",
+        Text::NoSourceScheduler => {
+            "Die Terminumfrage, aus der die Termine kommen sollen, wurde nicht gefunden"
+        }
+        Text::MissingFirst => "Die erste Terminumfrage wurde nicht gefunden",
+        Text::MissingSecond => "Die zweite Terminumfrage wurde nicht gefunden",
+        Text::FirstHidingResults => "Die erste Terminumfrage verbirgt ihre Ergebnisse noch",
+        Text::SecondHidingResults => "Die zweite Terminumfrage verbirgt ihre Ergebnisse noch",
+        Text::SchedulerClosed => "Diese Terminumfrage ist geschlossen",
+        Text::SchedulerAlreadyClosed => "Diese Terminumfrage ist schon geschlossen",
+        Text::AlreadyInChannel => "Diese Terminumfrage ist schon in diesem Kanal",
+        Text::NotAScheduler => "Diese Nachricht ist keine Terminumfrage",
+        Text::NotTakingResponses => "Diese Terminumfrage nimmt keine Antworten an",
+        Text::NoOpenSchedulers => "Keine offenen Terminumfragen",
+        Text::IntersectHeader => "Termine unter den besten {0} von **{1}** und **{2}**",
+        Text::IntersectNone => "{0}: keine",
+        Text::NotRespondedAnywhere => "<@{0}> hat hier auf keine offene Terminumfrage geantwortet",
+        Text::AvailabilityOf => "**Verfügbarkeit von <@{0}>**",
+        Text::MovedTo => "Verschoben nach {0}",
+        Text::TooManyResponses => "Zu viele Antworten für eine Datei",
+        Text::MatrixExplained => "Eine Zeile pro Person: 1 heißt verfügbar, x heißt gesperrt",
+        Text::NobodyCanMakeIt => "Noch kann niemand an einem der Termine",
+        Text::IcsExplained => "Öffne die Datei, um die Termine in deinen Kalender einzutragen",
+        Text::Cancelled => "Abgesagt",
+        Text::Cancel => "Absagen",
+        Text::Dates => "Termine",
+        Text::PostScheduled => "Diese Terminumfrage wird hier {0} gepostet",
+        Text::PostCancelled => "Abgesagt; sie wird nicht gepostet",
+        Text::PostGone => "Sie wurde schon gepostet oder abgesagt",
+        Text::Conflicts => {
+            "{0} der geänderten Termine wurden während deiner Antwort auch anderswo geändert; \
+            jetzt gilt deine Antwort"
+        }
+        Text::NotePrompt => "Noch etwas hinzuzufügen, etwa wann du gehen musst?",
+        Text::AddNote => "Notiz hinzufügen",
+        Text::BlackoutReasonPrompt => "Den Antwortenden sagen, warum Termine gesperrt sind?",
+        Text::GiveReasons => "Gründe angeben",
+        Text::NoBlackoutsToExplain => "Es gibt keine gesperrten Termine zu begründen",
+        Text::SelfConstraint => "Eine Terminumfrage kann sich nicht selbst einschränken",
+        Text::Linked => "Verknüpft: bisher {0} Termine gesperrt",
+        Text::Unlinked => "Verknüpfung aufgehoben",
+        Text::TitleFor => "**{0}** für <@{1}>",
+        Text::RespondForThem => "Für sie antworten",
+        Text::AnsweringFor => "Antwort für *{0}*",
+        Text::GuestsNeedName => "Gäste brauchen einen Namen",
+        Text::RosterTooLarge => "Die Datei ist zu groß für eine Teilnehmerliste",
+        Text::ClosedReply => "Geschlossen!",
+        Text::FinalizePrompt => "Abschließen?",
+        Text::Yes => "Ja",
+        Text::CancelWithoutResults => "Ohne Ergebnis absagen",
+        Text::SettingsPerServer => "Einstellungen gelten pro Server",
+        Text::ManagersOnly => "Nur Server-Verwaltende können Einstellungen ändern",
+        Text::OperatorsOnly => "Nur Betreiber können Wartungen ausführen",
+        Text::OwnerOnlyLink => "Nur wem beide Terminumfragen gehören, kann sie verknüpfen",
+        Text::OwnerOnlyEdit => "Nur Eigentümer können bearbeiten",
+        Text::OwnerOnlyRebind => "Nur Eigentümer können verschieben",
+        Text::OwnerOnlyCrossPost => "Nur Eigentümer können in andere Kanäle posten",
+        Text::OwnerOnlyAlerts => "Nur Eigentümer können Hinweise einrichten",
+        Text::OwnerOnlyAddOwners => "Nur Eigentümer können Eigentümer hinzufügen",
+        Text::OwnerOnlyRequire => "Nur Eigentümer können Pflichtteilnehmer festlegen",
+        Text::OwnerOnlyExport => "Nur Eigentümer können Antworten exportieren",
+        Text::ResultsHidden => "Die Ergebnisse bleiben verborgen, bis mehr geantwortet haben",
+        Text::OwnerOnlyCancel => "Nur Eigentümer können absagen",
+        Text::OwnerOnlyClose => "Nur Eigentümer können schließen",
+        Text::OwnerOnlyRespondForOthers => "Nur Eigentümer können für andere antworten",
+        Text::OwnerOnlyTakeDatesOff => "Nur Eigentümer können Termine streichen",
+        Text::OwnerOnlySchedule => "Nur Eigentümer können planen",
+        Text::OwnerOnlyRoster => "Nur Eigentümer können die Teilnehmerliste bearbeiten",
+        Text::OwnerOnlyGuests => "Nur Eigentümer können für Gäste antworten",
+        Text::OwnerOnlyBlackoutReasons => "Nur Eigentümer können Sperren begründen",
+        Text::OwnerOnlyRemind => "Nur Eigentümer können Erinnerungen senden",
+        Text::OwnerOnlyPings => "Nur Eigentümer können Erwähnungen ändern",
+        Text::OwnerOnlyNotify => "Nur Eigentümer können sich über Antworten benachrichtigen lassen",
+        Text::OwnerOnlyRecency => "Nur Eigentümer können die Reihenfolge ändern",
+        Text::OwnerOnlyRank => "Nur Eigentümer können Termine ordnen",
+        Text::OwnerOnlyPick => "Nur Eigentümer können den Termin wählen",
+        Text::OwnerOnlyDigest => "Nur Eigentümer können über das Ergebnis entscheiden",
+        Text::NobodyMissing => "Von den Erwarteten fehlt keine Antwort",
+        Text::Reminded => "{0} Spieler erinnert",
+        Text::ReminderQueued => "Die Erinnerung an {0} Spieler geht nach der Ruhezeit raus",
+        Text::ReminderLine => "Erinnerung: Bitte antworte auf **{0}** {1}",
+        Text::EditPingsOn => "Änderungen erwähnen wieder alle",
+        Text::EditPingsOff => "Nur der erste Beitrag erwähnt jetzt alle",
+        Text::NotifyOn => {
+            "Du bekommst eine DM, wann immer jemand antwortet oder die Antwort ändert"
+        }
+        Text::NotifyOff => "Du bekommst keine DM mehr zu jeder Antwort",
+        Text::RecencyOn => "Antwortende werden jetzt neueste zuerst aufgeführt",
+        Text::RecencyOff => "Antwortende werden jetzt in fester Reihenfolge aufgeführt",
+        Text::HasClosed => "**{0}** ist geschlossen: {1}",
+        Text::DigestInterested => "Interessiert: {0}",
+        Text::NoResponsesReceived => "Es kamen keine Antworten.",
+        Text::DigestBestDates => "Beste Termine ({0} verfügbar): {1}",
+        Text::ThresholdMet => "{0} Termine hatten mindestens {1} Verfügbare.",
+        Text::EveryoneResponded => "Alle Erwarteten haben geantwortet.",
+        Text::NotRespondedList => "Nicht geantwortet: {0}",
+        Text::FinalizeBest => "Besten festlegen",
+        Text::Extend => "Verlängern",
+        Text::Reopen => "Wieder öffnen",
+        Text::Finalize => "Festlegen",
+        Text::Finalized => "{0} festgelegt",
+        Text::NoDatesToFinalize => "Es gibt keine Termine zum Festlegen",
+        Text::Extended => "Um eine Woche verlängert und wieder geöffnet",
+        Text::CannotExtend => "Weiter verlängern geht nicht",
+        Text::Reopened => "Wieder geöffnet",
+        Text::AlertReached => "**{0}**: {1} verfügbar am {2}\n{3}",
+        Text::RespondedTo => "<@{0}> hat auf **{1}** geantwortet: {2}\n{3}",
+        Text::ChangedResponse => "<@{0}> hat die Antwort auf **{1}** geändert: {2}\n{3}",
+        Text::SameDates => "dieselben Termine",
+        Text::NoDatesPicked => "keine Termine",
+        Text::Maybe => "{0} (vielleicht)",
+        Text::SpotOpened => {
+            "Am {0} ist bei **{1}** ein Platz frei geworden, du bist jetzt dabei: {2}"
+        }
+        Text::FellThrough => {
+            "**{0}** am {1} findet nicht statt ({2}/{3} können noch), daher wurde die Umfrage \
+            ohne diesen Termin wieder geöffnet: {4}\n{5}"
+        }
+        Text::FirmUpAsk => "Du hast {0} bei **{1}** als unsicher markiert: {2}\nKannst du?",
+        Text::ConfirmTentative => "Ja, bestätigen",
+        Text::DropTentative => "Nein, streichen",
+        Text::NobodyCouldMakeIt => "niemand konnte an einem der Termine",
+        Text::BestDateIs => "der beste Termin ist {0} ({1} verfügbar)",
+        Text::BestDatesAre => "die besten Termine sind {0} ({1} verfügbar)",
+        Text::ClosedWith => "**{0}** ist geschlossen: {1}\n{2}",
+        Text::TimesInYourTimezone => "Zeiten sind in deiner Zeitzone, {0}",
+        Text::TimesInSetYours => "Zeiten sind in {0}; stell deine eigene mit /timezone ein",
+        Text::TimesIn => "Zeiten sind in {0}",
+        Text::InvitedOnly => "Nur Eingeladene dürfen antworten",
+        Text::RoleOnly => "Nur <@&{0}> dürfen antworten",
+        Text::AlertsDatesOnly => "Nur Terminumfragen haben Hinweise",
+        Text::AlertSet => "Du bekommst eine DM, sobald ein Termin {0} Verfügbare hat",
+        Text::AlertAlreadyMet => {
+            "Ein Termin hat schon so viele Verfügbare; du hast eine DM bekommen"
+        }
+        Text::NoFinalToWait => "Es gibt keinen festen Termin, auf den du warten kannst",
+        Text::AlreadyAttending => "Du bist schon dabei",
+        Text::LeftWaitlist => "Du hast die Warteliste verlassen",
+        Text::FinalNotFull => "Der feste Termin ist nicht voll",
+        Text::OnWaitlist => {
+            "Du bist Nr. {0} auf der Warteliste; du bekommst eine DM, wenn ein Platz frei wird"
+        }
+        Text::NoFinalToWithdraw => "Es gibt keinen festen Termin, von dem du dich abmelden kannst",
+        Text::NotOnFinal => "Du warst am festen Termin nicht verfügbar",
+        Text::Withdrawn => "Du wurdest vom festen Termin gestrichen",
+        Text::WithdrawReopened => {
+            "Zu wenige können am festen Termin, daher wurde die Terminumfrage wieder geöffnet"
+        }
+        Text::BestSoFar => "Bisher am besten: {0} ({1} verfügbar)",
+        Text::ExpectedShare => "{0}/{1} erwartet ({2} %)",
+        Text::Someone => "Jemand",
+        Text::ActivityResponded => "{0} hat geantwortet",
+        Text::ActivityWithdrew => "{0} kann nicht mehr",
+        Text::OwnerBlackedOut => "Eigentümer hat {0} gesperrt",
+        Text::RemovedDates => "entfernt: {0}",
+        Text::AddedDates => "hinzugefügt: {0}",
+        Text::WaitingForResponses => "Warte auf mehr Antworten ({0}/{1})…",
+        Text::InterestedField => "Interessiert",
+        Text::RespondedField => "Geantwortet",
+        Text::UnavailableDates => "Nicht verfügbare Termine",
+        Text::Stats => "Statistik",
+        Text::Notes => "Notizen",
+        Text::RecentActivity => "Letzte Aktivität",
+        Text::Results => "Ergebnisse",
+        Text::MoreDatesUnder => "*Weitere Termine unter {0}*",
+        Text::FinalDate => "Fester Termin: **{0}**",
+        Text::ConfirmedDate => "Termin: **{0}**",
+        Text::ScheduledIn => "Geplant: {0}",
+        Text::FromInterestCheck => "Aus der Interessenabfrage: {0}",
+        Text::PreviousScheduler => "Vorherige: {0}",
+        Text::NextScheduler => "Nächste: {0}",
+        Text::RespondWithCommand => "Antworte mit `/respond` und einem Link zu dieser Nachricht",
+        Text::ClosesAt => "Schließt <t:{0}:R>",
+        Text::ResultsHiddenUntil => {
+            "Die Ergebnisse bleiben verborgen, bis mehr geantwortet haben ({0}/{1})"
+        }
+        Text::AddBlackoutDates => "Termine sperren",
+        Text::EditRoster => "Teilnehmerliste bearbeiten",
+        Text::RemindNonResponders => "An Antwort erinnern",
+        Text::StopEditPings => "Bei Änderungen nicht mehr erwähnen",
+        Text::EditPings => "Bei Änderungen erwähnen",
+        Text::ListInOrder => "Antwortende der Reihe nach",
+        Text::ListNewestFirst => "Neueste Antworten zuerst",
+        Text::AnswerForGuest => "Für einen Gast antworten",
+        Text::StopResponseDms => "Keine DMs zu Antworten mehr",
+        Text::DmOnResponses => "DM bei Antworten",
+        Text::RankDates => "Termine ordnen",
+        Text::RespondedCount => "**Geantwortet ({0})**",
+        Text::LastUpdated => "**Zuletzt geändert**",
+        Text::EnteredBy => "{0} (eingetragen von <@{1}>)",
+        Text::NotesHeading => "**Notizen**",
+        Text::WaitlistCount => "**Warteliste ({0})**",
+        Text::CalendarConflicts => "**Konflikte mit persönlichen Kalendern**",
+        Text::Note => "Notiz",
+        Text::NoteLabel => "Was der Eigentümer wissen sollte",
+        Text::NotePlaceholder => "Kann nur bis 22 Uhr bleiben",
+        Text::RespondBeforeNote => "Antworte, bevor du eine Notiz hinzufügst",
+        Text::NoteRemoved => "Notiz entfernt",
+        Text::NoteSaved => "Notiz gespeichert",
+        Text::Confirmed => "Bestätigt",
+        Text::TakenOff => "Gestrichen",
+        Text::NothingToConfirm => "Nichts mehr zu bestätigen",
+        Text::RankedByEarliest => "**Termine ordnen** nach dem frühesten",
+        Text::RankedByRequired => "**Termine ordnen** nach Pflichtteilnehmern",
+        Text::RankedByMaybe => "**Termine ordnen** nach den wenigsten Vielleichts",
+        Text::Earliest => "Frühester",
+        Text::RequiredPlayers => "Pflichtteilnehmer",
+        Text::FewestMaybes => "Wenigste Vielleichts",
+        Text::RequiredAvailable => "{0}, {1}/{2} Pflichtteilnehmer",
+        Text::NoTies => "Keine Termine liegen gleichauf",
+        Text::PickDate => "{0} wählen",
+        Text::DateNotOffered => "Dieser Termin wird nicht mehr angeboten",
+        Text::ClosedWithFinal => "Mit {0} als festem Termin geschlossen",
+        Text::PickTopDates => "Wähle deine {0} liebsten Termine, den liebsten zuerst",
+        Text::ChoiceNumber => "Wahl Nr. {0}",
+    }
+}
+
+fn french(text: Text) -> &'static str {
+    match text {
+        Text::Interested => "Intéressé·e",
+        Text::NotInterested => "Pas intéressé·e",
+        Text::ScheduleIt => "Planifier",
+        Text::ImIn => "J'en suis",
+        Text::CantMakeIt => "Je ne peux pas",
+        Text::AddResponse => "Répondre",
+        Text::MyResponse => "Ma réponse",
+        Text::ShowDetails => "Afficher les détails",
+        Text::Close => "Clôturer",
+        Text::CantMakeItAnymore => "Je ne peux plus venir",
+        Text::JoinWaitlist => "Liste d'attente",
+        Text::Closed => "Clôturé",
+        Text::FinalResults => "Résultats finaux",
+        Text::BestDates => "Meilleures dates",
+        Text::Previous => "Précédent",
+        Text::Page => "Page",
+        Text::Next => "Suivant",
+        Text::SelectAll => "Tout sélectionner",
+        Text::ClearAll => "Tout effacer",
+        Text::QuickSelect => "Sélection rapide",
+        Text::Back => "Retour",
+        Text::Submit => "Envoyer",
+        Text::StartOver => "Recommencer",
+        Text::PickDates => {
+            "Cliquez une fois sur une date si vous êtes disponible, deux fois seulement en cas de \
+            besoin"
+        }
+        Text::PickDatesTentative => {
+            "Cliquez une fois sur une date si vous êtes disponible, deux fois si vous n'êtes pas \
+            encore sûr·e et trois fois seulement en cas de besoin"
+        }
+        Text::ResponseSubmitted => "Réponse envoyée",
+        Text::ResponseTimedOut => "Délai de réponse dépassé",
+        Text::NotResponded => "Vous n'avez pas encore répondu",
+        Text::YourResponse => "Votre réponse",
+        Text::IfNeeded => "si besoin",
+        Text::Tentative => "incertain",
+        Text::NoDates => "Vous ne pouvez à aucune des dates",
+        Text::PleaseWait => "Veuillez patienter...",
+        Text::Restarting => "Le bot redémarre, veuillez renvoyer",
+        Text::MissingScheduler => "Ce sondage est introuvable ; il a peut-être été supprimé",
+        Text::MissingServer => "Ce serveur est introuvable ; il a peut-être été supprimé",
+        Text::MissingMessage => "Ce message est introuvable ; il a peut-être été supprimé",
+        Text::NeedsFixing => "Ceci doit être corrigé dans les paramètres : {0}",
+        Text::SomethingWentWrong => "Une erreur s'est produite — réf. {0}",
+        Text::SchedulerGone => "Ce sondage n'existe plus",
+        Text::CreateDenied => {
+            "Seuls {0} et les gestionnaires du serveur peuvent créer des sondages"
+        }
+        Text::DescriptionEmpty => "La description ne peut pas être vide",
+        Text::DescriptionTooLong => {
+            "La description est trop longue ; la limite est de {0} caractères"
+        }
+        Text::BodyTooLong => "Le texte est trop long ; la limite est de {0} caractères",
+        Text::AnnouncementTooLong => "L'annonce est trop longue ; la limite est de {0} caractères",
+        Text::UnknownPlaceholder => {
+            "Espace réservé inconnu {0} ; les annonces peuvent utiliser {1}"
+        }
+        Text::UnknownTimezone => "Fuseau horaire inconnu : {0}",
+        Text::TimezoneSet => "Votre fuseau horaire est maintenant {0}",
+        Text::WeekdaysFormat => "Les jours de la semaine s'écrivent Mon ou Mon+Wed",
+        Text::DatesFormat => "Les dates s'écrivent 2024-06-01,2024-06-08",
+        Text::DaysFormat => "Les jours s'écrivent Mon+Wed+Fri ou Sat+Sun",
+        Text::TimesFormat => "Les heures s'écrivent 18:00+20:00",
+        Text::SlotFormat => {
+            "Les dates s'écrivent 2024-07-13, éventuellement avec une heure comme 2024-07-13 \
+            18:00"
+        }
+        Text::PostAtFormat => "Les heures de publication s'écrivent 2024-07-15 09:00",
+        Text::DeadlineFormat => "Les échéances s'écrivent 2024-07-15 ou 2024-07-15 18:00",
+        Text::HideResultsFormat => "Masquer les résultats attend un nombre ou un pourcentage",
+        Text::LayoutFormat => {
+            "La mise en page prend des sections parmi responded, results, stats, notes et \
+            activity"
+        }
+        Text::GiveWeekdaysOrDates => "Indiquez des jours de la semaine, des dates ou les deux",
+        Text::DatePassed => "Cette date est déjà passée",
+        Text::DatesPassed => "Ces dates sont déjà passées",
+        Text::TimePassed => "Cette heure est déjà passée",
+        Text::DeadlinePassed => "Cette échéance est déjà passée",
+        Text::TooManySlots => {
+            "Au plus {0} créneaux tiennent ; essayez moins de semaines, de dates, de jours ou \
+            d'heures"
+        }
+        Text::TooManyWeeks => "Au plus {0} créneaux tiennent ; essayez moins de semaines",
+        Text::OnlyWeeklyHaveWeeks => "Seuls les sondages hebdomadaires ont des semaines",
+        Text::NothingToChange => "Rien à modifier",
+        Text::CalendarEmpty => "Votre calendrier personnel est vide",
+        Text::CalendarShown => "Vous êtes indisponible : {0}",
+        Text::CalendarSaved => {
+            "Vous êtes indisponible : {0} ; les sondages laissent ces dates décochées quand vous \
+            répondez"
+        }
+        Text::NoPastSchedulers => "Il n'y a pas encore de sondages passés pour ce groupe",
+        Text::BestDaysByTurnout => "**Meilleurs jours selon la participation passée**",
+        Text::TemplatesPerServer => "Les modèles sont enregistrés par serveur",
+        Text::TooManyTemplates => "Un serveur peut enregistrer au plus {0} modèles",
+        Text::TemplateSaved => {
+            "Modèle **{0}** enregistré ; utilisez-le avec `/schedule from-template`"
+        }
+        Text::NoSuchTemplate => "Aucun modèle ne s'appelle {0}",
+        Text::AddOwnAvailability => {
+            "Ajoutez vos propres disponibilités ; les dates où vous ne pouvez pas sont retirées \
+            du sondage"
+        }
+        Text::AddMyAvailability => "Ajouter mes disponibilités",
+        Text::NoSchedulerToEdit => "Aucun sondage ouvert à modifier",
+        Text::NoSchedulerToWatch => "Aucun sondage ouvert à surveiller",
+        Text::NoSchedulerToExport => "Aucun sondage à exporter",
+        Text::NoSchedulerToCancel => "Aucun sondage ouvert à annuler",
+        Text::NoSchedulerToClose => "Aucun sondage ouvert à clôturer",
+        Text::NoOpenScheduler => "Aucun sondage ouvert trouvé",
+        Text::NoSourceScheduler => "Le sondage d'où prendre les dates est introuvable",
+        Text::MissingFirst => "Le premier sondage est introuvable",
+        Text::MissingSecond => "Le second sondage est introuvable",
+        Text::FirstHidingResults => "Le premier sondage masque encore ses résultats",
+        Text::SecondHidingResults => "Le second sondage masque encore ses résultats",
+        Text::SchedulerClosed => "Ce sondage est clôturé",
+        Text::SchedulerAlreadyClosed => "Ce sondage est déjà clôturé",
+        Text::AlreadyInChannel => "Ce sondage est déjà dans ce salon",
+        Text::NotAScheduler => "Ce message n'est pas un sondage",
+        Text::NotTakingResponses => "Ce sondage n'accepte pas de réponses",
+        Text::NoOpenSchedulers => "Aucun sondage ouvert",
+        Text::IntersectHeader => "Dates parmi les {0} meilleures de **{1}** et de **{2}**",
+        Text::IntersectNone => "{0} : aucune",
+        Text::NotRespondedAnywhere => "<@{0}> n'a répondu à aucun sondage ouvert ici",
+        Text::AvailabilityOf => "**Disponibilités de <@{0}>**",
+        Text::MovedTo => "Déplacé vers {0}",
+        Text::TooManyResponses => "Trop de réponses pour tenir dans un fichier",
+        Text::MatrixExplained => {
+            "Une ligne par personne : 1 signifie disponible, x signifie bloqué"
+        }
+        Text::NobodyCanMakeIt => "Personne ne peut encore venir à aucune des dates",
+        Text::IcsExplained => "Ouvrez le fichier pour ajouter les dates à votre calendrier",
+        Text::Cancelled => "Annulé",
+        Text::Cancel => "Annuler",
+        Text::Dates => "Dates",
+        Text::PostScheduled => "Ce sondage sera publié ici {0}",
+        Text::PostCancelled => "Annulé ; il ne sera pas publié",
+        Text::PostGone => "Il a déjà été publié ou annulé",
+        Text::Conflicts => {
+            "{0} des dates que vous avez modifiées l'ont aussi été ailleurs pendant votre réponse \
+            ; elles ont maintenant votre réponse"
+        }
+        Text::NotePrompt => "Quelque chose à ajouter, par exemple quand vous devez partir ?",
+        Text::AddNote => "Ajouter une note",
+        Text::BlackoutReasonPrompt => {
+            "Indiquer aux participants pourquoi des dates sont bloquées ?"
+        }
+        Text::GiveReasons => "Donner les raisons",
+        Text::NoBlackoutsToExplain => "Il n'y a aucune date bloquée à justifier",
+        Text::SelfConstraint => "Un sondage ne peut pas se contraindre lui-même",
+        Text::Linked => "Lié : {0} dates bloquées pour l'instant",
+        Text::Unlinked => "Lien supprimé",
+        Text::TitleFor => "**{0}** pour <@{1}>",
+        Text::RespondForThem => "Répondre à sa place",
+        Text::AnsweringFor => "Réponse pour *{0}*",
+        Text::GuestsNeedName => "Les invités ont besoin d'un nom",
+        Text::RosterTooLarge => "Ce fichier est trop volumineux pour une liste de joueurs",
+        Text::ClosedReply => "Clôturé !",
+        Text::FinalizePrompt => "Finaliser ?",
+        Text::Yes => "Oui",
+        Text::CancelWithoutResults => "Annuler sans résultats",
+        Text::SettingsPerServer => "Les paramètres sont propres à chaque serveur",
+        Text::ManagersOnly => "Seuls les gestionnaires du serveur peuvent modifier les paramètres",
+        Text::OperatorsOnly => "Seuls les opérateurs peuvent lancer la maintenance",
+        Text::OwnerOnlyLink => "Seul le propriétaire des deux sondages peut les lier",
+        Text::OwnerOnlyEdit => "Seul le propriétaire peut modifier",
+        Text::OwnerOnlyRebind => "Seul le propriétaire peut déplacer",
+        Text::OwnerOnlyCrossPost => "Seul le propriétaire peut publier ailleurs",
+        Text::OwnerOnlyAlerts => "Seul le propriétaire peut définir des alertes",
+        Text::OwnerOnlyAddOwners => "Seul le propriétaire peut ajouter des propriétaires",
+        Text::OwnerOnlyRequire => "Seul le propriétaire peut choisir les joueurs indispensables",
+        Text::OwnerOnlyExport => "Seul le propriétaire peut exporter les réponses",
+        Text::ResultsHidden => "Les résultats restent masqués tant que trop peu ont répondu",
+        Text::OwnerOnlyCancel => "Seul le propriétaire peut annuler",
+        Text::OwnerOnlyClose => "Seul le propriétaire peut clôturer",
+        Text::OwnerOnlyRespondForOthers => "Seul le propriétaire peut répondre pour les autres",
+        Text::OwnerOnlyTakeDatesOff => "Seul le propriétaire peut retirer des dates",
+        Text::OwnerOnlySchedule => "Seul le propriétaire peut planifier",
+        Text::OwnerOnlyRoster => "Seul le propriétaire peut modifier la liste des joueurs",
+        Text::OwnerOnlyGuests => "Seul le propriétaire peut répondre pour des invités",
+        Text::OwnerOnlyBlackoutReasons => "Seul le propriétaire peut justifier les dates bloquées",
+        Text::OwnerOnlyRemind => "Seul le propriétaire peut envoyer des rappels",
+        Text::OwnerOnlyPings => "Seul le propriétaire peut modifier les mentions",
+        Text::OwnerOnlyNotify => "Seul le propriétaire peut être notifié des réponses",
+        Text::OwnerOnlyRecency => "Seul le propriétaire peut réordonner les participants",
+        Text::OwnerOnlyRank => "Seul le propriétaire peut classer les dates",
+        Text::OwnerOnlyPick => "Seul le propriétaire peut choisir la date",
+        Text::OwnerOnlyDigest => "Seul le propriétaire peut agir sur les résultats",
+        Text::NobodyMissing => "Aucune personne attendue n'a oublié de répondre",
+        Text::Reminded => "{0} joueurs relancés",
+        Text::ReminderQueued => "Le rappel à {0} joueurs sera envoyé après les heures calmes",
+        Text::ReminderLine => "Rappel : merci de répondre à **{0}** {1}",
+        Text::EditPingsOn => "Les modifications mentionneront à nouveau tout le monde",
+        Text::EditPingsOff => "Seule la première publication mentionne désormais tout le monde",
+        Text::NotifyOn => {
+            "Vous recevrez un MP chaque fois que quelqu'un répond ou modifie sa réponse"
+        }
+        Text::NotifyOff => "Vous ne recevrez plus de MP pour chaque réponse",
+        Text::RecencyOn => "Les participants sont désormais listés du plus récent au plus ancien",
+        Text::RecencyOff => "Les participants sont désormais listés dans un ordre fixe",
+        Text::HasClosed => "**{0}** est clôturé : {1}",
+        Text::DigestInterested => "Intéressé·es : {0}",
+        Text::NoResponsesReceived => "Aucune réponse n'a été reçue.",
+        Text::DigestBestDates => "Meilleures dates ({0} disponibles) : {1}",
+        Text::ThresholdMet => "{0} dates avaient au moins {1} disponibles.",
+        Text::EveryoneResponded => "Toutes les personnes attendues ont répondu.",
+        Text::NotRespondedList => "Sans réponse : {0}",
+        Text::FinalizeBest => "Finaliser la meilleure",
+        Text::Extend => "Prolonger",
+        Text::Reopen => "Rouvrir",
+        Text::Finalize => "Finaliser",
+        Text::Finalized => "{0} finalisée",
+        Text::NoDatesToFinalize => "Il n'y a aucune date à finaliser",
+        Text::Extended => "Prolongé d'une semaine et rouvert",
+        Text::CannotExtend => "Impossible de prolonger davantage",
+        Text::Reopened => "Rouvert",
+        Text::AlertReached => "**{0}** : {1} disponibles le {2}\n{3}",
+        Text::RespondedTo => "<@{0}> a répondu à **{1}** : {2}\n{3}",
+        Text::ChangedResponse => "<@{0}> a modifié sa réponse à **{1}** : {2}\n{3}",
+        Text::SameDates => "mêmes dates",
+        Text::NoDatesPicked => "aucune date",
+        Text::Maybe => "{0} (peut-être)",
+        Text::SpotOpened => {
+            "Une place s'est libérée le {0} pour **{1}**, vous y participez donc : {2}"
+        }
+        Text::FellThrough => {
+            "**{0}** le {1} n'aura pas lieu ({2}/{3} peuvent encore venir), le sondage a donc été \
+            rouvert sans cette date : {4}\n{5}"
+        }
+        Text::FirmUpAsk => {
+            "Vous avez marqué {0} comme incertain pour **{1}** : {2}\nPouvez-vous venir ?"
+        }
+        Text::ConfirmTentative => "Oui, confirmer",
+        Text::DropTentative => "Non, les retirer",
+        Text::NobodyCouldMakeIt => "personne ne pouvait venir à aucune des dates",
+        Text::BestDateIs => "la meilleure date est {0} ({1} disponibles)",
+        Text::BestDatesAre => "les meilleures dates sont {0} ({1} disponibles)",
+        Text::ClosedWith => "**{0}** est clôturé : {1}\n{2}",
+        Text::TimesInYourTimezone => "Les heures sont dans votre fuseau horaire, {0}",
+        Text::TimesInSetYours => "Les heures sont en {0} ; choisissez le vôtre avec /timezone",
+        Text::TimesIn => "Les heures sont en {0}",
+        Text::InvitedOnly => "Seuls les joueurs invités peuvent répondre",
+        Text::RoleOnly => "Seul·es les <@&{0}> peuvent répondre",
+        Text::AlertsDatesOnly => "Seuls les sondages de dates ont des alertes",
+        Text::AlertSet => "Vous recevrez un MP dès qu'une date aura {0} disponibles",
+        Text::AlertAlreadyMet => "Une date a déjà autant de disponibles ; vous avez reçu un MP",
+        Text::NoFinalToWait => "Il n'y a pas de date finale à attendre",
+        Text::AlreadyAttending => "Vous participez déjà",
+        Text::LeftWaitlist => "Vous avez quitté la liste d'attente",
+        Text::FinalNotFull => "La date finale n'est pas complète",
+        Text::OnWaitlist => {
+            "Vous êtes n° {0} sur la liste d'attente ; vous recevrez un MP si une place se libère"
+        }
+        Text::NoFinalToWithdraw => "Il n'y a pas de date finale dont vous retirer",
+        Text::NotOnFinal => "Vous n'étiez pas disponible à la date finale",
+        Text::Withdrawn => "Vous avez été retiré·e de la date finale",
+        Text::WithdrawReopened => {
+            "Trop peu peuvent venir à la date finale, le sondage a donc été rouvert"
+        }
+        Text::BestSoFar => "Meilleure pour l'instant : {0} ({1} disponibles)",
+        Text::ExpectedShare => "{0}/{1} attendus ({2} %)",
+        Text::Someone => "Quelqu'un",
+        Text::ActivityResponded => "{0} a répondu",
+        Text::ActivityWithdrew => "{0} ne peut plus venir",
+        Text::OwnerBlackedOut => "Le propriétaire a bloqué {0}",
+        Text::RemovedDates => "retiré {0}",
+        Text::AddedDates => "ajouté {0}",
+        Text::WaitingForResponses => "En attente de plus de réponses ({0}/{1})…",
+        Text::InterestedField => "Intéressé·es",
+        Text::RespondedField => "Ont répondu",
+        Text::UnavailableDates => "Dates indisponibles",
+        Text::Stats => "Statistiques",
+        Text::Notes => "Notes",
+        Text::RecentActivity => "Activité récente",
+        Text::Results => "Résultats",
+        Text::MoreDatesUnder => "*Plus de dates sous {0}*",
+        Text::FinalDate => "Date finale : **{0}**",
+        Text::ConfirmedDate => "Date : **{0}**",
+        Text::ScheduledIn => "Planifié : {0}",
+        Text::FromInterestCheck => "Issu du sondage d'intérêt : {0}",
+        Text::PreviousScheduler => "Précédent : {0}",
+        Text::NextScheduler => "Suivant : {0}",
+        Text::RespondWithCommand => "Répondez avec `/respond` en donnant un lien vers ce message",
+        Text::ClosesAt => "Clôture <t:{0}:R>",
+        Text::ResultsHiddenUntil => {
+            "Les résultats restent masqués tant que trop peu ont répondu ({0}/{1})"
+        }
+        Text::AddBlackoutDates => "Bloquer des dates",
+        Text::EditRoster => "Modifier la liste de joueurs",
+        Text::RemindNonResponders => "Relancer les retardataires",
+        Text::StopEditPings => "Ne plus mentionner aux modifications",
+        Text::EditPings => "Mentionner aux modifications",
+        Text::ListInOrder => "Lister les participants dans l'ordre",
+        Text::ListNewestFirst => "Réponses récentes en premier",
+        Text::AnswerForGuest => "Répondre pour un invité",
+        Text::StopResponseDms => "Arrêter les MP de réponses",
+        Text::DmOnResponses => "MP à chaque réponse",
+        Text::RankDates => "Classer les dates",
+        Text::RespondedCount => "**Ont répondu ({0})**",
+        Text::LastUpdated => "**Dernières modifications**",
+        Text::EnteredBy => "{0} (saisi par <@{1}>)",
+        Text::NotesHeading => "**Notes**",
+        Text::WaitlistCount => "**Liste d'attente ({0})**",
+        Text::CalendarConflicts => "**Conflits avec les calendriers personnels**",
+        Text::Note => "Note",
+        Text::NoteLabel => "Ce que le propriétaire devrait savoir",
+        Text::NotePlaceholder => "Je ne peux rester que jusqu'à 22 h",
+        Text::RespondBeforeNote => "Répondez avant d'ajouter une note",
+        Text::NoteRemoved => "Note supprimée",
+        Text::NoteSaved => "Note enregistrée",
+        Text::Confirmed => "Confirmé",
+        Text::TakenOff => "Retiré",
+        Text::NothingToConfirm => "Plus rien à confirmer",
+        Text::RankedByEarliest => "**Classer les dates** par la plus proche",
+        Text::RankedByRequired => "**Classer les dates** par joueurs indispensables",
+        Text::RankedByMaybe => "**Classer les dates** par le moins de peut-être",
+        Text::Earliest => "La plus proche",
+        Text::RequiredPlayers => "Joueurs indispensables",
+        Text::FewestMaybes => "Le moins de peut-être",
+        Text::RequiredAvailable => "{0}, {1}/{2} indispensables",
+        Text::NoTies => "Aucune date n'est à égalité",
+        Text::PickDate => "Choisir {0}",
+        Text::DateNotOffered => "Cette date n'est plus proposée",
+        Text::ClosedWithFinal => "Clôturé avec {0} comme date finale",
+        Text::PickTopDates => "Choisissez vos {0} dates préférées, la favorite d'abord",
+        Text::ChoiceNumber => "Choix n° {0}",
+    }
+}
+
+fn with_time(date: String, slot: Slot) -> String {
+    match slot.time_label() {
+        Some(time) => format!("{} {}", date, time),
+        None => date,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saturday(time: Option<&str>) -> Slot {
+        Slot {
+            date: NaiveDate::from_ymd_opt(2024, 7, 13).unwrap(),
+            time: time.map(|t| NaiveTime::parse_from_str(t, "%H:%M").unwrap()),
+        }
+    }
+
+    fn style(locale: Locale, format: Option<DateFormat>) -> DateStyle {
+        DateStyle {
+            locale,
+            format,
+            timezone: chrono_tz::UTC,
+        }
+    }
+
+    #[test]
+    fn locales_are_parsed_by_language() {
+        assert!(Locale::parse("de") == Some(Locale::De));
+        assert!(Locale::parse("pt-BR") == Some(Locale::Pt));
+        assert!(Locale::parse("EN_us") == Some(Locale::En));
+        assert!(Locale::parse("ja").is_none());
+    }
+
+    #[test]
+    fn date_formats_are_discord_or_valid_patterns() {
+        assert!(DateFormat::parse("Discord") == Some(DateFormat::Discord));
+        assert!(
+            DateFormat::parse("%a %d.%m.") == Some(DateFormat::Pattern("%a %d.%m.".to_owned()))
+        );
+        assert!(DateFormat::parse("dd.mm.").is_none());
+        assert!(DateFormat::parse("%d.%Q").is_none());
+    }
+
+    #[test]
+    fn dates_are_written_in_the_locale() {
+        let slot = saturday(Some("18:00"));
+        assert_eq!(style(Locale::En, None).short(slot), "Sat Jul 13 18:00");
+        assert_eq!(style(Locale::De, None).short(slot), "Sa 13. Jul 18:00");
+        assert_eq!(style(Locale::Fr, None).short(slot), "sam. 13 juil. 18:00");
+        assert_eq!(style(Locale::En, None).long(slot), "Sat 2024-07-13 18:00");
+        assert_eq!(
+            style(Locale::De, None).long(saturday(None)),
+            "Sa 2024-07-13"
+        );
+    }
+
+    #[test]
+    fn patterns_use_the_locale_for_names() {
+        let pattern = DateFormat::parse("%a %d.%m.");
+        assert_eq!(
+            style(Locale::De, pattern.clone()).short(saturday(None)),
+            "Sa 13.07."
+        );
+        assert_eq!(
+            style(Locale::En, pattern.clone()).long(saturday(Some("18:00"))),
+            "Sat 13.07. 18:00"
+        );
+        // Exports stay machine-readable unless a pattern is set
+        assert_eq!(style(Locale::De, None).export(saturday(None)), "2024-07-13");
+        assert_eq!(
+            style(Locale::De, pattern).export(saturday(None)),
+            "Sa 13.07."
+        );
+    }
+
+    #[test]
+    fn discord_timestamps_keep_whole_days_on_their_date() {
+        let discord = style(Locale::En, Some(DateFormat::Discord));
+        assert_eq!(
+            discord.timestamp(saturday(Some("18:00"))).unwrap(),
+            "<t:1720893600:f>"
+        );
+        assert_eq!(discord.code(saturday(None)), "<t:1720872000:D>");
+        assert!(style(Locale::En, None).timestamp(saturday(None)).is_none());
+        assert_eq!(
+            style(Locale::En, None).code(saturday(None)),
+            "`Sat 2024-07-13`"
+        );
+    }
+
+    #[test]
+    fn fill_replaces_placeholders_in_one_pass() {
+        assert_eq!(
+            Locale::En.fill(Text::UnknownPlaceholder, &[&"{1}", &"{title}"]),
+            "Unknown placeholder {1}; announcements can use {title}"
+        );
+        // Placeholders without a value are left as they are
+        assert_eq!(
+            Locale::En.fill(Text::UnknownPlaceholder, &[&"{x}"]),
+            "Unknown placeholder {x}; announcements can use {1}"
+        );
+        assert_eq!(
+            Locale::De.fill(Text::BodyTooLong, &[&100]),
+            "Der Text ist zu lang; erlaubt sind 100 Zeichen"
+        );
+    }
+
+    #[test]
+    fn untranslated_languages_fall_back_to_english() {
+        assert_eq!(
+            Locale::Es.text(Text::OwnerOnlyDigest),
+            Locale::En.text(Text::OwnerOnlyDigest)
+        );
+        assert_eq!(
+            Locale::Es.phrase(&Phrase::new(Text::BodyTooLong, &[&5])),
+            "Body is too long; the limit is 5 characters"
+        );
+    }
+}
@@ -1,114 +1,533 @@
+mod actor;
+mod admin;
+mod announce;
+mod api;
+mod debounce;
+mod error;
+mod events;
+mod gcal;
+mod guild_config;
+#[cfg(any(test, feature = "test-harness"))]
+mod harness;
+mod heatmap;
+mod history;
+mod ical;
+mod limits;
+mod locale;
 mod message_shim;
+mod notify;
+mod posts;
+mod render;
+mod responder;
+mod roster;
 mod scheduler;
-use crate::scheduler::{ResponseType, Scheduler};
+mod shutdown;
+mod slot;
+mod storage;
+mod tasks;
+mod timezones;
+mod unavailable;
+mod validate;
+mod webhook;
+use crate::actor::SchedulerHandle;
+use crate::admin::Operation;
+use crate::debounce::Debouncer;
+use crate::error::{Error, Result, Tier};
+use crate::events::Venue;
+use crate::guild_config::{GuildConfig, Setting, Template};
+use crate::locale::{DateFormat, DateStyle, Locale, Phrase, Text};
+use crate::message_shim::MessageShim;
+use crate::notify::Notifier;
+use crate::posts::{Posts, ScheduledPost};
+use crate::render::DetailsLayout;
+use crate::responder::Responder;
+use crate::scheduler::{
+    BlackoutPolicy, Changes, Constraint, ConstraintKind, Embargo, Options, Prompt, ResponseType,
+    Scheduler, TieBreak,
+};
+use crate::shutdown::Shutdown;
+use crate::slot::Slot;
+use crate::storage::{SqliteStorage, Storage};
+use crate::unavailable::Calendar;
 
-use chrono::Weekday;
+use chrono::{DateTime, Local, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
 use clap::Parser;
+use dashmap::DashMap;
 use dotenv::dotenv;
-use log::{error, info};
+use futures::FutureExt;
 use serenity::async_trait;
+use serenity::builder::{
+    CreateActionRow, CreateAllowedMentions, CreateAttachment, CreateButton, CreateCommand,
+    CreateCommandOption, CreateEmbed, CreateInteractionResponse, CreateInteractionResponseFollowup,
+    CreateInteractionResponseMessage, CreateMessage, EditInteractionResponse,
+};
 use serenity::client::{Context, EventHandler};
-use serenity::json::Value;
-use serenity::model::gateway::Ready;
-use serenity::model::id::{ChannelId, GuildId, MessageId, RoleId};
-use serenity::model::interactions::application_command::{
-    ApplicationCommand, ApplicationCommandInteraction, ApplicationCommandOptionType,
+use serenity::model::application::CommandDataOptionValue as Value;
+use serenity::model::application::{
+    ButtonStyle, Command, CommandDataOption, CommandInteraction, CommandOptionType, CommandType,
+    ComponentInteraction, Interaction, ModalInteraction,
 };
-use serenity::model::interactions::message_component::MessageComponentInteraction;
-use serenity::model::interactions::{Interaction, InteractionResponseType};
+use serenity::model::channel::{ChannelType, Message, Reaction, ReactionType};
+use serenity::model::gateway::Ready;
+use serenity::model::guild::{Guild, UnavailableGuild};
+use serenity::model::id::{ChannelId, GuildId, MessageId, RoleId, UserId};
+use serenity::model::permissions::Permissions;
 use serenity::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::env;
-use std::fs::File;
-use std::ops::{Deref, DerefMut};
-use std::panic;
-use std::path::{Path, PathBuf};
+use std::panic::{self, AssertUnwindSafe};
 use std::str::FromStr;
-use tokio::sync::{RwLock, RwLockMappedWriteGuard, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{error, info, info_span, Instrument, Span};
+use tracing_subscriber::EnvFilter;
 
 const DATA_DIR: &str = "data";
-const MAX_WEEKS: usize = 10;
+const MAX_WEEKS: usize = 26;
+/// Weeks offered when a scheduler doesn't say
+pub const DEFAULT_WEEKS: i64 = 10;
+const EXPORT_FILENAME: &str = "schedule.ics";
+// Keeps the Responded fields well within the embed's total length
+const MAX_MENTION_LIMIT: usize = 100;
 
-// All mutable accesses to Handler.schedulers go through this wrapper, which dumps the data to disk
-// when it is dropped.
-struct ScheduleWrapper<'a> {
-    message_id: MessageId,
-    scheduler: RwLockMappedWriteGuard<'a, Scheduler>,
+type Schedulers = DashMap<MessageId, SchedulerHandle>;
+/// Date messages taking reactions, to the scheduler message they belong to
+type DateMessages = DashMap<MessageId, MessageId>;
+/// Copies of scheduler messages posted elsewhere, to the scheduler message they copy
+type Mirrors = DashMap<MessageId, MessageId>;
+/// Schedulers taking blackouts from another, by the scheduler they take them from
+type Constrained = DashMap<MessageId, HashSet<MessageId>>;
+
+struct Handler {
+    refresh: bool,
+    tasks_started: AtomicBool,
+    // All accesses to a scheduler go through its handle; see the actor module
+    schedulers: Arc<Schedulers>,
+    date_messages: Arc<DateMessages>,
+    mirrors: Mirrors,
+    constrained: Constrained,
+    storage: Arc<dyn Storage>,
+    guild_configs: Arc<RwLock<HashMap<GuildId, GuildConfig>>>,
+    timezones: RwLock<HashMap<UserId, Tz>>,
+    /// When users are never available, across every scheduler
+    calendars: RwLock<HashMap<UserId, Calendar>>,
+    posts: Posts,
+    /// Users allowed to run maintenance commands
+    operators: HashSet<UserId>,
+    max_title_length: usize,
+    shutdown: Arc<Shutdown>,
 }
 
-impl<'a> Deref for ScheduleWrapper<'a> {
-    type Target = Scheduler;
+/// A span with the ids `interaction` concerns, so everything logged while handling it can be
+/// filtered by guild, channel, message or user
+fn interaction_span(interaction: &Interaction, reference: &str) -> Span {
+    let (guild, channel, message, user) = match interaction {
+        Interaction::Command(command) => (
+            command.guild_id,
+            Some(command.channel_id),
+            None,
+            Some(command.user.id),
+        ),
+        Interaction::Component(component) => (
+            component.guild_id,
+            Some(component.channel_id),
+            Some(component.message.id),
+            Some(component.user.id),
+        ),
+        Interaction::Modal(modal) => (
+            modal.guild_id,
+            Some(modal.channel_id),
+            None,
+            Some(modal.user.id),
+        ),
+        _ => (None, None, None, None),
+    };
+    info_span!(
+        "interaction",
+        reference,
+        guild = guild.map(|id| id.get()),
+        channel = channel.map(|id| id.get()),
+        message = message.map(|id| id.get()),
+        user = user.map(|id| id.get()),
+    )
+}
+
+/// Respond to a creation command with the message that will hold the scheduler
+async fn post_placeholder(ctx: &Context, command: &CommandInteraction) -> Result<Message> {
+    let locale = Locale::parse(&command.locale).unwrap_or_default();
+    command
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new().content(locale.text(Text::PleaseWait)),
+            ),
+        )
+        .await?;
+    Ok(command.get_response(ctx).await?)
+}
 
-    fn deref(&self) -> &Self::Target {
-        &*self.scheduler
+/// Respond to a button with the message that will hold a new scheduler
+async fn post_component_placeholder(
+    ctx: &Context,
+    component: &ComponentInteraction,
+) -> Result<Message> {
+    let locale = Locale::parse(&component.locale).unwrap_or_default();
+    component
+        .create_response(
+            &ctx.http,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new().content(locale.text(Text::PleaseWait)),
+            ),
+        )
+        .await?;
+    Ok(component.get_response(ctx).await?)
+}
+
+/// Fill in a deferred ephemeral reply with `lines`, following up with whatever doesn't fit
+async fn edit_in_chunks(
+    ctx: &Context,
+    command: &CommandInteraction,
+    lines: impl IntoIterator<Item = String>,
+) -> Result {
+    let mut chunks = limits::chunk_lines(lines, limits::MESSAGE_LENGTH).into_iter();
+    if let Some(first) = chunks.next() {
+        command
+            .edit_response(ctx, EditInteractionResponse::new().content(first))
+            .await?;
+    }
+    for chunk in chunks {
+        command
+            .create_followup(
+                ctx,
+                CreateInteractionResponseFollowup::new()
+                    .content(chunk)
+                    .ephemeral(true),
+            )
+            .await?;
     }
+    Ok(())
+}
+
+/// The template a command names; names ignore case
+fn template_name(options: &HashMap<&str, &Value>) -> String {
+    options
+        .get("name")
+        .expect("Cannot find name option")
+        .as_str()
+        .expect("Name has incorrect type")
+        .trim()
+        .to_lowercase()
 }
 
-impl<'a> DerefMut for ScheduleWrapper<'a> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut *self.scheduler
+/// The options given to a subcommand
+pub(crate) fn subcommand_options(option: &CommandDataOption) -> &[CommandDataOption] {
+    match &option.value {
+        Value::SubCommand(options) | Value::SubCommandGroup(options) => options,
+        _ => &[],
     }
 }
 
-impl<'a> Drop for ScheduleWrapper<'a> {
-    fn drop(&mut self) {
-        write_file(&self.message_id, self);
+/// The language of the client an interaction came from, for replies only its user sees
+fn interaction_locale(interaction: &Interaction) -> Locale {
+    let locale = match interaction {
+        Interaction::Command(command) | Interaction::Autocomplete(command) => &command.locale,
+        Interaction::Component(component) => &component.locale,
+        Interaction::Modal(modal) => &modal.locale,
+        _ => return Locale::default(),
+    };
+    Locale::parse(locale).unwrap_or_default()
+}
+
+/// Tell the user why their interaction failed
+async fn report_error(ctx: &Context, interaction: &Interaction, msg: &str, reference: &str) {
+    let result = match interaction {
+        Interaction::Command(command) => {
+            let response = command
+                .create_response(
+                    ctx,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content(msg)
+                            .ephemeral(true),
+                    ),
+                )
+                .await;
+            match response {
+                Ok(()) => Ok(()),
+                // The interaction was already acknowledged
+                Err(_) => command
+                    .create_followup(
+                        ctx,
+                        CreateInteractionResponseFollowup::new()
+                            .content(msg)
+                            .ephemeral(true),
+                    )
+                    .await
+                    .map(|_| ()),
+            }
+        }
+        Interaction::Component(component) => {
+            let response = component
+                .create_response(
+                    ctx,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content(msg)
+                            .ephemeral(true),
+                    ),
+                )
+                .await;
+            match response {
+                Ok(()) => Ok(()),
+                Err(_) => component
+                    .create_followup(
+                        ctx,
+                        CreateInteractionResponseFollowup::new()
+                            .content(msg)
+                            .ephemeral(true),
+                    )
+                    .await
+                    .map(|_| ()),
+            }
+        }
+        Interaction::Modal(modal) => {
+            modal
+                .create_response(
+                    ctx,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .content(msg)
+                            .ephemeral(true),
+                    ),
+                )
+                .await
+        }
+        _ => Ok(()),
+    };
+    if let Err(e) = result {
+        error!("[{}] Cannot report error: {}", reference, e);
     }
 }
 
-#[derive(Default)]
-struct Handler {
-    refresh: bool,
-    schedulers: RwLock<HashMap<MessageId, Scheduler>>,
+/// The message id in a link to a scheduler message, or the id itself
+fn scheduler_id(link: &str) -> Option<MessageId> {
+    link.rsplit('/')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+        .map(MessageId::new)
 }
 
-async fn send_error(ctx: &Context, command: &ApplicationCommandInteraction, msg: &str) {
-    command
-        .create_interaction_response(ctx, |c| {
-            c.kind(InteractionResponseType::ChannelMessageWithSource)
-                .interaction_response_data(|m| m.content(msg).ephemeral(true))
+/// Render a scheduler whose creation stopped short of its message, marking it done if that works
+async fn render_pending(ctx: &Context, handle: &SchedulerHandle) {
+    let ctx = ctx.clone();
+    handle
+        .update(move |scheduler| {
+            Box::pin(async move {
+                if scheduler.update_message_now(&ctx).await {
+                    scheduler.set_pending(false);
+                }
+            })
         })
-        .await
-        .expect("Cannot send error response");
+        .await;
 }
 
-fn read_file(path: &Path) -> Option<(u64, Scheduler)> {
-    let extension = path.extension().and_then(|e| e.to_str());
-    if !matches!(extension, Some("json")) {
-        return None;
+/// Save a new scheduler, then render it over its placeholder message. The placeholder is
+/// deleted if the scheduler cannot be saved; rendering is retried at startup if it fails.
+async fn insert_scheduler(
+    ctx: &Context,
+    schedulers: &Schedulers,
+    storage: &Arc<dyn Storage>,
+    message_id: MessageId,
+    mut scheduler: Scheduler,
+) -> Option<Scheduler> {
+    scheduler.set_pending(true);
+    let (mut scheduler, result) = storage::save(storage, message_id, scheduler).await;
+    if let Err(e) = result {
+        error!("Cannot save new scheduler {}: {}", message_id, e);
+        scheduler
+            .channel_id()
+            .delete_message(ctx, message_id)
+            .await
+            .map_err(|e| error!("Cannot delete placeholder: {}", e))
+            .ok();
+        scheduler.set_pending(false);
+        return Some(scheduler);
     }
-    let id: u64 = path
-        .file_stem()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .parse()
-        .expect("Cannot parse file name");
-    let file = File::open(path).expect("Cannot open file");
-    Some((
-        id,
-        serde_json::from_reader(file).expect("Cannot parse data"),
-    ))
+    let handle = SchedulerHandle::spawn(message_id, scheduler, storage.clone());
+    schedulers.insert(message_id, handle.clone());
+    render_pending(ctx, &handle).await;
+    None
+}
+
+/// Post a message per date of a scheduler created with reactions, remembering which scheduler
+/// they belong to
+async fn post_date_messages(
+    ctx: &Context,
+    schedulers: &Schedulers,
+    date_messages: &DateMessages,
+    message_id: MessageId,
+) {
+    // Missing if the scheduler couldn't be saved
+    let handle = match schedulers.get(&message_id) {
+        Some(handle) => handle.clone(),
+        None => return,
+    };
+    let ctx = ctx.clone();
+    let posted = handle
+        .update(move |scheduler| Box::pin(async move { scheduler.post_date_messages(&ctx).await }))
+        .await
+        .unwrap_or_default();
+    for id in posted {
+        date_messages.insert(id, message_id);
+    }
+}
+
+/// Open a thread off a new scheduler's message for its activity
+async fn start_thread(ctx: &Context, schedulers: &Schedulers, message_id: MessageId) {
+    // Missing if the scheduler couldn't be saved
+    let handle = match schedulers.get(&message_id) {
+        Some(handle) => handle.clone(),
+        None => return,
+    };
+    let ctx = ctx.clone();
+    handle
+        .update(move |scheduler| Box::pin(async move { scheduler.start_thread(&ctx).await }))
+        .await;
 }
 
-fn file_path(id: &MessageId) -> PathBuf {
-    let mut path: PathBuf = DATA_DIR.into();
-    path.push(id.as_u64().to_string());
-    path.set_extension("json");
-    path
+/// Post the next generation of a recurring scheduler whose dates have all passed, closing it
+/// first if it is still open
+async fn roll_over(
+    ctx: &Context,
+    schedulers: &Schedulers,
+    storage: &Arc<dyn Storage>,
+    handle: SchedulerHandle,
+) {
+    let state = handle.get(|s| (s.channel_id(), s.has_thread(), s.date_style().locale));
+    let (channel, thread, locale) = match state.await {
+        Some(found) => found,
+        None => return,
+    };
+    let message = match channel
+        .send_message(
+            ctx,
+            CreateMessage::new().content(locale.text(Text::PleaseWait)),
+        )
+        .await
+    {
+        Ok(message) => message,
+        Err(e) => {
+            error!("Cannot post rolled over scheduler: {}", e);
+            return;
+        }
+    };
+    let message_id = message.id;
+    let c = ctx.clone();
+    let scheduler = handle
+        .update(move |s| {
+            Box::pin(async move {
+                if !s.is_closed() {
+                    s.close(&c).await;
+                }
+                let scheduler = s.roll_over(message);
+                s.update_message(&c).await;
+                scheduler
+            })
+        })
+        .await;
+    let scheduler = match scheduler {
+        Some(scheduler) => scheduler,
+        None => return,
+    };
+    info!("rolled over to scheduler {}", message_id);
+    insert_scheduler(ctx, schedulers, storage, message_id, scheduler).await;
+    if thread {
+        start_thread(ctx, schedulers, message_id).await;
+    }
 }
 
-fn write_file(id: &MessageId, scheduler: &Scheduler) {
-    let file = File::create(file_path(id)).expect("Cannot create file");
-    serde_json::to_writer(file, &scheduler).expect("Cannot serialize data");
+/// Post a scheduler that was prepared to go out later
+async fn post_scheduled(
+    ctx: &Context,
+    schedulers: &Schedulers,
+    date_messages: &DateMessages,
+    storage: &Arc<dyn Storage>,
+    post: ScheduledPost,
+) {
+    let placeholder = CreateMessage::new().content(post.options.locale.text(Text::PleaseWait));
+    let message = match post.channel.send_message(ctx, placeholder).await {
+        Ok(message) => message,
+        Err(e) => {
+            error!("Cannot post scheduled scheduler {}: {}", post.id, e);
+            return;
+        }
+    };
+    info!("posted scheduled scheduler {} as {}", post.id, message.id);
+    let message_id = message.id;
+    let reactions = post.options.reactions;
+    let thread = post.options.thread;
+    let scheduler = Scheduler::new(post.owner, post.guild, message, post.options);
+    insert_scheduler(ctx, schedulers, storage, message_id, scheduler).await;
+    if reactions {
+        post_date_messages(ctx, schedulers, date_messages, message_id).await;
+    }
+    if thread {
+        start_thread(ctx, schedulers, message_id).await;
+    }
 }
 
-fn delete_file(id: &MessageId) {
-    std::fs::remove_file(file_path(id)).expect("Cannot delete file");
+/// Delete everything stored for `guild`
+async fn purge_guild(
+    schedulers: &Schedulers,
+    storage: &Arc<dyn Storage>,
+    posts: &Posts,
+    guild_configs: &mut HashMap<GuildId, GuildConfig>,
+    guild: GuildId,
+) {
+    let ids: Vec<MessageId> = schedulers
+        .iter()
+        .filter(|h| h.guild() == Some(guild))
+        .map(|h| *h.key())
+        .collect();
+    for id in ids.iter() {
+        if let Some((_, handle)) = schedulers.remove(id) {
+            handle.stop().await;
+        }
+    }
+    let live = ids.clone();
+    storage::blocking(storage, move |storage| {
+        for id in live {
+            if let Err(e) = storage.delete(id) {
+                error!("Cannot delete scheduler {}: {}", id, e);
+            }
+        }
+        for (id, _) in storage.archived(Some(guild)) {
+            if let Err(e) = storage.delete_archived(id) {
+                error!("Cannot delete archived scheduler {}: {}", id, e);
+            }
+        }
+    })
+    .await;
+    if guild_configs.remove(&guild).is_some() {
+        if let Err(e) = guild_config::delete_file(guild) {
+            error!("Cannot delete config of guild {}: {}", guild, e);
+        }
+    }
+    let posts = posts.purge_guild(guild).await;
+    info!(
+        "purged guild {}: {} schedulers, {} scheduled posts",
+        guild,
+        ids.len(),
+        posts
+    );
 }
 
 impl Handler {
-    fn new(refresh: bool) -> Self {
+    async fn new(refresh: bool, operators: HashSet<UserId>, max_title_length: usize) -> Self {
         let data_dir = std::fs::metadata(DATA_DIR);
         let is_dir = match data_dir {
             Ok(f) => f.is_dir(),
@@ -118,297 +537,4405 @@ impl Handler {
             std::fs::create_dir(DATA_DIR).expect("Cannot create data dir");
         }
 
-        let mut schedulers: HashMap<MessageId, Scheduler> = HashMap::default();
-        for f in std::fs::read_dir(DATA_DIR).expect("Cannot read data dir") {
-            let path = f.unwrap().path();
-            if let Some((id, s)) = read_file(&path) {
-                schedulers.insert(id.into(), s);
+        let handler = Handler {
+            refresh,
+            tasks_started: AtomicBool::new(false),
+            schedulers: Arc::default(),
+            date_messages: Arc::default(),
+            mirrors: Default::default(),
+            constrained: Default::default(),
+            storage: Arc::new(SqliteStorage::open()),
+            guild_configs: Arc::new(RwLock::new(guild_config::load_all())),
+            timezones: RwLock::new(timezones::load()),
+            calendars: RwLock::new(unavailable::load()),
+            posts: Posts::load(),
+            operators,
+            max_title_length,
+            shutdown: Arc::default(),
+        };
+        handler.load(false).await;
+        handler
+    }
+
+    /// Start the stored schedulers that are closed, or open ones, indexing their date messages,
+    /// copies and constraints
+    async fn load(&self, closed: bool) {
+        let loaded = storage::load_parallel(self.storage.clone(), closed).await;
+        let count = loaded.len();
+        for (id, s) in loaded {
+            for date_message in s.date_message_ids() {
+                self.date_messages.insert(date_message, id);
+            }
+            for mirror in s.mirror_ids() {
+                self.mirrors.insert(mirror, id);
             }
+            if let Some(constraint) = s.constraint() {
+                self.constrained
+                    .entry(constraint.source)
+                    .or_default()
+                    .insert(id);
+            }
+            let handle = SchedulerHandle::spawn(id, s, self.storage.clone());
+            self.schedulers.insert(id, handle);
         }
-        info!("{} schedulers loaded", schedulers.len());
+        info!(
+            "{} {} schedulers loaded",
+            count,
+            if closed { "closed" } else { "open" }
+        );
+    }
 
+    /// A handler with nothing loaded, keeping its schedulers in memory
+    #[cfg(test)]
+    fn in_memory() -> Self {
         Handler {
-            refresh,
-            schedulers: RwLock::new(schedulers),
+            refresh: false,
+            tasks_started: AtomicBool::new(false),
+            schedulers: Arc::default(),
+            date_messages: Arc::default(),
+            mirrors: Mirrors::default(),
+            constrained: Constrained::default(),
+            storage: Arc::new(SqliteStorage::in_memory()),
+            guild_configs: Arc::default(),
+            timezones: RwLock::default(),
+            calendars: RwLock::default(),
+            posts: Posts::default(),
+            operators: HashSet::new(),
+            max_title_length: limits::EMBED_TITLE_LENGTH,
+            shutdown: Arc::default(),
         }
     }
 
-    async fn get_scheduler(
-        &self,
-        message_id: &MessageId,
-    ) -> Option<RwLockReadGuard<'_, Scheduler>> {
-        let schedulers = self.schedulers.read().await;
-        RwLockReadGuard::try_map(schedulers, |s| s.get(message_id)).ok()
+    async fn purge_guild(&self, guild: GuildId) {
+        let mut configs = self.guild_configs.write().await;
+        purge_guild(
+            &self.schedulers,
+            &self.storage,
+            &self.posts,
+            &mut configs,
+            guild,
+        )
+        .await;
     }
 
-    async fn get_mut_scheduler(&self, message_id: MessageId) -> Option<ScheduleWrapper<'_>> {
-        let schedulers = self.schedulers.write().await;
-        let scheduler = RwLockWriteGuard::try_map(schedulers, |s| s.get_mut(&message_id)).ok()?;
-        Some(ScheduleWrapper {
-            message_id,
-            scheduler,
-        })
+    /// The scheduler message `message_id` is a copy of, or `message_id` itself
+    fn primary_id(&self, message_id: MessageId) -> MessageId {
+        self.mirrors.get(&message_id).map_or(message_id, |id| *id)
     }
 
-    async fn create_scheduler(&self, ctx: Context, command: ApplicationCommandInteraction) {
-        let options: HashMap<&str, &Value> = command
-            .data
-            .options
+    /// Look up a scheduler by its message id or that of a copy
+    fn get_scheduler(&self, message_id: &MessageId) -> Option<SchedulerHandle> {
+        self.schedulers
+            .get(&self.primary_id(*message_id))
+            .map(|h| h.clone())
+    }
+
+    /// Look up a scheduler by a link to its message or by its message id
+    fn find_scheduler(&self, link: &str) -> Option<SchedulerHandle> {
+        self.get_scheduler(&scheduler_id(link)?)
+    }
+
+    /// Look up a scheduler like `find_scheduler`, along with its message id
+    fn find_scheduler_with_id(&self, link: &str) -> Option<(MessageId, SchedulerHandle)> {
+        let id = self.primary_id(scheduler_id(link)?);
+        Some((id, self.get_scheduler(&id)?))
+    }
+
+    /// The most recently created open scheduler `user` owns in `channel`
+    async fn latest_open_scheduler(
+        &self,
+        user: UserId,
+        channel: ChannelId,
+    ) -> Option<(MessageId, SchedulerHandle)> {
+        let handles: Vec<(MessageId, SchedulerHandle)> = self
+            .schedulers
             .iter()
-            .filter_map(|o| o.value.as_ref().map(|v| (o.name.as_ref(), v)))
+            .map(|h| (*h.key(), h.value().clone()))
             .collect();
-        let title = options
-            .get("description")
-            .expect("Cannot find description option");
-        let title = title.as_str().expect("Caption has incorrect type");
-        if title.len() > 256 {
-            send_error(&ctx, &command, "Description is too long").await;
-            return;
+        let mut latest = None;
+        for (id, handle) in handles {
+            let open = handle
+                .get(move |s| s.is_owner(user) && s.channel_id() == channel && !s.is_closed())
+                .await
+                .unwrap_or(false);
+            if open && latest.as_ref().is_none_or(|(latest, _)| id > *latest) {
+                latest = Some((id, handle));
+            }
         }
-        let group = options.get("group").map(|v| {
-            RoleId::from_str(v.as_str().expect("Group has incorrect type"))
-                .expect("Error parsing role")
-        });
-        let weeks = match options.get("weeks") {
-            Some(weeks) => weeks.as_i64().expect("Weeks has incorrect type"),
-            None => MAX_WEEKS as i64,
-        };
-        let days = options
-            .get("days")
-            .map(|s| {
-                s.as_str()
-                    .expect("Days has incorrect type")
-                    .split('+')
-                    .map(|d| Weekday::from_str(d).expect("Cannot parse day"))
-                    .collect::<HashSet<Weekday>>()
-            })
-            .unwrap_or_else(|| HashSet::from([Weekday::Sat, Weekday::Sun]));
-        let skip = options
-            .get("skip")
-            .map(|v| v.as_i64().expect("Skip has incorrect type"));
-        command
-            .create_interaction_response(&ctx.http, |response| {
-                response
-                    .kind(InteractionResponseType::ChannelMessageWithSource)
-                    .interaction_response_data(|m| m.content("Please wait..."))
-            })
-            .await
-            .expect("Cannot respond to slash command");
-        let message = command
-            .get_interaction_response(&ctx)
-            .await
-            .expect("Cannot get message");
-        let message_id = message.id;
-        let scheduler = Scheduler::new(command.user.id, group, message, weeks, skip, title, days);
-        scheduler.update_message(&ctx).await;
-        let mut schedulers = self.schedulers.write().await;
-        write_file(&message_id, &scheduler);
-        schedulers.insert(message_id, scheduler);
+        latest
     }
 
-    async fn handle_get_response(
+    /// The scheduler `link` points to, or without one the latest open scheduler the user of
+    /// `command` owns in its channel
+    async fn target_scheduler(
         &self,
-        ctx: Context,
-        component: &MessageComponentInteraction,
-        resp_type: ResponseType,
-    ) {
-        let message_id = match resp_type {
-            ResponseType::Normal => component.message.id,
-            ResponseType::Blackout => component
-                .message
-                .message_reference
-                .as_ref()
-                .expect("Cannot find message for DM")
-                .message_id
-                .unwrap(),
-        };
-        let scheduler = self
-            .get_scheduler(&message_id)
-            .await
-            .expect("Cannot find scheduler");
-        if !scheduler.can_respond(&ctx, component).await {
+        command: &CommandInteraction,
+        link: Option<&str>,
+    ) -> Option<(MessageId, SchedulerHandle)> {
+        match link {
+            Some(link) => self.find_scheduler_with_id(link),
+            None => {
+                self.latest_open_scheduler(command.user.id, command.channel_id)
+                    .await
+            }
+        }
+    }
+
+    async fn insert_scheduler(
+        &self,
+        ctx: &Context,
+        message_id: MessageId,
+        scheduler: Scheduler,
+    ) -> Option<Scheduler> {
+        insert_scheduler(ctx, &self.schedulers, &self.storage, message_id, scheduler).await
+    }
+
+    /// Count a 👍 on a date message as a response, or take it back when it is removed
+    async fn handle_reaction(&self, ctx: Context, reaction: Reaction, added: bool) {
+        if !matches!(&reaction.emoji, ReactionType::Unicode(e) if e == scheduler::REACTION) {
             return;
         }
-        let dates = scheduler.get_dates();
-        let blackout_dates = scheduler.get_blackout_dates();
-        let response = match resp_type {
-            ResponseType::Normal => scheduler
-                .get_user_response(&component.user.id)
-                .unwrap_or_default(),
-            ResponseType::Blackout => blackout_dates.clone().into(),
+        let user = match reaction.user_id {
+            Some(user) if user != ctx.cache.current_user().id => user,
+            _ => return,
         };
-        drop(scheduler); // Release the lock so we don't block other interactions
-        if let Some(response) =
-            scheduler::get_response(&ctx, component, response, dates, blackout_dates, resp_type)
-                .await
-        {
-            let mut scheduler = self.get_mut_scheduler(message_id).await.unwrap();
-            match resp_type {
-                ResponseType::Normal => {
-                    scheduler
-                        .add_response(&ctx, component.user.id, response)
-                        .await
-                }
-                ResponseType::Blackout => scheduler.set_blackout(&ctx, response).await,
-            }
+        let id = match self.date_messages.get(&reaction.message_id) {
+            Some(id) => *id,
+            None => return,
+        };
+        let handle = match self.get_scheduler(&id) {
+            Some(handle) => handle,
+            None => return,
+        };
+        let message = reaction.message_id;
+        let roles = reaction.member.map(|m| m.roles).unwrap_or_default();
+        let c = ctx.clone();
+        handle
+            .update(move |scheduler| {
+                Box::pin(async move {
+                    if added {
+                        scheduler.react(&c, message, user, &roles).await;
+                    } else {
+                        scheduler.unreact(&c, message, user).await;
+                    }
+                })
+            })
+            .await;
+        if let Err(e) = self.propagate_constraints(&ctx, id).await {
+            error!(
+                "Cannot propagate reaction on {} to linked schedulers: {}",
+                id, e
+            );
         }
     }
 
-    async fn handle_show_details(&self, ctx: Context, component: &MessageComponentInteraction) {
-        let message_id = component.message.id;
-        let scheduler = self
-            .get_scheduler(&message_id)
-            .await
-            .expect("Cannot find scheduler");
-        scheduler.show_details(&ctx, component).await;
+    /// The language for dates in schedulers created by `command`
+    async fn locale(&self, command: &CommandInteraction) -> Locale {
+        let configured = match command.guild_id {
+            Some(guild) => self
+                .guild_configs
+                .read()
+                .await
+                .get(&guild)
+                .and_then(|c| c.locale),
+            None => None,
+        };
+        configured
+            .or_else(|| command.guild_locale.as_deref().and_then(Locale::parse))
+            .unwrap_or_default()
     }
 
-    async fn handle_close(&self, ctx: Context, component: &MessageComponentInteraction) {
-        let scheduler = self
-            .get_scheduler(&component.message.id)
+    /// How the guild of `command` wants dates written, if it set that
+    async fn date_format(&self, command: &CommandInteraction) -> Option<DateFormat> {
+        let guild = command.guild_id?;
+        self.guild_configs
+            .read()
             .await
-            .expect("Cannot find scheduler");
-        scheduler.close_prompt(&ctx, component).await;
+            .get(&guild)
+            .and_then(|c| c.date_format.clone())
     }
 
-    async fn handle_close_yes(&self, ctx: Context, component: &MessageComponentInteraction) {
-        component
-            .defer(&ctx)
-            .await
-            .expect("Cannot respond to button");
-        let message_ref = component.message.message_reference.as_ref().unwrap();
-        let mut scheduler = self
-            .get_mut_scheduler(message_ref.message_id.unwrap())
+    /// The settings of `guild`, or the defaults outside of one
+    async fn guild_config(&self, guild: Option<GuildId>) -> GuildConfig {
+        match guild {
+            Some(guild) => self
+                .guild_configs
+                .read()
+                .await
+                .get(&guild)
+                .cloned()
+                .unwrap_or_default(),
+            None => Default::default(),
+        }
+    }
+
+    /// Refuse `command` if its user isn't in the guild's roles allowed to create schedulers
+    async fn check_can_create(&self, command: &CommandInteraction) -> Result {
+        let config = self.guild_config(command.guild_id).await;
+        if config.can_create(command.member.as_deref()) {
+            Ok(())
+        } else {
+            Err(Error::user(config.create_denied()))
+        }
+    }
+
+    /// The timezone a guild schedules in, if it has set one
+    async fn guild_timezone(&self, guild: Option<GuildId>) -> Option<Tz> {
+        self.guild_configs
+            .read()
             .await
-            .expect("Cannot find scheduler");
-        scheduler.handle_close(&ctx, component).await;
+            .get(&guild?)
+            .and_then(|c| c.timezone)
     }
-}
 
-#[async_trait]
-impl EventHandler for Handler {
-    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
-        match interaction {
-            Interaction::ApplicationCommand(command) => {
-                let user = command.user.name.as_str();
-                let command_name = command.data.name.as_str();
-                info!("{} <{}>", command_name, user);
-                match command_name {
-                    "schedule" => self.create_scheduler(ctx, command).await,
-                    _ => panic!("Unexpected command: {}", command_name),
-                }
+    /// The validated title and body of a scheduler being created
+    fn text(&self, options: &HashMap<&str, &Value>) -> Result<(String, Option<String>)> {
+        let title = options
+            .get("description")
+            .expect("Cannot find description option")
+            .as_str()
+            .expect("Description has incorrect type")
+            .trim();
+        let body = options
+            .get("body")
+            .map(|v| v.as_str().expect("Body has incorrect type").trim())
+            .filter(|body| !body.is_empty());
+        validate::title(title, self.max_title_length)
+            .and_then(|_| body.map_or(Ok(()), validate::body))
+            .map_err(Error::user)?;
+        Ok((title.to_owned(), body.map(str::to_owned)))
+    }
+
+    async fn set_timezone(&self, ctx: Context, command: CommandInteraction) -> Result {
+        let name = command
+            .data
+            .options
+            .iter()
+            .find(|o| o.name == "name")
+            .map(|o| &o.value)
+            .expect("Cannot find name option")
+            .as_str()
+            .expect("Name has incorrect type");
+        let tz = match Tz::from_str(name) {
+            Ok(tz) => tz,
+            Err(_) => {
+                return Err(Error::user(Phrase::new(Text::UnknownTimezone, &[&name])));
             }
-            Interaction::MessageComponent(component) => {
-                let user = component.user.name.as_str();
-                let button_id = component.data.custom_id.as_str();
-                info!("{} <{}>", button_id, user);
-                match button_id {
-                    "response" => {
-                        self.handle_get_response(ctx, &component, ResponseType::Normal)
-                            .await
-                    }
-                    "blackout" => {
-                        self.handle_get_response(ctx, &component, ResponseType::Blackout)
-                            .await
-                    }
-                    "details" => self.handle_show_details(ctx, &component).await,
-                    "close" => self.handle_close(ctx, &component).await,
-                    "close_yes" => self.handle_close_yes(ctx, &component).await,
-                    _ => (),
+        };
+        let mut timezones = self.timezones.write().await;
+        timezones.insert(command.user.id, tz);
+        timezones::save(&timezones)?;
+        drop(timezones);
+        command
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(
+                            Locale::parse(&command.locale)
+                                .unwrap_or_default()
+                                .fill(Text::TimezoneSet, &[&tz.name()]),
+                        )
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Add to, take from or show the user's personal calendar of when they can't play
+    async fn unavailable_command(&self, ctx: Context, command: CommandInteraction) -> Result {
+        let locale = Locale::parse(&command.locale).unwrap_or_default();
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .expect("Cannot find subcommand");
+        let options: HashMap<&str, &Value> = subcommand_options(subcommand)
+            .iter()
+            .map(|o| (o.name.as_str(), &o.value))
+            .collect();
+        let weekdays = match options.get("weekdays") {
+            Some(v) => match slot::parse_days(v.as_str().expect("Weekdays has incorrect type")) {
+                Some(days) => days,
+                None => {
+                    return Err(Error::user(Text::WeekdaysFormat));
+                }
+            },
+            None => HashSet::new(),
+        };
+        let dates = match options.get("dates") {
+            Some(v) => match slot::parse_dates(v.as_str().expect("Dates has incorrect type")) {
+                Some(dates) => dates,
+                None => {
+                    return Err(Error::user(Text::DatesFormat));
                 }
+            },
+            None => vec![],
+        };
+        let adding = match subcommand.name.as_str() {
+            "add" => true,
+            "remove" => false,
+            "list" => {
+                let calendar = self
+                    .calendars
+                    .read()
+                    .await
+                    .get(&command.user.id)
+                    .cloned()
+                    .unwrap_or_default();
+                let content = if calendar.is_empty() {
+                    locale.text(Text::CalendarEmpty).to_owned()
+                } else {
+                    locale.fill(Text::CalendarShown, &[&calendar.summary()])
+                };
+                command
+                    .create_response(
+                        &ctx,
+                        CreateInteractionResponse::Message(
+                            CreateInteractionResponseMessage::new()
+                                .content(content)
+                                .ephemeral(true),
+                        ),
+                    )
+                    .await?;
+                return Ok(());
+            }
+            _ => {
+                return Err(Error::Unexpected(format!(
+                    "subcommand: {}",
+                    subcommand.name
+                )))
             }
-            _ => panic!("Unexpected interaction: {:?}", interaction),
+        };
+        if weekdays.is_empty() && dates.is_empty() {
+            return Err(Error::user(Text::GiveWeekdaysOrDates));
+        }
+        let mut calendars = self.calendars.write().await;
+        let calendar = calendars.entry(command.user.id).or_default();
+        if adding {
+            calendar.weekdays.extend(weekdays);
+            calendar.dates.extend(dates);
+        } else {
+            calendar.weekdays.retain(|d| !weekdays.contains(d));
+            calendar.dates.retain(|d| !dates.contains(d));
         }
+        let content = if calendar.is_empty() {
+            calendars.remove(&command.user.id);
+            locale.text(Text::CalendarEmpty).to_owned()
+        } else {
+            locale.fill(Text::CalendarSaved, &[&calendar.summary()])
+        };
+        unavailable::save(&calendars)?;
+        drop(calendars);
+        command
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(content)
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        Ok(())
     }
 
-    async fn ready(&self, ctx: Context, _ready: Ready) {
-        info!("ready");
-
-        ApplicationCommand::create_global_application_command(&ctx, |command| {
-            command
-                .name("schedule")
-                .description("Create a scheduler")
-                .create_option(|o| {
-                    o.name("description")
-                        .description("event description")
-                        .kind(ApplicationCommandOptionType::String)
-                        .required(true)
-                })
-                .create_option(|o| {
-                    o.name("group")
-                        .description("player group")
-                        .kind(ApplicationCommandOptionType::Role)
-                })
-                .create_option(|o| {
-                    o.name("weeks")
-                        .description("number of weeks")
-                        .kind(ApplicationCommandOptionType::Integer)
-                        .min_int_value(1)
-                        .max_int_value(MAX_WEEKS)
-                })
-                .create_option(|o| {
-                    o.name("skip")
-                        .description("weeks before start")
-                        .kind(ApplicationCommandOptionType::Integer)
-                        .min_int_value(0)
-                })
-                .create_option(|o| {
-                    o.name("days")
-                        .description("weekdays to include")
-                        .kind(ApplicationCommandOptionType::String)
-                        .add_string_choice("Saturday + Sunday", "Sat+Sun")
-                        .add_string_choice("Saturday", "Sat")
-                        .add_string_choice("Sunday", "Sun")
+    async fn suggest_days(&self, ctx: Context, command: CommandInteraction) -> Result {
+        self.check_can_create(&command).await?;
+        let group = command
+            .data
+            .options
+            .iter()
+            .find(|o| o.name == "group")
+            .map(|o| &o.value)
+            .map(|v| {
+                RoleId::from_str(v.as_str().expect("Group has incorrect type"))
+                    .expect("Error parsing role")
+            });
+        let guild = command.guild_id;
+        let mut turnout: Vec<_> = storage::blocking(&self.storage, move |s| s.archived(guild))
+            .await
+            .into_iter()
+            .filter(|(_, s)| s.guild() == guild && s.group() == group)
+            .flat_map(|(_, s)| s.turnout())
+            .collect();
+        let handles: Vec<SchedulerHandle> = self
+            .schedulers
+            .iter()
+            .filter(|h| h.guild() == guild)
+            .map(|h| h.clone())
+            .collect();
+        for handle in handles {
+            let past = handle
+                .get(move |s| {
+                    if s.is_closed() && s.group() == group {
+                        s.turnout()
+                    } else {
+                        vec![]
+                    }
                 })
-        })
-        .await
-        .expect("Cannot create command");
+                .await;
+            turnout.extend(past.unwrap_or_default());
+        }
+        let lines = history::suggest_days(turnout);
+        let locale = Locale::parse(&command.locale).unwrap_or_default();
+        let content = if lines.is_empty() {
+            locale.text(Text::NoPastSchedulers).to_owned()
+        } else {
+            format!(
+                "{}\n{}",
+                locale.text(Text::BestDaysByTurnout),
+                lines.join("\n")
+            )
+        };
+        command
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(content)
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
 
-        if self.refresh {
-            for (_, scheduler) in self.schedulers.read().await.iter() {
-                scheduler.update_message(&ctx).await;
-            }
+    async fn create_interest(&self, ctx: Context, command: CommandInteraction) -> Result {
+        let options: HashMap<&str, &Value> = command
+            .data
+            .options
+            .iter()
+            .map(|o| (o.name.as_str(), &o.value))
+            .collect();
+        self.check_can_create(&command).await?;
+        let (title, body) = self.text(&options)?;
+        let group = options.get("group").map(|v| {
+            RoleId::from_str(v.as_str().expect("Group has incorrect type"))
+                .expect("Error parsing role")
+        });
+        let locale = self.locale(&command).await;
+        let date_format = self.date_format(&command).await;
+        let message = post_placeholder(&ctx, &command).await?;
+        let message_id = message.id;
+        let scheduler = Scheduler::new_interest(
+            command.user.id,
+            command.guild_id,
+            message,
+            title,
+            body,
+            group,
+            locale,
+            date_format,
+        );
+        self.insert_scheduler(&ctx, message_id, scheduler).await;
+        Ok(())
+    }
+
+    async fn schedule_command(&self, ctx: Context, command: CommandInteraction) -> Result {
+        let subcommand = command
+            .data
+            .options
+            .first()
+            .expect("Cannot find subcommand");
+        let options: HashMap<&str, &Value> = subcommand_options(subcommand)
+            .iter()
+            .map(|o| (o.name.as_str(), &o.value))
+            .collect();
+        match subcommand.name.as_str() {
+            "create" => self.create_scheduler(&ctx, &command, options, None).await,
+            "template" => self.save_template(&ctx, &command, options).await,
+            "from-template" => self.create_from_template(&ctx, &command, options).await,
+            "confirm" => self.create_confirmation(&ctx, &command, options).await,
+            "edit" => self.edit_scheduler(&ctx, &command, options).await,
+            "cancel" => self.cancel_command(&ctx, &command, options).await,
+            "export" => self.export_command(&ctx, &command, options).await,
+            "alert" => self.alert_command(&ctx, &command, options).await,
+            "require" => self.require_command(&ctx, &command, options).await,
+            "addowner" => self.add_owner_command(&ctx, &command, options).await,
+            "crosspost" => self.crosspost_command(&ctx, &command, options).await,
+            "list" => self.list_command(&ctx, &command, options).await,
+            "intersect" => self.intersect_command(&ctx, &command, options).await,
+            "rebind" => self.rebind_command(&ctx, &command, options).await,
+            _ => Err(Error::Unexpected(format!(
+                "subcommand: {}",
+                subcommand.name
+            ))),
         }
     }
 
-    async fn message_delete(
+    /// Save the given options under a name for `/schedule from-template`
+    async fn save_template(
         &self,
-        _ctx: Context,
-        _channel_id: ChannelId,
-        deleted_message_id: MessageId,
-        _guild_id: Option<GuildId>,
-    ) {
-        let mut schedulers = self.schedulers.write().await;
-        if let Some(_scheduler) = schedulers.remove(&deleted_message_id) {
-            info!("scheduler message deleted: {}", deleted_message_id);
-            delete_file(&deleted_message_id);
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        self.check_can_create(command).await?;
+        let guild = command
+            .guild_id
+            .ok_or(Error::user(Text::TemplatesPerServer))?;
+        let name = template_name(&options);
+        let prefix = options
+            .get("prefix")
+            .map(|v| {
+                v.as_str()
+                    .expect("Prefix has incorrect type")
+                    .trim()
+                    .to_owned()
+            })
+            .filter(|prefix| !prefix.is_empty());
+        if let Some(prefix) = &prefix {
+            validate::title(prefix, self.max_title_length).map_err(Error::user)?;
+        }
+        let days = match options.get("days") {
+            Some(v) => match slot::parse_days(v.as_str().expect("Days has incorrect type")) {
+                Some(days) => Some(days),
+                None => {
+                    return Err(Error::user(Text::DaysFormat));
+                }
+            },
+            None => None,
+        };
+        let timezone = match options.get("timezone") {
+            Some(v) => {
+                let name = v.as_str().expect("Timezone has incorrect type");
+                match Tz::from_str(name) {
+                    Ok(tz) => Some(tz),
+                    Err(_) => {
+                        return Err(Error::user(Phrase::new(Text::UnknownTimezone, &[&name])));
+                    }
+                }
+            }
+            None => None,
+        };
+        let template = Template {
+            prefix,
+            days,
+            weeks: options
+                .get("weeks")
+                .map(|v| v.as_i64().expect("Weeks has incorrect type")),
+            group: options.get("group").map(|v| {
+                RoleId::from_str(v.as_str().expect("Group has incorrect type"))
+                    .expect("Error parsing role")
+            }),
+            timezone,
+            threshold: options
+                .get("threshold")
+                .map(|v| v.as_i64().expect("Threshold has incorrect type") as usize),
+        };
+        let mut configs = self.guild_configs.write().await;
+        let config = configs.entry(guild).or_default();
+        if !config.templates.contains_key(&name) && config.templates.len() >= limits::TEMPLATES {
+            return Err(Error::user(Phrase::new(
+                Text::TooManyTemplates,
+                &[&limits::TEMPLATES],
+            )));
         }
+        config.templates.insert(name.clone(), template);
+        guild_config::write_file(guild, config)?;
+        drop(configs);
+        command
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(
+                            Locale::parse(&command.locale)
+                                .unwrap_or_default()
+                                .fill(Text::TemplateSaved, &[&name]),
+                        )
+                        .ephemeral(true),
+                ),
+            )
+            .await?;
+        Ok(())
     }
-}
-
-#[derive(Parser)]
-#[clap(author, version, about, long_about = None)]
-struct Cli {
-    #[clap(long, action)]
-    refresh: bool,
-}
 
-#[tokio::main]
-async fn main() {
-    env_logger::Builder::new()
-        .target(env_logger::Target::Stdout)
-        .filter(Some("scheduler"), log::LevelFilter::Info)
-        .init();
+    async fn create_from_template(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        let name = template_name(&options);
+        let template = self
+            .guild_config(command.guild_id)
+            .await
+            .templates
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| Error::user(Phrase::new(Text::NoSuchTemplate, &[&name])))?;
+        self.create_scheduler(ctx, command, options, Some(template))
+            .await
+    }
+
+    async fn create_confirmation(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        self.check_can_create(command).await?;
+        let (title, body) = self.text(&options)?;
+        let date = options
+            .get("date")
+            .expect("Cannot find date option")
+            .as_str()
+            .expect("Date has incorrect type");
+        let slot = match Slot::parse(date) {
+            Some(slot) if slot.date >= Local::now().date_naive() => slot,
+            Some(_) => {
+                return Err(Error::user(Text::DatePassed));
+            }
+            None => {
+                return Err(Error::user(Text::SlotFormat));
+            }
+        };
+        let group = options.get("group").map(|v| {
+            RoleId::from_str(v.as_str().expect("Group has incorrect type"))
+                .expect("Error parsing role")
+        });
+        let notes = options
+            .get("notes")
+            .map(|v| v.as_str().expect("Notes has incorrect type").to_owned());
+        let message = post_placeholder(ctx, command).await?;
+        let message_id = message.id;
+        let options = Options {
+            title,
+            body,
+            group,
+            weeks: 1,
+            skip: None,
+            days: scheduler::default_days(),
+            times: vec![],
+            dates: vec![],
+            details_layout: Default::default(),
+            blackout_policy: Default::default(),
+            embargo: None,
+            locale: self.locale(command).await,
+            date_format: self.date_format(command).await,
+            layout: render::default_layout(),
+            notes,
+            mention_limit: None,
+            quorum: None,
+            threshold: None,
+            deadline: None,
+            remind_after: None,
+            firm_up: None,
+            venue: None,
+            reactions: false,
+            announcement: None,
+            timezone: self.guild_timezone(command.guild_id).await,
+            read_only: false,
+            anonymous: false,
+            thread: false,
+            recurring: false,
+            ranked: false,
+        };
+        let scheduler =
+            Scheduler::new_confirmation(command.user.id, command.guild_id, message, options, slot);
+        self.insert_scheduler(ctx, message_id, scheduler).await;
+        Ok(())
+    }
+
+    async fn create_scheduler(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+        template: Option<Template>,
+    ) -> Result {
+        self.check_can_create(command).await?;
+        let (mut title, body) = self.text(&options)?;
+        // Omitted options fall back to the template, then to the guild's defaults
+        let template = template.unwrap_or_default();
+        if let Some(prefix) = &template.prefix {
+            title = format!("{} {}", prefix, title);
+            validate::title(&title, self.max_title_length).map_err(Error::user)?;
+        }
+        let config = self.guild_config(command.guild_id).await;
+        let group = options
+            .get("group")
+            .map(|v| {
+                RoleId::from_str(v.as_str().expect("Group has incorrect type"))
+                    .expect("Error parsing role")
+            })
+            .or(template.group)
+            .or(config.default_group);
+        let days = match options.get("days") {
+            Some(v) => match slot::parse_days(v.as_str().expect("Days has incorrect type")) {
+                Some(days) => days,
+                None => {
+                    return Err(Error::user(Text::DaysFormat));
+                }
+            },
+            None => template
+                .days
+                .or(config.default_days)
+                .unwrap_or_else(scheduler::default_days),
+        };
+        let times = match options.get("times") {
+            Some(v) => match slot::parse_times(v.as_str().expect("Times has incorrect type")) {
+                Some(times) => times,
+                None => {
+                    return Err(Error::user(Text::TimesFormat));
+                }
+            },
+            None => vec![],
+        };
+        let dates = match options.get("dates") {
+            Some(v) => match slot::parse_dates(v.as_str().expect("Dates has incorrect type")) {
+                Some(dates) if dates[0] >= Local::now().date_naive() => dates,
+                Some(_) => {
+                    return Err(Error::user(Text::DatesPassed));
+                }
+                None => {
+                    return Err(Error::user(Text::DatesFormat));
+                }
+            },
+            None => vec![],
+        };
+        let reactions = options
+            .get("reactions")
+            .is_some_and(|v| v.as_bool().expect("Reactions has incorrect type"));
+        let thread = options
+            .get("thread")
+            .is_some_and(|v| v.as_bool().expect("Thread has incorrect type"));
+        let announcement = match options.get("announcement") {
+            Some(v) => {
+                let template = v.as_str().expect("Announcement has incorrect type");
+                announce::validate(template).map_err(Error::user)?;
+                Some(template.to_owned())
+            }
+            None => config.announcement.clone(),
+        };
+        // Fewer when each slot gets a message of its own
+        let max_slots = if reactions {
+            scheduler::MAX_REACTION_DATES
+        } else {
+            scheduler::MAX_SLOTS
+        };
+        // Only as many weeks as a scheduler has slots for
+        let per_week = days.len() * times.len().max(1);
+        let max_weeks = std::cmp::min(MAX_WEEKS, max_slots / per_week) as i64;
+        let weeks = match options.get("weeks") {
+            Some(weeks) => weeks.as_i64().expect("Weeks has incorrect type"),
+            None => max_weeks.min(
+                template
+                    .weeks
+                    .or(config.default_weeks)
+                    .unwrap_or(DEFAULT_WEEKS),
+            ),
+        };
+        let too_many = if dates.is_empty() {
+            weeks > max_weeks || max_weeks == 0
+        } else {
+            dates.len() * times.len().max(1) > max_slots
+        };
+        if too_many {
+            return Err(Error::user(Phrase::new(Text::TooManySlots, &[&max_slots])));
+        }
+        let skip = options
+            .get("skip")
+            .map(|v| v.as_i64().expect("Skip has incorrect type"));
+        let details_layout = options
+            .get("details")
+            .map(|v| {
+                DetailsLayout::parse(v.as_str().expect("Details has incorrect type"))
+                    .expect("Cannot parse details layout")
+            })
+            .unwrap_or_default();
+        let blackout_policy = options
+            .get("blackouts")
+            .map(|v| {
+                BlackoutPolicy::parse(v.as_str().expect("Blackouts has incorrect type"))
+                    .expect("Cannot parse blackout policy")
+            })
+            .unwrap_or_default();
+        let embargo = match options.get("hide_results") {
+            Some(v) => {
+                let v = v.as_str().expect("Hide results has incorrect type");
+                match Embargo::parse(v) {
+                    Some(embargo) => Some(embargo),
+                    None => {
+                        return Err(Error::user(Text::HideResultsFormat));
+                    }
+                }
+            }
+            None => None,
+        };
+        let layout = match options.get("layout") {
+            Some(v) => match render::parse_layout(v.as_str().expect("Layout has incorrect type")) {
+                Some(layout) => layout,
+                None => {
+                    return Err(Error::user(Text::LayoutFormat));
+                }
+            },
+            None => render::default_layout(),
+        };
+        let notes = options
+            .get("notes")
+            .map(|v| v.as_str().expect("Notes has incorrect type").to_owned());
+        let mention_limit = options
+            .get("mentions")
+            .map(|v| v.as_i64().expect("Mentions has incorrect type") as usize);
+        let quorum = options
+            .get("quorum")
+            .map(|v| v.as_i64().expect("Quorum has incorrect type") as usize);
+        let remind_after = options
+            .get("remind_after")
+            .map(|v| v.as_i64().expect("Remind after has incorrect type"));
+        let firm_up = options
+            .get("firm_up")
+            .map(|v| v.as_i64().expect("Firm up has incorrect type"));
+        let venue = options.get("voice").map(|v| {
+            let channel = ChannelId::from_str(v.as_str().expect("Voice has incorrect type"))
+                .expect("Error parsing channel");
+            let stage = command
+                .data
+                .resolved
+                .channels
+                .get(&channel)
+                .is_some_and(|c| c.kind == ChannelType::Stage);
+            Venue { channel, stage }
+        });
+        let mode = options
+            .get("mode")
+            .map(|v| v.as_str().expect("Mode has incorrect type"));
+        let read_only = mode == Some("read_only");
+        let ranked = mode == Some("ranked");
+        let anonymous = options
+            .get("anonymous")
+            .is_some_and(|v| v.as_bool().expect("Anonymous has incorrect type"));
+        let timezone = template
+            .timezone
+            .or(self.guild_timezone(command.guild_id).await);
+        let post_at = match options.get("post_at") {
+            Some(v) => {
+                let v = v.as_str().expect("Post at has incorrect type");
+                let tz = timezone.unwrap_or(chrono_tz::UTC);
+                match Slot::parse(v).and_then(|slot| slot.timestamp(tz)) {
+                    Some(ts) if ts > Utc::now().timestamp() => {
+                        Some(Utc.timestamp_opt(ts, 0).unwrap())
+                    }
+                    Some(_) => {
+                        return Err(Error::user(Text::TimePassed));
+                    }
+                    None => {
+                        return Err(Error::user(Text::PostAtFormat));
+                    }
+                }
+            }
+            None => None,
+        };
+        let deadline = match options.get("deadline") {
+            Some(v) => {
+                let v = v.as_str().expect("Deadline has incorrect type");
+                let tz = timezone.unwrap_or(chrono_tz::UTC);
+                // A date alone runs to the end of that day
+                let slot = Slot::parse(v).map(|slot| match slot.time {
+                    Some(_) => slot,
+                    None => Slot {
+                        date: slot.date.succ_opt().unwrap(),
+                        time: Some(NaiveTime::from_hms_opt(0, 0, 0).unwrap()),
+                    },
+                });
+                match slot.and_then(|slot| slot.timestamp(tz)) {
+                    Some(ts) if ts > Utc::now().timestamp() => {
+                        Some(Utc.timestamp_opt(ts, 0).unwrap())
+                    }
+                    Some(_) => {
+                        return Err(Error::user(Text::DeadlinePassed));
+                    }
+                    None => {
+                        return Err(Error::user(Text::DeadlineFormat));
+                    }
+                }
+            }
+            None => None,
+        };
+        let options = Options {
+            title,
+            body,
+            group,
+            weeks,
+            skip,
+            days,
+            times,
+            dates,
+            details_layout,
+            blackout_policy,
+            embargo,
+            locale: self.locale(command).await,
+            date_format: self.date_format(command).await,
+            layout,
+            notes,
+            mention_limit,
+            quorum,
+            threshold: template.threshold,
+            deadline,
+            remind_after,
+            firm_up,
+            venue,
+            reactions,
+            announcement,
+            timezone,
+            read_only,
+            anonymous,
+            thread,
+            recurring: false,
+            ranked,
+        };
+        if let Some(due) = post_at {
+            return self.prepare_post(ctx, command, options, due).await;
+        }
+        let message = post_placeholder(ctx, command).await?;
+        let message_id = message.id;
+        let scheduler = Scheduler::new(command.user.id, command.guild_id, message, options);
+        self.insert_scheduler(ctx, message_id, scheduler).await;
+        if reactions {
+            post_date_messages(ctx, &self.schedulers, &self.date_messages, message_id).await;
+        }
+        if thread {
+            start_thread(ctx, &self.schedulers, message_id).await;
+        }
+        let locale = Locale::parse(&command.locale).unwrap_or_default();
+        command
+            .create_followup(
+                ctx,
+                CreateInteractionResponseFollowup::new()
+                    .ephemeral(true)
+                    .content(locale.text(Text::AddOwnAvailability))
+                    .components(vec![CreateActionRow::Buttons(vec![CreateButton::new(
+                        format!("own_availability {}", message_id),
+                    )
+                    .label(locale.text(Text::AddMyAvailability))])]),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn edit_scheduler(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        let link = options
+            .get("scheduler")
+            .map(|v| v.as_str().expect("Scheduler has incorrect type"));
+        let handle = match self.target_scheduler(command, link).await {
+            Some((_, handle)) => handle,
+            None => {
+                return Err(Error::user(Text::NoSchedulerToEdit));
+            }
+        };
+        let caller = command.user.id;
+        let (is_owner, closed) = handle
+            .get(move |scheduler| (scheduler.is_owner(caller), scheduler.is_closed()))
+            .await
+            .ok_or(Error::Scheduler)?;
+        if !is_owner {
+            return Err(Error::user(Text::OwnerOnlyEdit));
+        }
+        if closed {
+            return Err(Error::user(Text::SchedulerClosed));
+        }
+        let title = options
+            .get("description")
+            .map(|v| v.as_str().expect("Description has incorrect type").trim());
+        if let Some(title) = title {
+            if let Err(e) = validate::title(title, self.max_title_length) {
+                return Err(Error::user(e));
+            }
+        }
+        let group = options.get("group").map(|v| {
+            RoleId::from_str(v.as_str().expect("Group has incorrect type"))
+                .expect("Error parsing role")
+        });
+        let dates = match options.get("weeks") {
+            Some(weeks) => {
+                let weeks = weeks.as_i64().expect("Weeks has incorrect type");
+                let dates = handle
+                    .get(move |scheduler| scheduler.resized_dates(weeks))
+                    .await
+                    .ok_or(Error::Scheduler)?;
+                match dates {
+                    Some(dates) if dates.len() <= scheduler::MAX_SLOTS => Some(dates),
+                    Some(_) => {
+                        return Err(Error::user(Phrase::new(
+                            Text::TooManyWeeks,
+                            &[&scheduler::MAX_SLOTS],
+                        )));
+                    }
+                    None => {
+                        return Err(Error::user(Text::OnlyWeeklyHaveWeeks));
+                    }
+                }
+            }
+            None => None,
+        };
+        let recurring = options
+            .get("recurring")
+            .map(|v| v.as_bool().expect("Recurring has incorrect type"));
+        let threshold = options
+            .get("threshold")
+            .map(|v| v.as_i64().expect("Threshold has incorrect type") as usize);
+        let show_blackouts = options
+            .get("blackouts")
+            .map(|v| v.as_bool().expect("Blackouts has incorrect type"));
+        let ranked = options
+            .get("mode")
+            .map(|v| v.as_str().expect("Mode has incorrect type") == "ranked");
+        let capacity = options
+            .get("capacity")
+            .map(|v| v.as_i64().expect("Capacity has incorrect type") as usize);
+        let changes = Changes {
+            title: title.map(str::to_owned),
+            group,
+            dates,
+            recurring,
+            threshold,
+            show_blackouts,
+            ranked,
+            capacity,
+        };
+        if changes.is_empty() {
+            return Err(Error::user(Text::NothingToChange));
+        }
+        command
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        let c = ctx.clone();
+        let summary = handle
+            .update(move |scheduler| Box::pin(async move { scheduler.edit(&c, changes).await }))
+            .await
+            .ok_or(Error::Scheduler)?;
+        command
+            .edit_response(
+                ctx,
+                EditInteractionResponse::new()
+                    .content(summary)
+                    .allowed_mentions(CreateAllowedMentions::new()),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// List the open schedulers in this channel, or in the whole server
+    async fn list_command(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        let server = options
+            .get("where")
+            .map(|v| v.as_str().expect("Where has incorrect type"))
+            == Some("server");
+        let (channel, guild) = (command.channel_id, command.guild_id);
+        command
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        let handles: Vec<(MessageId, SchedulerHandle)> = self
+            .schedulers
+            .iter()
+            .map(|h| (*h.key(), h.value().clone()))
+            .collect();
+        let mut listed = vec![];
+        for (id, handle) in handles {
+            let listing = handle
+                .get(move |s| {
+                    let here = match (server, guild) {
+                        (true, Some(guild)) => s.guild() == Some(guild),
+                        _ => s.channel_id() == channel,
+                    };
+                    (here && !s.is_closed()).then(|| s.listing())
+                })
+                .await
+                .flatten();
+            if let Some(listing) = listing {
+                listed.push((id, listing));
+            }
+        }
+        if listed.is_empty() {
+            command
+                .edit_response(
+                    ctx,
+                    EditInteractionResponse::new().content(
+                        Locale::parse(&command.locale)
+                            .unwrap_or_default()
+                            .text(Text::NoOpenSchedulers),
+                    ),
+                )
+                .await?;
+            return Ok(());
+        }
+        // Newest first
+        listed.sort_unstable_by_key(|(id, _)| std::cmp::Reverse(*id));
+        edit_in_chunks(ctx, command, listed.into_iter().map(|(_, listing)| listing)).await
+    }
+
+    /// Post the dates among the best of two schedulers, e.g. for groups sharing a venue
+    async fn intersect_command(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        let mut found = vec![];
+        for (name, missing, hiding) in [
+            ("first", Text::MissingFirst, Text::FirstHidingResults),
+            ("second", Text::MissingSecond, Text::SecondHidingResults),
+        ] {
+            let link = options
+                .get(name)
+                .expect("Cannot find scheduler option")
+                .as_str()
+                .expect("Scheduler has incorrect type");
+            // Another server's results aren't this one's to see
+            let (_, handle) = self
+                .find_scheduler_with_id(link)
+                .filter(|(_, handle)| handle.guild() == command.guild_id)
+                .ok_or(Error::user(missing))?;
+            let c = ctx.clone();
+            let top = handle
+                .view(move |scheduler| {
+                    Box::pin(async move {
+                        let top = scheduler.top_results(&c).await?;
+                        Some((scheduler.title().to_owned(), top, scheduler.date_style()))
+                    })
+                })
+                .await
+                .ok_or(Error::Scheduler)?
+                .ok_or(Error::user(hiding))?;
+            found.push(top);
+        }
+        let (second, first) = (found.pop().unwrap(), found.pop().unwrap());
+        let common = scheduler::intersect(&first.1, &second.1);
+        let locale = Locale::parse(&command.locale).unwrap_or_default();
+        let header = locale.fill(
+            Text::IntersectHeader,
+            &[&scheduler::INTERSECT_DATES, &first.0, &second.0],
+        );
+        let lines: Vec<String> = common
+            .iter()
+            .map(|(slot, a, b)| {
+                format!(
+                    "{}: {} + {}",
+                    first.2.code(*slot),
+                    render::count(a.0, a.1),
+                    render::count(b.0, b.1)
+                )
+            })
+            .collect();
+        let content = if lines.is_empty() {
+            locale.fill(Text::IntersectNone, &[&header])
+        } else {
+            format!("{}\n{}", header, lines.join("\n"))
+        };
+        command
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(limits::truncate(&content, limits::MESSAGE_LENGTH))
+                        .allowed_mentions(CreateAllowedMentions::new()),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Which open schedulers in the guild the member a context menu was opened on has responded
+    /// to, and what they picked
+    async fn show_availability(&self, ctx: Context, command: CommandInteraction) -> Result {
+        let user = command
+            .data
+            .target_id
+            .expect("Cannot find target user")
+            .to_user_id();
+        let (viewer, guild) = (command.user.id, command.guild_id);
+        let locale = Locale::parse(&command.locale).unwrap_or_default();
+        command
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        let handles: Vec<(MessageId, SchedulerHandle)> = self
+            .schedulers
+            .iter()
+            .map(|h| (*h.key(), h.value().clone()))
+            .collect();
+        let mut listed = vec![];
+        for (id, handle) in handles {
+            let line = handle
+                .get(move |s| {
+                    let here = guild.is_some() && s.guild() == guild && !s.is_closed();
+                    here.then(|| s.availability_of(user, viewer)).flatten()
+                })
+                .await
+                .flatten();
+            if let Some(line) = line {
+                listed.push((id, line));
+            }
+        }
+        if listed.is_empty() {
+            command
+                .edit_response(
+                    &ctx,
+                    EditInteractionResponse::new()
+                        .content(locale.fill(Text::NotRespondedAnywhere, &[&user])),
+                )
+                .await?;
+            return Ok(());
+        }
+        listed.sort_unstable_by_key(|(id, _)| std::cmp::Reverse(*id));
+        let lines = std::iter::once(locale.fill(Text::AvailabilityOf, &[&user]))
+            .chain(listed.into_iter().map(|(_, line)| line));
+        edit_in_chunks(&ctx, &command, lines).await
+    }
+
+    /// Move a scheduler whose message can no longer be edited onto a new message in its channel,
+    /// keeping every response
+    async fn rebind_command(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        let link = options
+            .get("scheduler")
+            .expect("Cannot find scheduler option")
+            .as_str()
+            .expect("Scheduler has incorrect type");
+        let (old_id, handle) = self
+            .find_scheduler_with_id(link)
+            .ok_or(Error::Missing("scheduler"))?;
+        let caller = command.user.id;
+        let (is_owner, channel, locale) = handle
+            .get(move |scheduler| {
+                (
+                    scheduler.is_owner(caller),
+                    scheduler.channel_id(),
+                    scheduler.date_style().locale,
+                )
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        if !is_owner && !self.operators.contains(&command.user.id) {
+            return Err(Error::user(Text::OwnerOnlyRebind));
+        }
+        command
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        let message = channel
+            .send_message(
+                ctx,
+                CreateMessage::new().content(locale.text(Text::PleaseWait)),
+            )
+            .await?;
+        let new_id = message.id;
+        self.schedulers.remove(&old_id);
+        let mut scheduler = match handle.take().await {
+            Some(scheduler) => scheduler,
+            None => {
+                self.schedulers.insert(old_id, handle);
+                channel.delete_message(ctx, new_id).await.ok();
+                return Err(Error::Scheduler);
+            }
+        };
+        scheduler.rebind(message.into());
+        if let Some(mut scheduler) = self.insert_scheduler(ctx, new_id, scheduler).await {
+            // The placeholder is already gone, so put the scheduler back where it was
+            scheduler.rebind(MessageShim::new(channel, old_id));
+            let handle = SchedulerHandle::spawn(old_id, scheduler, self.storage.clone());
+            self.schedulers.insert(old_id, handle);
+            return Err(Error::Unexpected(format!(
+                "cannot save scheduler {} rebound to {}",
+                old_id, new_id
+            )));
+        }
+        if let Err(e) = storage::blocking(&self.storage, move |s| s.delete(old_id)).await {
+            error!("Cannot delete scheduler {} after rebinding: {}", old_id, e);
+        }
+        for map in [&*self.date_messages, &self.mirrors] {
+            for mut scheduler in map.iter_mut() {
+                if *scheduler == old_id {
+                    *scheduler = new_id;
+                }
+            }
+        }
+        for mut targets in self.constrained.iter_mut() {
+            if targets.remove(&old_id) {
+                targets.insert(new_id);
+            }
+        }
+        if let Some((_, targets)) = self.constrained.remove(&old_id) {
+            for target in &targets {
+                if let Some(handle) = self.get_scheduler(target) {
+                    handle
+                        .update(move |s| {
+                            s.retarget_constraint(old_id, new_id);
+                            Box::pin(async {})
+                        })
+                        .await;
+                }
+            }
+            self.constrained.insert(new_id, targets);
+        }
+        // The old message may well be gone already
+        channel.delete_message(ctx, old_id).await.ok();
+        info!("scheduler {} rebound to {}", old_id, new_id);
+        command
+            .edit_response(
+                ctx,
+                EditInteractionResponse::new().content(
+                    Locale::parse(&command.locale).unwrap_or_default().fill(
+                        Text::MovedTo,
+                        &[&format!(
+                            "https://discord.com/channels/{}/{}/{}",
+                            command.guild_id.map_or("@me".to_owned(), |g| g.to_string()),
+                            channel,
+                            new_id
+                        )],
+                    ),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Post a copy of a scheduler in this channel, which takes responses like the original
+    async fn crosspost_command(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        self.check_can_create(command).await?;
+        let link = options
+            .get("scheduler")
+            .expect("Cannot find scheduler option")
+            .as_str()
+            .expect("Scheduler has incorrect type");
+        let (message_id, handle) = self
+            .find_scheduler_with_id(link)
+            .ok_or(Error::Missing("scheduler"))?;
+        let caller = command.user.id;
+        let (is_owner, closed, channel) = handle
+            .get(move |scheduler| {
+                (
+                    scheduler.is_owner(caller),
+                    scheduler.is_closed(),
+                    scheduler.channel_id(),
+                )
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        if !is_owner {
+            return Err(Error::user(Text::OwnerOnlyCrossPost));
+        }
+        if closed {
+            return Err(Error::user(Text::SchedulerClosed));
+        }
+        if channel == command.channel_id {
+            return Err(Error::user(Text::AlreadyInChannel));
+        }
+        let message = post_placeholder(ctx, command).await?;
+        self.mirrors.insert(message.id, message_id);
+        let c = ctx.clone();
+        handle
+            .update(move |scheduler| {
+                Box::pin(async move { scheduler.add_mirror(&c, message.into()).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        Ok(())
+    }
+
+    /// Have the owner DMed once a date reaches a number of people available
+    async fn alert_command(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        let link = options
+            .get("scheduler")
+            .map(|v| v.as_str().expect("Scheduler has incorrect type"));
+        let handle = match self.target_scheduler(command, link).await {
+            Some((_, handle)) => handle,
+            None => {
+                return Err(Error::user(Text::NoSchedulerToWatch));
+            }
+        };
+        let available = options
+            .get("available")
+            .expect("Cannot find available option")
+            .as_i64()
+            .expect("Available has incorrect type") as usize;
+        let caller = command.user.id;
+        let (is_owner, closed) = handle
+            .get(move |scheduler| (scheduler.is_owner(caller), scheduler.is_closed()))
+            .await
+            .ok_or(Error::Scheduler)?;
+        if !is_owner {
+            return Err(Error::user(Text::OwnerOnlyAlerts));
+        }
+        if closed {
+            return Err(Error::user(Text::SchedulerClosed));
+        }
+        command
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        let c = ctx.clone();
+        let reply = handle
+            .update(move |scheduler| {
+                Box::pin(async move { scheduler.add_alert(&c, available).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        let locale = Locale::parse(&command.locale).unwrap_or_default();
+        command
+            .edit_response(
+                ctx,
+                EditInteractionResponse::new().content(locale.phrase(&reply)),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Let someone else manage a scheduler alongside its owners
+    async fn add_owner_command(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        let link = options
+            .get("scheduler")
+            .map(|v| v.as_str().expect("Scheduler has incorrect type"));
+        let handle = match self.target_scheduler(command, link).await {
+            Some((_, handle)) => handle,
+            None => {
+                return Err(Error::user(Text::NoOpenScheduler));
+            }
+        };
+        let user = options
+            .get("user")
+            .expect("Cannot find user option")
+            .as_str()
+            .expect("User has incorrect type");
+        let user = UserId::new(user.parse().expect("Error parsing user"));
+        let caller = command.user.id;
+        let is_owner = handle
+            .get(move |scheduler| scheduler.is_owner(caller))
+            .await
+            .ok_or(Error::Scheduler)?;
+        if !is_owner {
+            return Err(Error::user(Text::OwnerOnlyAddOwners));
+        }
+        let reply = handle
+            .update(move |scheduler| {
+                let reply = scheduler.add_owner(user);
+                Box::pin(async move { reply })
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        command
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(reply)
+                        .allowed_mentions(CreateAllowedMentions::new()),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Mark a player a scheduler's dates can't go ahead without, or unmark them
+    async fn require_command(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        let link = options
+            .get("scheduler")
+            .map(|v| v.as_str().expect("Scheduler has incorrect type"));
+        let handle = match self.target_scheduler(command, link).await {
+            Some((_, handle)) => handle,
+            None => {
+                return Err(Error::user(Text::NoOpenScheduler));
+            }
+        };
+        let user = options
+            .get("player")
+            .expect("Cannot find player option")
+            .as_str()
+            .expect("Player has incorrect type");
+        let user = UserId::new(user.parse().expect("Error parsing user"));
+        let caller = command.user.id;
+        let is_owner = handle
+            .get(move |scheduler| scheduler.is_owner(caller))
+            .await
+            .ok_or(Error::Scheduler)?;
+        if !is_owner {
+            return Err(Error::user(Text::OwnerOnlyRequire));
+        }
+        command
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        let c = ctx.clone();
+        let reply = handle
+            .update(move |scheduler| {
+                Box::pin(async move { scheduler.toggle_required(&c, user).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        command
+            .edit_response(
+                ctx,
+                EditInteractionResponse::new()
+                    .content(reply)
+                    .allowed_mentions(CreateAllowedMentions::new()),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Send an iCalendar file of a scheduler's finalized or best dates, or the owner a spreadsheet
+    /// of every response
+    async fn export_command(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        let link = options
+            .get("scheduler")
+            .map(|v| v.as_str().expect("Scheduler has incorrect type"));
+        let handle = match self.target_scheduler(command, link).await {
+            Some((_, handle)) => handle,
+            None => {
+                return Err(Error::user(Text::NoSchedulerToExport));
+            }
+        };
+        let locale = Locale::parse(&command.locale).unwrap_or_default();
+        let spreadsheet = options
+            .get("format")
+            .is_some_and(|v| v.as_str().expect("Format has incorrect type") == "spreadsheet");
+        let (filename, data, content) = if spreadsheet {
+            // Unlike the calendar, the matrix shows who answered what
+            let caller = command.user.id;
+            let is_owner = handle
+                .get(move |scheduler| scheduler.is_owner(caller))
+                .await
+                .ok_or(Error::Scheduler)?;
+            if !is_owner {
+                return Err(Error::user(Text::OwnerOnlyExport));
+            }
+            command
+                .create_response(
+                    ctx,
+                    CreateInteractionResponse::Defer(
+                        CreateInteractionResponseMessage::new().ephemeral(true),
+                    ),
+                )
+                .await?;
+            let c = ctx.clone();
+            let matrix = handle
+                .view(move |scheduler| {
+                    Box::pin(async move { scheduler.availability_matrix(&c).await })
+                })
+                .await
+                .ok_or(Error::Scheduler)?;
+            if matrix.len() > limits::UPLOAD_SIZE {
+                return Err(Error::user(Text::TooManyResponses));
+            }
+            (
+                scheduler::MATRIX_FILENAME,
+                matrix,
+                locale.text(Text::MatrixExplained),
+            )
+        } else {
+            // The best dates are the results an embargo keeps back
+            let (c, caller) = (ctx.clone(), command.user.id);
+            let hidden = handle
+                .view(move |scheduler| {
+                    Box::pin(async move { scheduler.hides_results_from(&c, caller).await })
+                })
+                .await
+                .ok_or(Error::Scheduler)?;
+            if hidden {
+                return Err(Error::user(Text::ResultsHidden));
+            }
+            let ics = match handle.get(|scheduler| scheduler.ics()).await {
+                Some(Some(ics)) => ics,
+                Some(None) => return Err(Error::user(Text::NobodyCanMakeIt)),
+                None => return Err(Error::Scheduler),
+            };
+            command
+                .create_response(
+                    ctx,
+                    CreateInteractionResponse::Defer(
+                        CreateInteractionResponseMessage::new().ephemeral(true),
+                    ),
+                )
+                .await?;
+            (EXPORT_FILENAME, ics, locale.text(Text::IcsExplained))
+        };
+        command
+            .create_followup(
+                ctx,
+                CreateInteractionResponseFollowup::new()
+                    .ephemeral(true)
+                    .content(content)
+                    .add_file(CreateAttachment::bytes(data.into_bytes(), filename)),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn cancel_command(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: HashMap<&str, &Value>,
+    ) -> Result {
+        let link = options
+            .get("scheduler")
+            .map(|v| v.as_str().expect("Scheduler has incorrect type"));
+        let (message_id, handle) = match self.target_scheduler(command, link).await {
+            Some(found) => found,
+            None => {
+                return Err(Error::user(Text::NoSchedulerToCancel));
+            }
+        };
+        let caller = command.user.id;
+        let is_owner = handle
+            .get(move |scheduler| scheduler.is_owner(caller))
+            .await
+            .ok_or(Error::Scheduler)?;
+        if !is_owner {
+            return Err(Error::user(Text::OwnerOnlyCancel));
+        }
+        command
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        self.cancel_scheduler(ctx, message_id, handle).await?;
+        command
+            .edit_response(
+                ctx,
+                EditInteractionResponse::new().content(
+                    Locale::parse(&command.locale)
+                        .unwrap_or_default()
+                        .text(Text::Cancelled),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Mark a scheduler's message as cancelled, then forget the scheduler entirely
+    async fn cancel_scheduler(
+        &self,
+        ctx: &Context,
+        message_id: MessageId,
+        handle: SchedulerHandle,
+    ) -> Result {
+        let c = ctx.clone();
+        handle
+            .update(move |scheduler| Box::pin(async move { scheduler.cancel(&c).await }))
+            .await
+            .ok_or(Error::Scheduler)?;
+        self.schedulers.remove(&message_id);
+        handle.stop().await;
+        storage::blocking(&self.storage, move |s| s.delete(message_id))
+            .await
+            .map_err(|e| {
+                Error::Unexpected(format!("cannot delete scheduler {}: {}", message_id, e))
+            })?;
+        info!("scheduler cancelled: {}", message_id);
+        Ok(())
+    }
+
+    /// Queue a scheduler to be posted at `due`, showing the owner a preview of it meanwhile
+    async fn prepare_post(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        options: Options,
+        due: DateTime<Utc>,
+    ) -> Result {
+        let day = due.with_timezone(&Local).naive_local().date();
+        let style = DateStyle {
+            locale: options.locale,
+            format: options.date_format.clone(),
+            timezone: options.timezone.unwrap_or(chrono_tz::UTC),
+        };
+        let dates = scheduler::slots(&options, day)
+            .into_iter()
+            .map(|slot| style.short(slot))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let title = limits::truncate(&options.title, limits::EMBED_TITLE_LENGTH);
+        let body = options.body.clone();
+        let id = command.id.get();
+        let locale = Locale::parse(&command.locale).unwrap_or_default();
+        self.posts
+            .add(ScheduledPost {
+                id,
+                due,
+                owner: command.user.id,
+                guild: command.guild_id,
+                channel: command.channel_id,
+                options,
+            })
+            .await;
+        let mut embed = CreateEmbed::new().title(title).field(
+            locale.text(Text::Dates),
+            limits::truncate(&dates, limits::EMBED_FIELD_VALUE_LENGTH),
+            false,
+        );
+        if let Some(body) = body {
+            embed = embed.description(limits::truncate(&body, limits::EMBED_DESCRIPTION_LENGTH));
+        }
+        command
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(locale.fill(
+                            Text::PostScheduled,
+                            &[&format!("<t:{}:R>", due.timestamp())],
+                        ))
+                        .embed(embed)
+                        .components(vec![CreateActionRow::Buttons(vec![CreateButton::new(
+                            format!("cancel_post {}", id),
+                        )
+                        .style(ButtonStyle::Danger)
+                        .label(locale.text(Text::Cancel))])]),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_cancel_post(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        id: u64,
+    ) -> Result {
+        let locale = Locale::parse(&component.locale).unwrap_or_default();
+        let content = if self.posts.cancel(id, component.user.id).await {
+            locale.text(Text::PostCancelled)
+        } else {
+            locale.text(Text::PostGone)
+        };
+        component
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content(content)
+                        .components(vec![]),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_get_response(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        resp_type: ResponseType,
+        target: Option<MessageId>,
+        prune: bool,
+        on_behalf: Option<Responder>,
+    ) -> Result {
+        // Buttons outside of the scheduler message name the scheduler they respond to
+        let message_id = match (target, resp_type) {
+            (Some(target), _) => target,
+            (None, ResponseType::Normal) => self.primary_id(component.message.id),
+            (None, ResponseType::Blackout) => component
+                .message
+                .message_reference
+                .as_ref()
+                .and_then(|r| r.message_id)
+                .ok_or(Error::Missing("message for DM"))?,
+        };
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let tz = self.timezones.read().await.get(&component.user.id).copied();
+        let (c, comp) = (ctx.clone(), component.clone());
+        // Owners can answer for players who can't themselves, and for guests
+        let user = component.user.id;
+        let proxied = on_behalf.is_some();
+        let responder = on_behalf.unwrap_or(Responder::Discord(user));
+        let for_prompt = responder.clone();
+        let calendar = match responder.user() {
+            Some(user) => self.calendars.read().await.get(&user).cloned(),
+            None => None,
+        }
+        .unwrap_or_default();
+        let state = handle
+            .view(move |scheduler| {
+                Box::pin(async move {
+                    let allowed = if prune {
+                        scheduler
+                            .check_owner(&c, &comp, Text::OwnerOnlyTakeDatesOff)
+                            .await?
+                    } else if proxied {
+                        scheduler
+                            .check_owner(&c, &comp, Text::OwnerOnlyRespondForOthers)
+                            .await?
+                    } else {
+                        scheduler.can_respond(&c, &comp).await?
+                    };
+                    if !allowed {
+                        return Ok(None);
+                    }
+                    // Imported blackouts only stay off limits for respondents; owners edit their own
+                    let (blackout_dates, response) = match resp_type {
+                        ResponseType::Normal => (
+                            scheduler.blocked_dates(),
+                            scheduler.response_of(&for_prompt).unwrap_or_default(),
+                        ),
+                        ResponseType::Blackout => {
+                            let blackout_dates = scheduler.get_blackout_dates();
+                            (blackout_dates.clone(), blackout_dates.clone().into())
+                        }
+                    };
+                    let dates = scheduler.offered_dates();
+                    let unavailable = match resp_type {
+                        ResponseType::Normal if !prune => dates
+                            .iter()
+                            .filter(|date| calendar.blocks(date))
+                            .copied()
+                            .collect(),
+                        _ => HashSet::new(),
+                    };
+                    let locale = Locale::parse(&comp.locale).unwrap_or_default();
+                    let prompt = Prompt {
+                        dates,
+                        labels: scheduler.slot_labels(tz),
+                        note: scheduler.timezone_note(tz, locale),
+                        blackout_dates,
+                        quick: scheduler.quick_actions(),
+                        tentative: resp_type == ResponseType::Normal
+                            && scheduler.allows_tentative(),
+                        ranked: resp_type == ResponseType::Normal && !prune && scheduler.ranked(),
+                        locale,
+                        unavailable,
+                    };
+                    Ok::<_, Error>(Some((prompt, response)))
+                })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        // The scheduler stays free for other interactions while the user responds
+        let (prompt, base) = match state {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+        let locale = prompt.locale;
+        if let Some(edited) =
+            scheduler::get_response(&ctx, component, base.clone(), prompt, resp_type).await?
+        {
+            // Only what this session changed is applied, so concurrent sessions don't clobber
+            // each other
+            let c = ctx.clone();
+            let conflicts = handle
+                .update(move |scheduler| {
+                    Box::pin(async move {
+                        match resp_type {
+                            ResponseType::Normal if proxied => {
+                                scheduler
+                                    .merge_proxied_response(&c, responder, user, &base, &edited)
+                                    .await
+                            }
+                            ResponseType::Normal => {
+                                let conflicts =
+                                    scheduler.merge_response(&c, user, &base, &edited).await;
+                                if prune {
+                                    scheduler.black_out_unavailable(&c, user).await;
+                                }
+                                conflicts
+                            }
+                            ResponseType::Blackout => {
+                                scheduler.merge_blackout(&c, &base, &edited).await
+                            }
+                        }
+                    })
+                })
+                .await
+                .ok_or(Error::Scheduler)?;
+            if conflicts > 0 {
+                component
+                    .create_followup(
+                        &ctx,
+                        CreateInteractionResponseFollowup::new()
+                            .ephemeral(true)
+                            .content(locale.fill(Text::Conflicts, &[&conflicts])),
+                    )
+                    .await?;
+            }
+            let follow_up = match resp_type {
+                // A note would be the owner's, not the player's
+                ResponseType::Normal if proxied => None,
+                ResponseType::Normal => Some((Text::NotePrompt, Text::AddNote, "note")),
+                ResponseType::Blackout => Some((
+                    Text::BlackoutReasonPrompt,
+                    Text::GiveReasons,
+                    "blackout_reasons",
+                )),
+            };
+            if let Some((question, label, action)) = follow_up {
+                component
+                    .create_followup(
+                        &ctx,
+                        CreateInteractionResponseFollowup::new()
+                            .ephemeral(true)
+                            .content(locale.text(question))
+                            .components(vec![CreateActionRow::Buttons(vec![CreateButton::new(
+                                format!("{} {}", action, message_id),
+                            )
+                            .style(ButtonStyle::Secondary)
+                            .label(locale.text(label))])]),
+                    )
+                    .await?;
+            }
+            self.propagate_constraints(&ctx, message_id).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_interest(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        interested: bool,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&component.message.id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .update(move |scheduler| {
+                Box::pin(async move {
+                    if !scheduler.can_respond(&ctx, &component).await? {
+                        return Ok(());
+                    }
+                    component.defer(&ctx).await?;
+                    scheduler
+                        .set_interest(&ctx, component.user.id, interested)
+                        .await;
+                    Ok::<_, Error>(())
+                })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_attendance(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        going: bool,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&component.message.id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .update(move |scheduler| {
+                Box::pin(async move {
+                    if !scheduler.can_respond(&ctx, &component).await? {
+                        return Ok(());
+                    }
+                    component.defer(&ctx).await?;
+                    scheduler
+                        .set_attendance(&ctx, component.user.id, going)
+                        .await;
+                    Ok::<_, Error>(())
+                })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_withdraw(&self, ctx: Context, component: &ComponentInteraction) -> Result {
+        let handle = self
+            .get_scheduler(&component.message.id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let user = component.user.id;
+        let c = ctx.clone();
+        let reply = handle
+            .update(move |scheduler| Box::pin(async move { scheduler.withdraw(&c, user).await }))
+            .await
+            .ok_or(Error::Scheduler)?;
+        self.propagate_constraints(&ctx, self.primary_id(component.message.id))
+            .await?;
+        let locale = Locale::parse(&component.locale).unwrap_or_default();
+        component
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(locale.phrase(&reply)),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_waitlist(&self, ctx: Context, component: &ComponentInteraction) -> Result {
+        let handle = self
+            .get_scheduler(&component.message.id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let user = component.user.id;
+        let c = ctx.clone();
+        let reply = handle
+            .update(move |scheduler| {
+                Box::pin(async move { scheduler.toggle_waitlist(&c, user).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        let locale = Locale::parse(&component.locale).unwrap_or_default();
+        component
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(locale.phrase(&reply)),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_schedule_interest(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&component.message.id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let (c, comp) = (ctx.clone(), component.clone());
+        let is_owner = handle
+            .view(move |scheduler| {
+                Box::pin(async move {
+                    scheduler
+                        .check_owner(&c, &comp, Text::OwnerOnlySchedule)
+                        .await
+                })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        if !is_owner {
+            return Ok(());
+        }
+        let message = post_component_placeholder(&ctx, component).await?;
+        let message_id = message.id;
+        let c = ctx.clone();
+        let scheduler = handle
+            .update(move |interest| {
+                Box::pin(async move {
+                    let scheduler = interest.schedule_interest(message);
+                    interest.update_message(&c).await;
+                    scheduler
+                })
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        self.insert_scheduler(&ctx, message_id, scheduler).await;
+        Ok(())
+    }
+
+    async fn handle_show_details(&self, ctx: Context, component: &ComponentInteraction) -> Result {
+        let handle = self
+            .get_scheduler(&component.message.id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        let timezones = self.timezones.read().await.clone();
+        let calendars = self.calendars.read().await.clone();
+        handle
+            .view(move |scheduler| {
+                Box::pin(async move {
+                    scheduler
+                        .show_details(&ctx, &component, &timezones, &calendars)
+                        .await
+                })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_my_response(&self, ctx: Context, component: &ComponentInteraction) -> Result {
+        let handle = self
+            .get_scheduler(&component.message.id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let user = component.user.id;
+        let locale = Locale::parse(&component.locale).unwrap_or_default();
+        let content = handle
+            .get(move |scheduler| scheduler.my_response(user, locale))
+            .await
+            .ok_or(Error::Scheduler)?;
+        component
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(content),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_close(&self, ctx: Context, component: &ComponentInteraction) -> Result {
+        let handle = self
+            .get_scheduler(&component.message.id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .view(move |scheduler| {
+                Box::pin(async move { scheduler.close_prompt(&ctx, &component).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_close_yes(&self, ctx: Context, component: &ComponentInteraction) -> Result {
+        // The scheduler answers the click itself
+        let message_id = component
+            .message
+            .message_reference
+            .as_ref()
+            .and_then(|r| r.message_id)
+            .ok_or(Error::Missing("scheduler message"))?;
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .update(move |scheduler| {
+                Box::pin(async move { scheduler.handle_close(&ctx, &component).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_cancel_yes(&self, ctx: Context, component: &ComponentInteraction) -> Result {
+        component.defer(&ctx).await?;
+        let message_id = component
+            .message
+            .message_reference
+            .as_ref()
+            .and_then(|r| r.message_id)
+            .ok_or(Error::Missing("scheduler message"))?;
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        self.cancel_scheduler(&ctx, message_id, handle).await?;
+        component
+            .edit_response(
+                &ctx,
+                EditInteractionResponse::new()
+                    .content(
+                        Locale::parse(&component.locale)
+                            .unwrap_or_default()
+                            .text(Text::Cancelled),
+                    )
+                    .components(vec![]),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Refresh the blackouts of every scheduler constrained by `source`
+    async fn propagate_constraints(&self, ctx: &Context, source: MessageId) -> Result {
+        let handle = match self.get_scheduler(&source) {
+            Some(handle) => handle,
+            None => return Ok(()),
+        };
+        let targets: Vec<SchedulerHandle> = match self.constrained.get(&source) {
+            Some(targets) => targets
+                .iter()
+                .filter_map(|id| self.schedulers.get(id).map(|h| h.clone()))
+                .collect(),
+            None => return Ok(()),
+        };
+        for target in targets {
+            let kind = target
+                .get(move |s| {
+                    s.constraint()
+                        .filter(|c| c.source == source)
+                        .map(|c| c.kind)
+                })
+                .await
+                .flatten();
+            let kind = match kind {
+                Some(kind) => kind,
+                None => continue,
+            };
+            let slots = handle
+                .get(move |s| s.exported(kind))
+                .await
+                .ok_or(Error::Scheduler)?;
+            let ctx = ctx.clone();
+            target
+                .update(move |s| Box::pin(async move { s.import_blackouts(&ctx, slots).await }))
+                .await
+                .ok_or(Error::Scheduler)?;
+        }
+        Ok(())
+    }
+
+    async fn constraints_command(&self, ctx: Context, command: CommandInteraction) -> Result {
+        let option = |name: &str| {
+            command
+                .data
+                .options
+                .iter()
+                .find(|o| o.name == name)
+                .map(|o| &o.value)
+                .and_then(|v| v.as_str())
+        };
+        let link = option("scheduler").expect("Cannot find scheduler option");
+        let (id, handle) = match self.find_scheduler_with_id(link) {
+            Some(found) => found,
+            None => {
+                return Err(Error::Missing("scheduler"));
+            }
+        };
+        let source = match option("from") {
+            Some(from) => match self.find_scheduler_with_id(from) {
+                Some(source) => Some(source),
+                None => {
+                    return Err(Error::user(Text::NoSourceScheduler));
+                }
+            },
+            None => None,
+        };
+        let kind = option("use")
+            .and_then(ConstraintKind::parse)
+            .unwrap_or(ConstraintKind::Blackouts);
+        let caller = command.user.id;
+        let owns = handle
+            .get(move |s| s.is_owner(caller))
+            .await
+            .ok_or(Error::Scheduler)?;
+        let owns_source = match &source {
+            Some((_, source)) => source.get(move |s| s.is_owner(caller)).await,
+            None => Some(owns),
+        };
+        if !owns || owns_source != Some(true) {
+            return Err(Error::user(Text::OwnerOnlyLink));
+        }
+        let constraint = match &source {
+            Some((source_id, _)) if *source_id == id => {
+                return Err(Error::user(Text::SelfConstraint));
+            }
+            Some((source_id, _)) => Some(Constraint {
+                source: *source_id,
+                kind,
+            }),
+            None => None,
+        };
+        let slots = match &source {
+            Some((_, source)) => source
+                .get(move |s| s.exported(kind))
+                .await
+                .ok_or(Error::Scheduler)?,
+            None => HashSet::new(),
+        };
+        command
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        let count = slots.len();
+        let c = ctx.clone();
+        let previous = handle
+            .update(move |s| {
+                let previous = s.constraint();
+                Box::pin(async move {
+                    s.set_constraint(&c, constraint, slots).await;
+                    previous
+                })
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        if let Some(previous) = previous {
+            if let Some(mut targets) = self.constrained.get_mut(&previous.source) {
+                targets.remove(&id);
+            }
+        }
+        if let Some(constraint) = constraint {
+            self.constrained
+                .entry(constraint.source)
+                .or_default()
+                .insert(id);
+        }
+        let locale = Locale::parse(&command.locale).unwrap_or_default();
+        let content = match constraint {
+            Some(_) => locale.fill(Text::Linked, &[&count]),
+            None => locale.text(Text::Unlinked).to_owned(),
+        };
+        command
+            .edit_response(&ctx, EditInteractionResponse::new().content(content))
+            .await?;
+        Ok(())
+    }
+
+    /// Replace a scheduler's roster with the players listed in `text`, describing the result
+    async fn apply_roster(
+        &self,
+        ctx: Context,
+        handle: SchedulerHandle,
+        text: String,
+    ) -> Result<String> {
+        let guild = handle.guild();
+        let roster = roster::parse(&ctx, guild, &text).await;
+        let summary = roster.summary();
+        handle
+            .update(move |scheduler| {
+                Box::pin(async move { scheduler.set_roster(&ctx, roster.users).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        Ok(summary)
+    }
+
+    /// Offer a button to respond to a scheduler whose message has none
+    async fn respond_command(&self, ctx: Context, command: CommandInteraction) -> Result {
+        let link = command
+            .data
+            .options
+            .iter()
+            .find(|o| o.name == "scheduler")
+            .map(|o| &o.value)
+            .expect("Cannot find scheduler option")
+            .as_str()
+            .expect("Scheduler has incorrect type");
+        let (message_id, handle) = match self.find_scheduler_with_id(link) {
+            Some(found) => found,
+            None => {
+                return Err(Error::Missing("scheduler"));
+            }
+        };
+        let on_behalf = command
+            .data
+            .options
+            .iter()
+            .find(|o| o.name == "for")
+            .map(|o| &o.value)
+            .map(|v| {
+                let user = v.as_str().expect("For has incorrect type");
+                UserId::new(user.parse().expect("Error parsing user"))
+            });
+        self.offer_response(&ctx, &command, message_id, handle, on_behalf)
+            .await
+    }
+
+    /// Offer a button to respond to the scheduler message a context menu was opened on, for
+    /// when its own buttons are cut off
+    async fn respond_context(&self, ctx: Context, command: CommandInteraction) -> Result {
+        let target = command
+            .data
+            .target_id
+            .ok_or(Error::Missing("message"))?
+            .to_message_id();
+        let message_id = self.primary_id(target);
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::user(Text::NotAScheduler))?;
+        self.offer_response(&ctx, &command, message_id, handle, None)
+            .await
+    }
+
+    async fn offer_response(
+        &self,
+        ctx: &Context,
+        command: &CommandInteraction,
+        message_id: MessageId,
+        handle: SchedulerHandle,
+        on_behalf: Option<UserId>,
+    ) -> Result {
+        let caller = command.user.id;
+        let (open, title, is_owner) = handle
+            .get(move |scheduler| {
+                (
+                    scheduler.takes_responses(),
+                    scheduler.title().to_owned(),
+                    scheduler.is_owner(caller),
+                )
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        if !open {
+            return Err(Error::user(Text::NotTakingResponses));
+        }
+        let locale = Locale::parse(&command.locale).unwrap_or_default();
+        let (content, label, custom_id) = match on_behalf {
+            Some(_) if !is_owner => {
+                return Err(Error::user(Text::OwnerOnlyRespondForOthers));
+            }
+            Some(user) => (
+                locale.fill(Text::TitleFor, &[&title, &user]),
+                locale.text(Text::RespondForThem),
+                format!("respond_for {} {}", message_id, user),
+            ),
+            None => (
+                format!("**{}**", title),
+                locale.text(Text::AddResponse),
+                format!("respond {}", message_id),
+            ),
+        };
+        command
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(content)
+                        .allowed_mentions(CreateAllowedMentions::new())
+                        .components(vec![CreateActionRow::Buttons(vec![CreateButton::new(
+                            custom_id,
+                        )
+                        .label(label)])]),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn close_command(&self, ctx: Context, command: CommandInteraction) -> Result {
+        let link = command
+            .data
+            .options
+            .iter()
+            .find(|o| o.name == "scheduler")
+            .map(|o| &o.value)
+            .map(|v| v.as_str().expect("Scheduler has incorrect type"));
+        let handle = match self.target_scheduler(&command, link).await {
+            Some((_, handle)) => handle,
+            None => {
+                return Err(Error::user(Text::NoSchedulerToClose));
+            }
+        };
+        let caller = command.user.id;
+        let (is_owner, closed) = handle
+            .get(move |scheduler| (scheduler.is_owner(caller), scheduler.is_closed()))
+            .await
+            .ok_or(Error::Scheduler)?;
+        if !is_owner {
+            return Err(Error::user(Text::OwnerOnlyClose));
+        }
+        if closed {
+            return Err(Error::user(Text::SchedulerAlreadyClosed));
+        }
+        command
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        let c = ctx.clone();
+        handle
+            .update(move |scheduler| Box::pin(async move { scheduler.close(&c).await }))
+            .await
+            .ok_or(Error::Scheduler)?;
+        command
+            .edit_response(
+                &ctx,
+                EditInteractionResponse::new().content(
+                    Locale::parse(&command.locale)
+                        .unwrap_or_default()
+                        .text(Text::ClosedReply),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn import_roster(&self, ctx: Context, command: CommandInteraction) -> Result {
+        let link = command
+            .data
+            .options
+            .iter()
+            .find(|o| o.name == "scheduler")
+            .map(|o| &o.value)
+            .expect("Cannot find scheduler option")
+            .as_str()
+            .expect("Scheduler has incorrect type");
+        let handle = match self.find_scheduler(link) {
+            Some(handle) => handle,
+            None => {
+                return Err(Error::Missing("scheduler"));
+            }
+        };
+        let caller = command.user.id;
+        let is_owner = handle
+            .get(move |scheduler| scheduler.is_owner(caller))
+            .await
+            .ok_or(Error::Scheduler)?;
+        if !is_owner {
+            return Err(Error::user(Text::OwnerOnlyRoster));
+        }
+        let attachment = command
+            .data
+            .options
+            .iter()
+            .find(|o| o.name == "file")
+            .and_then(|o| o.value.as_attachment_id())
+            .and_then(|id| command.data.resolved.attachments.get(&id))
+            .expect("Cannot find file option");
+        if attachment.size > roster::MAX_FILE_SIZE {
+            return Err(Error::user(Text::RosterTooLarge));
+        }
+        command
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        let text = attachment.download().await?;
+        let text = String::from_utf8_lossy(&text).into_owned();
+        let summary = self.apply_roster(ctx.clone(), handle, text).await?;
+        command
+            .edit_response(
+                &ctx,
+                EditInteractionResponse::new()
+                    .content(summary)
+                    .allowed_mentions(CreateAllowedMentions::new()),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_roster(
+        &self,
+        ctx: Context,
+        modal: &ModalInteraction,
+        message_id: MessageId,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        modal
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        let text = guild_config::modal_value(modal, "roster").unwrap_or_default();
+        let summary = self.apply_roster(ctx.clone(), handle, text).await?;
+        modal
+            .edit_response(
+                &ctx,
+                EditInteractionResponse::new()
+                    .content(summary)
+                    .allowed_mentions(CreateAllowedMentions::new()),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_note_prompt(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        message_id: MessageId,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .view(move |scheduler| {
+                Box::pin(async move { scheduler.note_prompt(&ctx, &component).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_note(
+        &self,
+        ctx: Context,
+        modal: &ModalInteraction,
+        message_id: MessageId,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let text = guild_config::modal_value(modal, "note").unwrap_or_default();
+        let user = modal.user.id;
+        let reply = handle
+            .update(move |scheduler| {
+                let reply = scheduler.set_note(user, &text);
+                Box::pin(async move { reply })
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        let locale = Locale::parse(&modal.locale).unwrap_or_default();
+        modal
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(locale.text(reply)),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_guest_prompt(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        message_id: MessageId,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .view(move |scheduler| {
+                Box::pin(async move { scheduler.guest_prompt(&ctx, &component).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    /// Offer the owner a picker answering for the guest named in `modal`
+    async fn handle_guest(
+        &self,
+        ctx: Context,
+        modal: &ModalInteraction,
+        message_id: MessageId,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let user = modal.user.id;
+        if !handle
+            .get(move |s| s.is_owner(user))
+            .await
+            .ok_or(Error::Scheduler)?
+        {
+            return Err(Error::user(Text::OwnerOnlyGuests));
+        }
+        let name = guild_config::modal_value(modal, "guest").unwrap_or_default();
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(Error::user(Text::GuestsNeedName));
+        }
+        let name = limits::truncate(name, limits::GUEST_NAME_LENGTH);
+        let locale = Locale::parse(&modal.locale).unwrap_or_default();
+        modal
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(locale.fill(Text::AnsweringFor, &[&name]))
+                        .components(vec![CreateActionRow::Buttons(vec![CreateButton::new(
+                            format!("respond_guest {} {}", message_id, name),
+                        )
+                        .label(locale.text(Text::RespondForThem))])]),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_rank(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        message_id: MessageId,
+        by: TieBreak,
+        update: bool,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .view(move |scheduler| {
+                Box::pin(async move { scheduler.rank_prompt(&ctx, &component, by, update).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_pick(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        message_id: MessageId,
+        slot: Slot,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .update(move |scheduler| {
+                Box::pin(async move { scheduler.pick(&ctx, &component, slot).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_blackout_reason_prompt(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        message_id: MessageId,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .view(move |scheduler| {
+                Box::pin(async move { scheduler.blackout_reason_prompt(&ctx, &component).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_blackout_reasons(
+        &self,
+        ctx: Context,
+        modal: &ModalInteraction,
+        message_id: MessageId,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let user = modal.user.id;
+        if !handle
+            .get(move |s| s.is_owner(user))
+            .await
+            .ok_or(Error::Scheduler)?
+        {
+            return Err(Error::user(Text::OwnerOnlyBlackoutReasons));
+        }
+        modal
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        // Each input is named after the date it gives the reason for
+        let reasons: Vec<(Slot, String)> = handle
+            .get(|s| s.get_blackout_dates())
+            .await
+            .ok_or(Error::Scheduler)?
+            .into_iter()
+            .filter_map(|date| Some((date, guild_config::modal_value(modal, &date.to_string())?)))
+            .collect();
+        let c = ctx.clone();
+        let reply = handle
+            .update(move |scheduler| {
+                Box::pin(async move { scheduler.set_blackout_reasons(&c, reasons).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)?;
+        modal
+            .edit_response(&ctx, EditInteractionResponse::new().content(reply))
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_roster_prompt(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        message_id: MessageId,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .view(move |scheduler| {
+                Box::pin(async move { scheduler.roster_prompt(&ctx, &component).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_remind(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        message_id: MessageId,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .view(move |scheduler| {
+                Box::pin(async move { scheduler.remind(&ctx, &component).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_notify_me(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        message_id: MessageId,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .update(move |scheduler| {
+                Box::pin(async move { scheduler.toggle_notify(&ctx, &component).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_edit_pings(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        message_id: MessageId,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .update(move |scheduler| {
+                Box::pin(async move { scheduler.toggle_edit_pings(&ctx, &component).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    async fn handle_recency(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        message_id: MessageId,
+    ) -> Result {
+        let handle = self
+            .get_scheduler(&message_id)
+            .ok_or(Error::Missing("scheduler"))?;
+        let component = component.clone();
+        handle
+            .update(move |scheduler| {
+                Box::pin(async move { scheduler.toggle_recency(&ctx, &component).await })
+            })
+            .await
+            .ok_or(Error::Scheduler)??;
+        Ok(())
+    }
+
+    /// Show a guild's settings with buttons to change them
+    async fn config_command(&self, ctx: Context, command: CommandInteraction) -> Result {
+        let guild = match command.guild_id {
+            Some(guild) => guild,
+            None => {
+                return Err(Error::user(Text::SettingsPerServer));
+            }
+        };
+        let config = self.guild_config(Some(guild)).await;
+        if !guild_config::can_configure(command.member.as_deref(), &config.admin_roles) {
+            return Err(Error::user(Text::ManagersOnly));
+        }
+        command
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(config.summary())
+                        .allowed_mentions(CreateAllowedMentions::new())
+                        .components(guild_config::setup_buttons(guild)),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn admin_command(&self, ctx: Context, command: CommandInteraction) -> Result {
+        if !self.operators.contains(&command.user.id) {
+            return Err(Error::user(Text::OperatorsOnly));
+        }
+        let option = command
+            .data
+            .options
+            .first()
+            .expect("Cannot find subcommand");
+        // Only a report, with nothing to run
+        if option.name == "storage" {
+            let report = admin::storage_report(&self.storage).await;
+            command
+                .create_response(
+                    &ctx,
+                    CreateInteractionResponse::Message(
+                        CreateInteractionResponseMessage::new()
+                            .ephemeral(true)
+                            .content(report),
+                    ),
+                )
+                .await?;
+            return Ok(());
+        }
+        let operation = Operation::from_command(option)?;
+        let dry_run = operation.dry_run(&self.schedulers, &self.storage).await;
+        command
+            .create_response(
+                &ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(dry_run)
+                        .allowed_mentions(CreateAllowedMentions::new())
+                        .components(vec![CreateActionRow::Buttons(vec![CreateButton::new(
+                            operation.custom_id(),
+                        )
+                        .style(ButtonStyle::Danger)
+                        .label("Run")])]),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_admin(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        operation: Operation,
+    ) -> Result {
+        if !self.operators.contains(&component.user.id) {
+            return Ok(());
+        }
+        component.defer(&ctx).await?;
+        let result = operation.run(&ctx, &self.schedulers, &self.storage).await;
+        info!("maintenance: {}", result);
+        component
+            .edit_response(
+                &ctx,
+                EditInteractionResponse::new()
+                    .content(result)
+                    .components(vec![]),
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn handle_setup(
+        &self,
+        ctx: Context,
+        modal: &ModalInteraction,
+        setting: Setting,
+        guild: GuildId,
+    ) -> Result {
+        let mut configs = self.guild_configs.write().await;
+        let config = configs.entry(guild).or_default();
+        config.handle_modal(&ctx, modal, setting, guild).await
+    }
+
+    async fn dispatch(&self, ctx: Context, interaction: Interaction, reference: &str) -> Result {
+        match interaction {
+            Interaction::Command(command) => {
+                let user = command.user.name.as_str();
+                let command_name = command.data.name.as_str();
+                info!("[{}] {} <{}>", reference, command_name, user);
+                match command_name {
+                    "schedule" => self.schedule_command(ctx, command).await,
+                    "interest" => self.create_interest(ctx, command).await,
+                    "timezone" => self.set_timezone(ctx, command).await,
+                    "unavailable" => self.unavailable_command(ctx, command).await,
+                    "suggest" => self.suggest_days(ctx, command).await,
+                    "roster" => self.import_roster(ctx, command).await,
+                    "close" => self.close_command(ctx, command).await,
+                    "respond" => self.respond_command(ctx, command).await,
+                    "Respond to scheduler" => self.respond_context(ctx, command).await,
+                    "Show availability" => self.show_availability(ctx, command).await,
+                    "constraints" => self.constraints_command(ctx, command).await,
+                    "admin" => self.admin_command(ctx, command).await,
+                    "schedconfig" => self.config_command(ctx, command).await,
+                    _ => Err(Error::Unexpected(format!("command: {}", command_name))),
+                }
+            }
+            Interaction::Component(component) => {
+                let user = component.user.name.as_str();
+                let button_id = component.data.custom_id.as_str();
+                info!("[{}] {} <{}>", reference, button_id, user);
+                // Answering for someone else also carries who the answer is for
+                if let Some(target) = button_id.strip_prefix("respond_for ") {
+                    let unexpected = || Error::Unexpected(format!("button: {}", button_id));
+                    let (target, user) = target.split_once(' ').ok_or_else(unexpected)?;
+                    let target = target.parse().map_err(|_| unexpected())?;
+                    let user = user.parse().map_err(|_| unexpected())?;
+                    return self
+                        .handle_get_response(
+                            ctx,
+                            &component,
+                            ResponseType::Normal,
+                            Some(MessageId::new(target)),
+                            false,
+                            Some(UserId::new(user).into()),
+                        )
+                        .await;
+                }
+                // Picking a ranked date carries the date
+                if let Some(target) = button_id.strip_prefix("pick_date ") {
+                    let unexpected = || Error::Unexpected(format!("button: {}", button_id));
+                    let (target, slot) = target.split_once(' ').ok_or_else(unexpected)?;
+                    let target = target.parse().map_err(|_| unexpected())?;
+                    let slot = Slot::parse(slot).ok_or_else(unexpected)?;
+                    return self
+                        .handle_pick(ctx, &component, MessageId::new(target), slot)
+                        .await;
+                }
+                if let Some(target) = button_id.strip_prefix("respond_guest ") {
+                    let unexpected = || Error::Unexpected(format!("button: {}", button_id));
+                    let (target, name) = target.split_once(' ').ok_or_else(unexpected)?;
+                    let target = target.parse().map_err(|_| unexpected())?;
+                    return self
+                        .handle_get_response(
+                            ctx,
+                            &component,
+                            ResponseType::Normal,
+                            Some(MessageId::new(target)),
+                            false,
+                            Some(Responder::Guest(name.to_owned())),
+                        )
+                        .await;
+                }
+                // Buttons outside of the scheduler message carry the scheduler's message id
+                if let Some((action, target)) = button_id.split_once(' ') {
+                    if let Ok(target) = target.parse::<u64>() {
+                        if let Some(operation) = Operation::parse(action, target) {
+                            return self.handle_admin(ctx, &component, operation).await;
+                        }
+                        if let Some(setting) = Setting::parse(action) {
+                            let guild = GuildId::new(target);
+                            let admin_roles = self.guild_config(Some(guild)).await.admin_roles;
+                            return guild_config::prompt_setting(
+                                &ctx,
+                                &component,
+                                setting,
+                                guild,
+                                &admin_roles,
+                            )
+                            .await;
+                        }
+                        if let Some(by) = TieBreak::parse(action) {
+                            return self
+                                .handle_rank(ctx, &component, MessageId::new(target), by, true)
+                                .await;
+                        }
+                        return match action {
+                            "digest_finalize" | "digest_extend" | "digest_reopen"
+                            | "alert_finalize" => {
+                                self.handle_digest(ctx, &component, action, MessageId::new(target))
+                                    .await
+                            }
+                            "roster" => {
+                                self.handle_roster_prompt(ctx, &component, MessageId::new(target))
+                                    .await
+                            }
+                            "remind" => {
+                                self.handle_remind(ctx, &component, MessageId::new(target))
+                                    .await
+                            }
+                            "guest" => {
+                                self.handle_guest_prompt(ctx, &component, MessageId::new(target))
+                                    .await
+                            }
+                            "rank" => {
+                                let by = TieBreak::Earliest;
+                                self.handle_rank(ctx, &component, MessageId::new(target), by, false)
+                                    .await
+                            }
+                            "note" => {
+                                self.handle_note_prompt(ctx, &component, MessageId::new(target))
+                                    .await
+                            }
+                            "blackout_reasons" => {
+                                self.handle_blackout_reason_prompt(
+                                    ctx,
+                                    &component,
+                                    MessageId::new(target),
+                                )
+                                .await
+                            }
+                            "notify_me" => {
+                                self.handle_notify_me(ctx, &component, MessageId::new(target))
+                                    .await
+                            }
+                            "edit_pings" => {
+                                self.handle_edit_pings(ctx, &component, MessageId::new(target))
+                                    .await
+                            }
+                            "by_recency" => {
+                                self.handle_recency(ctx, &component, MessageId::new(target))
+                                    .await
+                            }
+                            "cancel_post" => self.handle_cancel_post(ctx, &component, target).await,
+                            "firm_yes" | "firm_no" => {
+                                let confirm = action == "firm_yes";
+                                self.handle_firm_up(
+                                    ctx,
+                                    &component,
+                                    confirm,
+                                    MessageId::new(target),
+                                )
+                                .await
+                            }
+                            "respond" => {
+                                self.handle_get_response(
+                                    ctx,
+                                    &component,
+                                    ResponseType::Normal,
+                                    Some(MessageId::new(target)),
+                                    false,
+                                    None,
+                                )
+                                .await
+                            }
+                            "own_availability" => {
+                                self.handle_get_response(
+                                    ctx,
+                                    &component,
+                                    ResponseType::Normal,
+                                    Some(MessageId::new(target)),
+                                    true,
+                                    None,
+                                )
+                                .await
+                            }
+                            _ => Ok(()),
+                        };
+                    }
+                    return Ok(());
+                }
+                match button_id {
+                    "response" => {
+                        self.handle_get_response(
+                            ctx,
+                            &component,
+                            ResponseType::Normal,
+                            None,
+                            false,
+                            None,
+                        )
+                        .await
+                    }
+                    "blackout" => {
+                        self.handle_get_response(
+                            ctx,
+                            &component,
+                            ResponseType::Blackout,
+                            None,
+                            false,
+                            None,
+                        )
+                        .await
+                    }
+                    "interest" => self.handle_interest(ctx, &component, true).await,
+                    "confirm_yes" => self.handle_attendance(ctx, &component, true).await,
+                    "confirm_no" => self.handle_attendance(ctx, &component, false).await,
+                    "not_interested" => self.handle_interest(ctx, &component, false).await,
+                    "schedule_interest" => self.handle_schedule_interest(ctx, &component).await,
+                    "details" => self.handle_show_details(ctx, &component).await,
+                    "my_response" => self.handle_my_response(ctx, &component).await,
+                    "close" => self.handle_close(ctx, &component).await,
+                    "close_yes" => self.handle_close_yes(ctx, &component).await,
+                    "cancel_yes" => self.handle_cancel_yes(ctx, &component).await,
+                    "withdraw" => self.handle_withdraw(ctx, &component).await,
+                    "waitlist" => self.handle_waitlist(ctx, &component).await,
+                    _ => Ok(()),
+                }
+            }
+            Interaction::Modal(modal) => {
+                let user = modal.user.name.as_str();
+                let modal_id = modal.data.custom_id.as_str();
+                info!("[{}] {} <{}>", reference, modal_id, user);
+                let unexpected = || Error::Unexpected(format!("modal: {}", modal_id));
+                let (action, target) = modal_id.split_once(' ').ok_or_else(unexpected)?;
+                let target: u64 = target.parse().map_err(|_| unexpected())?;
+                if action == "roster" {
+                    return self
+                        .handle_roster(ctx, &modal, MessageId::new(target))
+                        .await;
+                }
+                if action == "note" {
+                    return self.handle_note(ctx, &modal, MessageId::new(target)).await;
+                }
+                if action == "guest" {
+                    return self.handle_guest(ctx, &modal, MessageId::new(target)).await;
+                }
+                if action == "blackout_reasons" {
+                    return self
+                        .handle_blackout_reasons(ctx, &modal, MessageId::new(target))
+                        .await;
+                }
+                match Setting::parse(action) {
+                    Some(setting) => {
+                        self.handle_setup(ctx, &modal, setting, GuildId::new(target))
+                            .await
+                    }
+                    None => Err(unexpected()),
+                }
+            }
+            _ => Err(Error::Unexpected(format!("interaction: {:?}", interaction))),
+        }
+    }
+
+    async fn handle_firm_up(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        confirm: bool,
+        message_id: MessageId,
+    ) -> Result {
+        match self.get_scheduler(&message_id) {
+            Some(handle) => {
+                let component = component.clone();
+                handle
+                    .update(move |scheduler| {
+                        Box::pin(async move { scheduler.firm_up(&ctx, &component, confirm).await })
+                    })
+                    .await
+                    .ok_or(Error::Scheduler)??;
+            }
+            None => {
+                component
+                    .create_response(
+                        &ctx,
+                        CreateInteractionResponse::UpdateMessage(
+                            CreateInteractionResponseMessage::new()
+                                .content(
+                                    Locale::parse(&component.locale)
+                                        .unwrap_or_default()
+                                        .text(Text::SchedulerGone),
+                                )
+                                .components(vec![]),
+                        ),
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_digest(
+        &self,
+        ctx: Context,
+        component: &ComponentInteraction,
+        action: &str,
+        message_id: MessageId,
+    ) -> Result {
+        match self.get_scheduler(&message_id) {
+            Some(handle) => {
+                let (component, action) = (component.clone(), action.to_owned());
+                handle
+                    .update(move |scheduler| {
+                        Box::pin(
+                            async move { scheduler.handle_digest(&ctx, &component, &action).await },
+                        )
+                    })
+                    .await
+                    .ok_or(Error::Scheduler)??;
+            }
+            None => {
+                component
+                    .create_response(
+                        &ctx,
+                        CreateInteractionResponse::UpdateMessage(
+                            CreateInteractionResponseMessage::new()
+                                .content(
+                                    Locale::parse(&component.locale)
+                                        .unwrap_or_default()
+                                        .text(Text::SchedulerGone),
+                                )
+                                .components(vec![]),
+                        ),
+                    )
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventHandler for Handler {
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        let reference = format!("{:04x}", interaction.id().get() & 0xffff);
+        let span = interaction_span(&interaction, &reference);
+        async {
+            if self.shutdown.stopping() {
+                info!("[{}] refused while stopping", reference);
+                let restarting = interaction_locale(&interaction).text(Text::Restarting);
+                report_error(&ctx, &interaction, restarting, &reference).await;
+                return;
+            }
+            let dispatch = self.dispatch(ctx.clone(), interaction.clone(), &reference);
+            let failure = match AssertUnwindSafe(dispatch).catch_unwind().await {
+                Ok(Ok(())) => return,
+                Ok(Err(e)) => e,
+                Err(_) => Error::Panicked,
+            };
+            match failure.tier() {
+                Tier::System => error!("[{}] interaction failed: {}", reference, failure),
+                Tier::User | Tier::Owner => info!("[{}] {}", reference, failure),
+            }
+            let reply = failure.reply(interaction_locale(&interaction), &reference);
+            report_error(&ctx, &interaction, &reply, &reference).await;
+        }
+        .instrument(span)
+        .await
+    }
+
+    async fn ready(&self, ctx: Context, ready: Ready) {
+        let (shard, shards) = ready.shard.map_or((0, 1), |info| (info.id.0, info.total));
+        info!(
+            "shard {} of {} ready in {} guilds",
+            shard,
+            shards,
+            ready.guilds.len()
+        );
+
+        // Every shard becomes ready, but the background tasks only need one of their contexts:
+        // they use its HTTP client and data, which all shards share
+        if !self.tasks_started.swap(true, Ordering::SeqCst) {
+            // Closed schedulers are only loaded now, so the bot connects without waiting on them
+            self.load(true).await;
+            tasks::spawn(
+                &ctx,
+                self.schedulers.clone(),
+                self.date_messages.clone(),
+                self.storage.clone(),
+                self.guild_configs.clone(),
+                self.posts.clone(),
+            );
+        }
+
+        // Commands are global and the pass below covers every scheduler, so one shard does both
+        if shard != 0 {
+            return;
+        }
+
+        let create = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "create",
+            "Poll for the best dates over the coming weeks",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "description",
+                "event description",
+            )
+            .required(true),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "body",
+            "longer details shown under the title; markdown works",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Role,
+            "group",
+            "player group",
+        ))
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::Integer, "weeks", "number of weeks")
+                .min_int_value(1)
+                .max_int_value(MAX_WEEKS as u64),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::Integer, "skip", "weeks before start")
+                .min_int_value(0),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "days",
+            "weekdays to include, e.g. Mon+Wed+Fri",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "dates",
+            "exact dates instead of weeks and days, e.g. 2024-06-01,2024-07-04",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "times",
+            "times of day to offer, e.g. 18:00+20:00",
+        ))
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "details",
+                "how detailed results are arranged",
+            )
+            .add_string_choice("Chronological", "chronological")
+            .add_string_choice("Availability tiers", "tiers"),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "blackouts",
+                "what happens to answers on dates you black out",
+            )
+            .add_string_choice("Hide them until the date is back", "hide")
+            .add_string_choice("Drop them", "prune"),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "hide_results",
+            "hide results until this many responses, e.g. 4 or 50%",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "layout",
+            "sections to show in order, e.g. results,stats,notes,activity",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "notes",
+            "notes shown on the scheduler",
+        ))
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "mentions",
+                "respondents to list before collapsing the rest",
+            )
+            .min_int_value(0)
+            .max_int_value(MAX_MENTION_LIMIT as u64),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "quorum",
+                "players needed for a date to go ahead; closes once one has them",
+            )
+            .min_int_value(1),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "deadline",
+            "close automatically then, e.g. 2024-07-15 18:00",
+        ))
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "remind_after",
+                "days after posting to ping everyone who hasn't responded",
+            )
+            .min_int_value(1)
+            .max_int_value(MAX_WEEKS as u64 * 7),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "voice",
+                "voice or stage channel sessions are held in",
+            )
+            .channel_types(vec![ChannelType::Voice, ChannelType::Stage]),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "firm_up",
+                "allow tentative picks, asking about them this many days ahead",
+            )
+            .min_int_value(1)
+            .max_int_value(MAX_WEEKS as u64 * 7),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::String, "mode", "how players answer")
+                .add_string_choice("Pick every date they can make", "dates")
+                .add_string_choice("Rank their top 3 dates", "ranked")
+                .add_string_choice("Use /respond; the message has no buttons", "read_only"),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "anonymous",
+            "only show counts; you see who responded in details",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "reactions",
+            "also post a message per date; 👍 reactions count as responses",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "thread",
+            "open a thread that responses and changes are posted to",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "announcement",
+            "text posted when a date is finalized, e.g. {title} is on {when}! {attendees}",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "post_at",
+            "post later instead of now, e.g. 2024-07-15 09:00",
+        ));
+        let template = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "template",
+            "Save options under a name to create schedulers from later",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "name",
+                "what to call the template; saving it again replaces it",
+            )
+            .required(true),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "prefix",
+            "put in front of each description, e.g. D&D:",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "days",
+            "weekdays to include, e.g. Mon+Wed+Fri",
+        ))
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::Integer, "weeks", "number of weeks")
+                .min_int_value(1)
+                .max_int_value(MAX_WEEKS as u64),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Role,
+            "group",
+            "player group",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "timezone",
+            "IANA timezone times are in, e.g. Europe/Berlin",
+        ))
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "threshold",
+                "players a date needs to be marked workable",
+            )
+            .min_int_value(1),
+        );
+        let from_template = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "from-template",
+            "Poll for the best dates using a saved template",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::String, "name", "the template's name")
+                .required(true),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "description",
+                "event description, after the template's prefix",
+            )
+            .required(true),
+        );
+        let confirm = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "confirm",
+            "Ask who can make it on a date that was already chosen",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "description",
+                "event description",
+            )
+            .required(true),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "body",
+            "longer details shown under the title; markdown works",
+        ))
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "date",
+                "the date, e.g. 2024-07-13 or 2024-07-13 18:00",
+            )
+            .required(true),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Role,
+            "group",
+            "player group",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "notes",
+            "notes shown on the scheduler",
+        ));
+        let intersect = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "intersect",
+            "Post the dates among the best of two schedulers",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "first",
+                "link to the first scheduler message",
+            )
+            .required(true),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "second",
+                "link to the second scheduler message",
+            )
+            .required(true),
+        );
+        let list = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "list",
+            "List the open schedulers here",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "where",
+                "defaults to this channel",
+            )
+            .add_string_choice("This channel", "channel")
+            .add_string_choice("This server", "server"),
+        );
+        let rebind = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "rebind",
+            "Repost a scheduler whose message is broken, keeping responses",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "scheduler",
+                "link to the scheduler message",
+            )
+            .required(true),
+        );
+        let crosspost = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "crosspost",
+            "Post a copy of a scheduler here; answers on either count",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "scheduler",
+                "link to the scheduler message",
+            )
+            .required(true),
+        );
+        let alert = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "alert",
+            "Get a DM once a date has enough people available",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "available",
+                "how many need to be available",
+            )
+            .min_int_value(1)
+            .required(true),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "scheduler",
+            "link to the scheduler message; defaults to your latest here",
+        ));
+        let require = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "require",
+            "Mark a player dates can't go ahead without, or unmark them",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::User, "player", "who has to be there")
+                .required(true),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "scheduler",
+            "link to the scheduler message; defaults to your latest here",
+        ));
+        let addowner = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "addowner",
+            "Let someone else manage a scheduler with you",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::User,
+                "user",
+                "who can set blackouts, edit, close and export",
+            )
+            .required(true),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "scheduler",
+            "link to the scheduler message; defaults to your latest here",
+        ));
+        let export = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "export",
+            "Get a calendar file of the finalized or best dates",
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "format",
+                "what to export; owners can get every response",
+            )
+            .add_string_choice("Calendar (.ics)", "calendar")
+            .add_string_choice("Spreadsheet of responses (.csv)", "spreadsheet"),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "scheduler",
+            "link to the scheduler message; defaults to your latest here",
+        ));
+        let cancel = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "cancel",
+            "Call off a scheduler and delete it",
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "scheduler",
+            "link to the scheduler message; defaults to your latest here",
+        ));
+        let edit = CreateCommandOption::new(
+            CommandOptionType::SubCommand,
+            "edit",
+            "Change an open scheduler",
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "scheduler",
+            "link to the scheduler message; defaults to your latest here",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::String,
+            "description",
+            "new event description",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Role,
+            "group",
+            "new player group",
+        ))
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "weeks",
+                "number of weeks from the first one",
+            )
+            .min_int_value(1)
+            .max_int_value(MAX_WEEKS as u64),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "threshold",
+                "players a date needs to be marked workable; 0 for none",
+            )
+            .min_int_value(0),
+        )
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "recurring",
+            "post a new scheduler for as many weeks once the last date passes",
+        ))
+        .add_sub_option(CreateCommandOption::new(
+            CommandOptionType::Boolean,
+            "blackouts",
+            "list blacked out dates and their reasons on the message",
+        ))
+        .add_sub_option(
+            CreateCommandOption::new(CommandOptionType::String, "mode", "how players answer")
+                .add_string_choice("Pick every date they can make", "dates")
+                .add_string_choice("Rank their top 3 dates", "ranked"),
+        )
+        .add_sub_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "capacity",
+                "most who can attend; more can join a waitlist; 0 for no limit",
+            )
+            .min_int_value(0),
+        );
+        Command::create_global_command(
+            &ctx,
+            CreateCommand::new("schedule")
+                .description("Create a scheduler")
+                .add_option(create)
+                .add_option(template)
+                .add_option(from_template)
+                .add_option(confirm)
+                .add_option(intersect)
+                .add_option(list)
+                .add_option(rebind)
+                .add_option(crosspost)
+                .add_option(alert)
+                .add_option(require)
+                .add_option(addowner)
+                .add_option(export)
+                .add_option(cancel)
+                .add_option(edit),
+        )
+        .await
+        .expect("Cannot create command");
+
+        Command::create_global_command(
+            &ctx,
+            CreateCommand::new("interest")
+                .description("Check who is interested in an event")
+                .add_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "description",
+                        "event description",
+                    )
+                    .required(true),
+                )
+                .add_option(CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "body",
+                    "longer details shown under the title; markdown works",
+                ))
+                .add_option(CreateCommandOption::new(
+                    CommandOptionType::Role,
+                    "group",
+                    "player group",
+                )),
+        )
+        .await
+        .expect("Cannot create command");
+
+        Command::create_global_command(
+            &ctx,
+            CreateCommand::new("timezone")
+                .description("Set your timezone, used to show the times of day you're offered")
+                .add_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "name",
+                        "IANA timezone, e.g. Europe/Berlin",
+                    )
+                    .required(true),
+                ),
+        )
+        .await
+        .expect("Cannot create command");
+
+        Command::create_global_command(
+            &ctx,
+            CreateCommand::new("unavailable")
+                .description("Keep a personal calendar of when you can't play, for every scheduler")
+                .add_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::SubCommand,
+                        "add",
+                        "Mark weekdays or dates you're never available",
+                    )
+                    .add_sub_option(CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "weekdays",
+                        "weekdays, e.g. Mon or Mon+Wed",
+                    ))
+                    .add_sub_option(CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "dates",
+                        "dates, e.g. 2024-06-01,2024-06-08",
+                    )),
+                )
+                .add_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::SubCommand,
+                        "remove",
+                        "Take weekdays or dates off your personal calendar",
+                    )
+                    .add_sub_option(CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "weekdays",
+                        "weekdays, e.g. Mon or Mon+Wed",
+                    ))
+                    .add_sub_option(CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "dates",
+                        "dates, e.g. 2024-06-01,2024-06-08",
+                    )),
+                )
+                .add_option(CreateCommandOption::new(
+                    CommandOptionType::SubCommand,
+                    "list",
+                    "Show your personal calendar",
+                )),
+        )
+        .await
+        .expect("Cannot create command");
+
+        Command::create_global_command(
+            &ctx,
+            CreateCommand::new("suggest")
+                .description("Suggest days with the best turnout in past schedulers")
+                .add_option(CreateCommandOption::new(
+                    CommandOptionType::Role,
+                    "group",
+                    "player group",
+                )),
+        )
+        .await
+        .expect("Cannot create command");
+
+        Command::create_global_command(
+            &ctx,
+            CreateCommand::new("close")
+                .description("Close a scheduler and show the winning dates")
+                .add_option(CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "scheduler",
+                    "link to the scheduler message; defaults to your latest here",
+                )),
+        )
+        .await
+        .expect("Cannot create command");
+
+        Command::create_global_command(
+            &ctx,
+            CreateCommand::new("roster")
+                .description("Upload the players expected to respond to a scheduler")
+                .add_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "scheduler",
+                        "link to the scheduler message",
+                    )
+                    .required(true),
+                )
+                .add_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::Attachment,
+                        "file",
+                        "text file of mentions, IDs or names, one per line",
+                    )
+                    .required(true),
+                ),
+        )
+        .await
+        .expect("Cannot create command");
+
+        Command::create_global_command(
+            &ctx,
+            CreateCommand::new("respond")
+                .description("Respond to a scheduler posted without buttons")
+                .add_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "scheduler",
+                        "link to the scheduler message",
+                    )
+                    .required(true),
+                )
+                .add_option(CreateCommandOption::new(
+                    CommandOptionType::User,
+                    "for",
+                    "owners only: a player to answer for",
+                )),
+        )
+        .await
+        .expect("Cannot create command");
+
+        Command::create_global_command(
+            &ctx,
+            CreateCommand::new("Respond to scheduler").kind(CommandType::Message),
+        )
+        .await
+        .expect("Cannot create command");
+
+        Command::create_global_command(
+            &ctx,
+            CreateCommand::new("Show availability").kind(CommandType::User),
+        )
+        .await
+        .expect("Cannot create command");
+
+        Command::create_global_command(
+            &ctx,
+            CreateCommand::new("constraints")
+                .description("Black out dates from another of your schedulers, kept in sync")
+                .add_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "scheduler",
+                        "link to the scheduler to constrain",
+                    )
+                    .required(true),
+                )
+                .add_option(CreateCommandOption::new(
+                    CommandOptionType::String,
+                    "from",
+                    "link to the scheduler to take dates from; omit to unlink",
+                ))
+                .add_option(
+                    CreateCommandOption::new(
+                        CommandOptionType::String,
+                        "use",
+                        "which of its dates to black out",
+                    )
+                    .add_string_choice("Its blackout dates", "blackouts")
+                    .add_string_choice("Dates nobody can make", "unavailable"),
+                ),
+        )
+        .await
+        .expect("Cannot create command");
+
+        Command::create_global_command(
+            &ctx,
+            CreateCommand::new("schedconfig")
+                .description("Change this server's scheduler settings and defaults"),
+        )
+        .await
+        .expect("Cannot create command");
+
+        if !self.operators.is_empty() {
+            Command::create_global_command(
+                &ctx,
+                CreateCommand::new("admin")
+                    .description("Maintenance for bot operators")
+                    .default_member_permissions(Permissions::empty())
+                    .add_option(
+                        CreateCommandOption::new(
+                            CommandOptionType::SubCommand,
+                            "close",
+                            "Close schedulers older than a number of weeks",
+                        )
+                        .add_sub_option(
+                            CreateCommandOption::new(
+                                CommandOptionType::Integer,
+                                "weeks",
+                                "age in weeks",
+                            )
+                            .min_int_value(1)
+                            .required(true),
+                        ),
+                    )
+                    .add_option(
+                        CreateCommandOption::new(
+                            CommandOptionType::SubCommand,
+                            "rerender",
+                            "Re-render every scheduler in a guild",
+                        )
+                        .add_sub_option(
+                            CreateCommandOption::new(
+                                CommandOptionType::String,
+                                "guild",
+                                "guild id",
+                            )
+                            .required(true),
+                        ),
+                    )
+                    .add_option(CreateCommandOption::new(
+                        CommandOptionType::SubCommand,
+                        "storage",
+                        "Show storage health and the last backup",
+                    ))
+                    .add_option(
+                        CreateCommandOption::new(
+                            CommandOptionType::SubCommand,
+                            "purge_archives",
+                            "Delete archived schedulers",
+                        )
+                        .add_sub_option(CreateCommandOption::new(
+                            CommandOptionType::String,
+                            "guild",
+                            "only this guild id",
+                        )),
+                    ),
+            )
+            .await
+            .expect("Cannot create command");
+        }
+
+        let handles: Vec<SchedulerHandle> = self.schedulers.iter().map(|h| h.clone()).collect();
+        for handle in handles {
+            // Finish schedulers that were saved but never rendered before a restart
+            if handle.get(|s| s.is_pending()).await.unwrap_or(false) {
+                render_pending(&ctx, &handle).await;
+            } else if self.refresh {
+                let ctx = ctx.clone();
+                handle
+                    .view(move |scheduler| {
+                        Box::pin(async move { scheduler.update_message(&ctx).await })
+                    })
+                    .await;
+            }
+        }
+    }
+
+    async fn guild_create(&self, ctx: Context, guild: Guild, _is_new: Option<bool>) {
+        let mut configs = self.guild_configs.write().await;
+        if let Some(config) = configs.get_mut(&guild.id) {
+            if config.removed_at.take().is_some() {
+                info!("rejoined guild, purge cancelled: {}", guild.id);
+                if let Err(e) = guild_config::write_file(guild.id, config) {
+                    error!("Cannot save config of guild {}: {}", guild.id, e);
+                }
+            }
+            return;
+        }
+        if !guild_config::is_new_guild(&guild) {
+            return;
+        }
+        info!("joined guild: {}", guild.id);
+        let config = GuildConfig::default();
+        if let Err(e) = guild_config::write_file(guild.id, &config) {
+            error!("Cannot save config of guild {}: {}", guild.id, e);
+        }
+        configs.insert(guild.id, config);
+        drop(configs);
+        guild_config::send_setup(&ctx, &guild).await;
+    }
+
+    async fn guild_delete(
+        &self,
+        _ctx: Context,
+        incomplete: UnavailableGuild,
+        _full: Option<Guild>,
+    ) {
+        // An unavailable guild is an outage rather than the bot being removed
+        if incomplete.unavailable {
+            return;
+        }
+        info!("removed from guild: {}", incomplete.id);
+        let mut configs = self.guild_configs.write().await;
+        let config = configs.entry(incomplete.id).or_default();
+        config.removed_at = Some(chrono::Utc::now());
+        if let Err(e) = guild_config::write_file(incomplete.id, config) {
+            error!("Cannot save config of guild {}: {}", incomplete.id, e);
+        }
+    }
+
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        self.handle_reaction(ctx, reaction, true).await;
+    }
+
+    async fn reaction_remove(&self, ctx: Context, reaction: Reaction) {
+        self.handle_reaction(ctx, reaction, false).await;
+    }
+
+    async fn message_delete(
+        &self,
+        ctx: Context,
+        _channel_id: ChannelId,
+        deleted_message_id: MessageId,
+        _guild_id: Option<GuildId>,
+    ) {
+        if let Some((_, scheduler)) = self.mirrors.remove(&deleted_message_id) {
+            info!("scheduler copy deleted: {}", deleted_message_id);
+            if let Some(handle) = self.get_scheduler(&scheduler) {
+                handle
+                    .update(move |s| Box::pin(async move { s.remove_mirror(deleted_message_id) }))
+                    .await;
+            }
+            return;
+        }
+        if let Some((_, handle)) = self.schedulers.remove(&deleted_message_id) {
+            info!("scheduler message deleted: {}", deleted_message_id);
+            self.date_messages
+                .retain(|_, scheduler| *scheduler != deleted_message_id);
+            self.mirrors
+                .retain(|_, scheduler| *scheduler != deleted_message_id);
+            for mut targets in self.constrained.iter_mut() {
+                targets.remove(&deleted_message_id);
+            }
+            // Schedulers that took blackouts from this one go back to their own
+            if let Some((_, targets)) = self.constrained.remove(&deleted_message_id) {
+                for target in &targets {
+                    if let Some(target) = self.get_scheduler(target) {
+                        let c = ctx.clone();
+                        target
+                            .update(move |s| {
+                                Box::pin(
+                                    async move { s.set_constraint(&c, None, HashSet::new()).await },
+                                )
+                            })
+                            .await;
+                    }
+                }
+            }
+            // Let queued operations finish saving before the file is moved
+            handle.stop().await;
+            if let Err(e) =
+                storage::blocking(&self.storage, move |s| s.archive(deleted_message_id)).await
+            {
+                error!("Cannot archive scheduler {}: {}", deleted_message_id, e);
+            }
+        }
+    }
+}
+
+#[derive(Parser)]
+#[clap(author, version, about, long_about = None)]
+struct Cli {
+    #[clap(long, action)]
+    refresh: bool,
+    /// Delete a guild's data immediately, without waiting for its retention window
+    #[clap(long, value_name = "GUILD_ID", value_parser)]
+    purge_guild: Vec<u64>,
+    /// User allowed to run the `/admin` maintenance commands
+    #[clap(long, value_name = "USER_ID", value_parser)]
+    operator: Vec<u64>,
+    /// Longest scheduler title allowed; Discord caps embed titles at 256 characters
+    #[clap(long, value_parser, default_value_t = limits::EMBED_TITLE_LENGTH)]
+    max_title_length: usize,
+    /// Serve read-only JSON about schedulers, and a health check, on this port of localhost
+    #[clap(long, value_name = "PORT", value_parser)]
+    api_port: Option<u16>,
+    /// Log a JSON object per line, with span fields, instead of plain text
+    #[clap(long, action)]
+    log_json: bool,
+}
+
+#[tokio::main]
+async fn main() {
     let cli = Cli::parse();
+    let logs = tracing_subscriber::fmt().with_env_filter(EnvFilter::new("scheduler=info"));
+    if cli.log_json {
+        logs.json().init();
+    } else {
+        logs.init();
+    }
 
     dotenv().ok();
     // Configure the client with your Discord bot token in the environment.
     let token = env::var("DISCORD_TOKEN").expect("Expected a token in the environment");
 
     // Build our client.
-    let intents = GatewayIntents::GUILD_MESSAGES;
+    let intents = GatewayIntents::GUILDS
+        | GatewayIntents::GUILD_MESSAGES
+        | GatewayIntents::GUILD_MESSAGE_REACTIONS;
+    let operators = cli.operator.into_iter().map(UserId::new).collect();
+    let handler = Handler::new(cli.refresh, operators, cli.max_title_length).await;
+    for guild in cli.purge_guild {
+        handler.purge_guild(GuildId::new(guild)).await;
+    }
+    if let Some(port) = cli.api_port {
+        tokio::spawn(api::serve(port, handler.schedulers.clone()));
+    }
+    let notifier = Notifier::new(handler.guild_configs.clone());
+    let (schedulers, stopping) = (handler.schedulers.clone(), handler.shutdown.clone());
+    let debouncer = Debouncer::default();
     let mut client = Client::builder(token, intents)
-        .event_handler(Handler::new(cli.refresh))
+        .event_handler(handler)
+        .type_map_insert::<Notifier>(notifier)
+        .type_map_insert::<Shutdown>(stopping.clone())
+        .type_map_insert::<Debouncer>(debouncer.clone())
         .await
         .expect("Error creating client");
 
@@ -416,10 +4943,200 @@ async fn main() {
         error!("{}", p);
     }));
 
-    // Finally, start a single shard, and start listening to events.
-    // Shards will automatically attempt to reconnect, and will perform
-    // exponential backoff until it reconnects.
-    if let Err(why) = client.start().await {
-        error!("Client error: {:?}", why);
+    let (shard_manager, http) = (client.shard_manager.clone(), client.http.clone());
+    // Start as many shards as Discord recommends for the bot's guild count. Shards will
+    // automatically attempt to reconnect, and will perform exponential backoff until they do.
+    tokio::select! {
+        result = client.start_autosharded() => {
+            if let Err(why) = result {
+                error!("Client error: {:?}", why);
+            }
+        }
+        () = shutdown::signal() => {
+            info!("Shutting down");
+            stopping.begin(&http).await;
+            // Stopping a scheduler finishes its queued operations and writes any unsaved changes
+            let handles: Vec<_> = schedulers.iter().map(|entry| entry.value().clone()).collect();
+            futures::future::join_all(handles.iter().map(|handle| handle.stop())).await;
+            debouncer.flush().await;
+            shard_manager.shutdown_all().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::harness::{self, Recorder};
+    use serde_json::json;
+
+    const OWNER: u64 = 100;
+    const PLAYER: u64 = 101;
+
+    async fn dispatch(handler: &Handler, ctx: &Context, interaction: Interaction) {
+        handler
+            .dispatch(ctx.clone(), interaction, "test")
+            .await
+            .unwrap();
+    }
+
+    /// Dispatch an interaction whose handler waits for clicks on its reply, making them while it
+    /// waits
+    async fn dispatch_picker(
+        handler: &Arc<Handler>,
+        ctx: &Context,
+        recorder: &Recorder,
+        interaction: Interaction,
+        clicks: &[&str],
+        user: u64,
+    ) {
+        let token = interaction.token().to_owned();
+        let (handler, c) = (handler.clone(), ctx.clone());
+        let session = tokio::spawn(async move { dispatch(&handler, &c, interaction).await });
+        for click in clicks {
+            recorder.click_waiting(&token, click, user).await;
+        }
+        session.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn create_respond_black_out_and_close() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let handler = Arc::new(Handler::in_memory());
+
+        let create = recorder.command(
+            "schedule",
+            json!([{
+                "name": "create",
+                "type": 1,
+                "options": [{"name": "description", "type": 3, "value": "Game night"}],
+            }]),
+            OWNER,
+        );
+        dispatch(&handler, &ctx, create).await;
+        let id = *handler
+            .schedulers
+            .iter()
+            .next()
+            .expect("No scheduler")
+            .key();
+        let handle = handler.get_scheduler(&id).unwrap();
+        let dates = handle.get(|s| s.offered_dates()).await.unwrap();
+        assert!(harness::text(&recorder.last_edit(id).unwrap()).contains("Game night"));
+
+        let respond = recorder.click(id, "response", PLAYER);
+        let clicks = ["select 0", "submit"];
+        dispatch_picker(&handler, &ctx, &recorder, respond, &clicks, PLAYER).await;
+        let turnout = handle.get(|s| s.turnout()).await.unwrap();
+        assert_eq!(turnout[0], (dates[0], 1, 1));
+        assert_eq!(turnout[1], (dates[1], 0, 1));
+
+        // Owners find the blackout button with the details
+        let details = recorder.click(id, "details", OWNER);
+        let details_token = details.token().to_owned();
+        dispatch(&handler, &ctx, details).await;
+        let blackout = recorder.click_reply(&details_token, "blackout", OWNER);
+        let clicks = ["select 1", "submit"];
+        dispatch_picker(&handler, &ctx, &recorder, blackout, &clicks, OWNER).await;
+        let blackouts = handle.get(|s| s.get_blackout_dates()).await.unwrap();
+        assert_eq!(blackouts, HashSet::from([dates[1]]));
+
+        let close = recorder.click(id, "close", OWNER);
+        let close_token = close.token().to_owned();
+        dispatch(&handler, &ctx, close).await;
+        let confirm = recorder.click_reply(&close_token, "close_yes", OWNER);
+        let confirm_token = confirm.token().to_owned();
+        dispatch(&handler, &ctx, confirm).await;
+        assert!(handle.get(|s| s.is_closed()).await.unwrap());
+        assert_eq!(recorder.replies(&confirm_token), vec!["Closed!"]);
+    }
+
+    #[tokio::test]
+    async fn intersect_ignores_other_servers() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let handler = Arc::new(Handler::in_memory());
+
+        for description in ["Game night", "Raid night"] {
+            let create = recorder.command(
+                "schedule",
+                json!([{
+                    "name": "create",
+                    "type": 1,
+                    "options": [{"name": "description", "type": 3, "value": description}],
+                }]),
+                OWNER,
+            );
+            dispatch(&handler, &ctx, create).await;
+        }
+        let mut ids: Vec<_> = handler.schedulers.iter().map(|s| *s.key()).collect();
+        ids.sort();
+
+        // Move the second scheduler to another server
+        let handle = handler.get_scheduler(&ids[1]).unwrap();
+        let mut json = serde_json::to_value(handle.take().await.unwrap()).unwrap();
+        json["guild"] = json!("42");
+        let moved: Scheduler = serde_json::from_value(json).unwrap();
+        let handle = SchedulerHandle::spawn(ids[1], moved, handler.storage.clone());
+        handler.schedulers.insert(ids[1], handle);
+
+        let intersect = recorder.command(
+            "schedule",
+            json!([{
+                "name": "intersect",
+                "type": 1,
+                "options": [
+                    {"name": "first", "type": 3, "value": ids[0].to_string()},
+                    {"name": "second", "type": 3, "value": ids[1].to_string()},
+                ],
+            }]),
+            OWNER,
+        );
+        let result = handler.dispatch(ctx.clone(), intersect, "test").await;
+        assert!(
+            matches!(result, Err(Error::User(p)) if p == Text::MissingSecond.into()),
+            "Intersected another server's scheduler"
+        );
+    }
+
+    #[tokio::test]
+    async fn deleting_a_source_unlinks_its_targets() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let handler = Arc::new(Handler::in_memory());
+
+        for description in ["Game night", "Raid night"] {
+            let create = recorder.command(
+                "schedule",
+                json!([{
+                    "name": "create",
+                    "type": 1,
+                    "options": [{"name": "description", "type": 3, "value": description}],
+                }]),
+                OWNER,
+            );
+            dispatch(&handler, &ctx, create).await;
+        }
+        let mut ids: Vec<_> = handler.schedulers.iter().map(|s| *s.key()).collect();
+        ids.sort();
+        let link = recorder.command(
+            "constraints",
+            json!([
+                {"name": "scheduler", "type": 3, "value": ids[1].to_string()},
+                {"name": "from", "type": 3, "value": ids[0].to_string()},
+            ]),
+            OWNER,
+        );
+        dispatch(&handler, &ctx, link).await;
+        let target = handler.get_scheduler(&ids[1]).unwrap();
+        assert!(target.get(|s| s.constraint()).await.unwrap().is_some());
+
+        let channel = ChannelId::new(1);
+        handler
+            .message_delete(ctx.clone(), channel, ids[0], None)
+            .await;
+        assert!(handler.constrained.get(&ids[0]).is_none());
+        assert!(target.get(|s| s.constraint()).await.unwrap().is_none());
     }
 }
@@ -1,36 +1,49 @@
+#[cfg(any(test, feature = "test-harness"))]
+use crate::harness::{Call, Recorder};
+
 use serde::{Deserialize, Serialize};
 use serenity::builder::EditMessage;
-use serenity::http::CacheHttp;
-use serenity::json;
-use serenity::json::Value;
+use serenity::client::Context;
 use serenity::model::channel::Message;
 use serenity::model::id::{ChannelId, MessageId};
 
 /// Lightweight version of [`serenity::model::channel::Message`] that only supports [`edit`](MessageShim::edit)
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct MessageShim {
     message_id: MessageId,
     channel_id: ChannelId,
 }
 
 impl MessageShim {
+    pub fn new(channel_id: ChannelId, message_id: MessageId) -> Self {
+        Self {
+            message_id,
+            channel_id,
+        }
+    }
+
+    pub fn id(&self) -> MessageId {
+        self.message_id
+    }
+
+    pub fn channel_id(&self) -> ChannelId {
+        self.channel_id
+    }
+
     /// See [`serenity::model::channel::Message::edit`]
-    pub async fn edit<'a, F>(&self, cache_http: impl CacheHttp, f: F) -> serenity::Result<()>
-    where
-        F: for<'b> FnOnce(&'b mut EditMessage<'a>) -> &'b mut EditMessage<'a>,
-    {
-        let mut builder = EditMessage::default();
-        f(&mut builder);
-        let map = json::hashmap_to_json_map(builder.0);
-
-        let http = cache_http.http();
-        http.edit_message_and_attachments(
-            self.channel_id.0,
-            self.message_id.0,
-            &Value::from(map),
-            builder.1,
-        )
-        .await?;
+    pub async fn edit(&self, ctx: &Context, builder: EditMessage) -> serenity::Result<()> {
+        #[cfg(any(test, feature = "test-harness"))]
+        if let Some(recorder) = Recorder::get(ctx).await {
+            recorder.record(Call::EditMessage {
+                message: self.message_id,
+                body: serenity::json::to_value(&builder)?,
+            });
+            return Ok(());
+        }
+
+        self.channel_id
+            .edit_message(ctx, self.message_id, builder)
+            .await?;
         Ok(())
     }
 }
@@ -0,0 +1,220 @@
+//! Outgoing notifications, held back during a guild's quiet hours
+
+use crate::guild_config::GuildConfig;
+#[cfg(any(test, feature = "test-harness"))]
+use crate::harness::{Call, Recorder};
+use crate::DATA_DIR;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serenity::builder::{
+    CreateActionRow, CreateAllowedMentions, CreateAttachment, CreateButton, CreateMessage,
+};
+use serenity::client::Context;
+use serenity::model::application::ButtonStyle;
+use serenity::model::id::{ChannelId, GuildId, RoleId, UserId};
+use serenity::prelude::TypeMapKey;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{error, info};
+
+const PENDING_FILE: &str = "notifications.json";
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum Recipient {
+    Channel(ChannelId),
+    /// Sent as a DM
+    User(UserId),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Button {
+    pub label: String,
+    pub custom_id: String,
+    pub style: ButtonStyle,
+    pub disabled: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Attachment {
+    pub filename: String,
+    pub data: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub recipient: Recipient,
+    pub content: String,
+    /// Users the content may ping
+    pub mentions: Vec<UserId>,
+    /// Roles the content may ping
+    #[serde(default)]
+    pub roles: Vec<RoleId>,
+    pub attachment: Option<Attachment>,
+    pub buttons: Vec<Button>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Pending {
+    due: DateTime<Utc>,
+    notification: Notification,
+}
+
+#[derive(Clone)]
+pub struct Notifier {
+    guild_configs: Arc<RwLock<HashMap<GuildId, GuildConfig>>>,
+    pending: Arc<Mutex<Vec<Pending>>>,
+}
+
+impl TypeMapKey for Notifier {
+    type Value = Notifier;
+}
+
+fn file_path() -> PathBuf {
+    let mut path: PathBuf = DATA_DIR.into();
+    path.push(PENDING_FILE);
+    path
+}
+
+fn save(pending: &[Pending]) {
+    let file = File::create(file_path()).expect("Cannot create file");
+    serde_json::to_writer(file, pending).expect("Cannot serialize notifications");
+}
+
+impl Notifier {
+    pub fn new(guild_configs: Arc<RwLock<HashMap<GuildId, GuildConfig>>>) -> Self {
+        let path = file_path();
+        let pending: Vec<Pending> = if path.exists() {
+            let file = File::open(path).expect("Cannot open file");
+            serde_json::from_reader(file).expect("Cannot parse notifications")
+        } else {
+            vec![]
+        };
+        info!("{} deferred notifications loaded", pending.len());
+        Notifier {
+            guild_configs,
+            pending: Arc::new(Mutex::new(pending)),
+        }
+    }
+
+    /// A notifier that starts empty and never touches the data dir, for tests
+    #[cfg(any(test, feature = "test-harness"))]
+    pub fn in_memory(guild_configs: Arc<RwLock<HashMap<GuildId, GuildConfig>>>) -> Self {
+        Notifier {
+            guild_configs,
+            pending: Default::default(),
+        }
+    }
+
+    /// When the quiet hours `guild` is in right now end, if it is in them
+    async fn quiet_until(&self, guild: Option<GuildId>) -> Option<DateTime<Utc>> {
+        let configs = self.guild_configs.read().await;
+        let config = configs.get(&guild?)?;
+        let quiet_hours = config.quiet_hours?;
+        let tz = config.timezone.unwrap_or(chrono_tz::UTC);
+        quiet_hours.end_after(Utc::now().with_timezone(&tz))
+    }
+
+    /// The URL `guild` wants scheduler events sent to
+    pub async fn webhook(&self, guild: Option<GuildId>) -> Option<String> {
+        self.guild_configs
+            .read()
+            .await
+            .get(&guild?)?
+            .webhook
+            .clone()
+    }
+
+    /// Send everything whose quiet hours have ended
+    pub async fn flush(&self, ctx: &Context) {
+        let now = Utc::now();
+        let mut pending = self.pending.lock().await;
+        let (due, waiting): (Vec<Pending>, Vec<Pending>) =
+            pending.drain(..).partition(|p| p.due <= now);
+        *pending = waiting;
+        if due.is_empty() {
+            return;
+        }
+        save(&pending);
+        drop(pending);
+        for p in due {
+            deliver(ctx, &p.notification).await;
+        }
+    }
+}
+
+/// Send `notification` now, or queue it if `guild` is in its quiet hours. Returns whether it was
+/// sent now.
+pub async fn send(ctx: &Context, guild: Option<GuildId>, notification: Notification) -> bool {
+    let notifier = ctx
+        .data
+        .read()
+        .await
+        .get::<Notifier>()
+        .cloned()
+        .expect("Cannot find notifier");
+    match notifier.quiet_until(guild).await {
+        Some(due) => {
+            let mut pending = notifier.pending.lock().await;
+            pending.push(Pending { due, notification });
+            save(&pending);
+            false
+        }
+        None => {
+            deliver(ctx, &notification).await;
+            true
+        }
+    }
+}
+
+async fn deliver(ctx: &Context, notification: &Notification) {
+    #[cfg(any(test, feature = "test-harness"))]
+    if let Some(recorder) = Recorder::get(ctx).await {
+        recorder.record(Call::Notify(notification.clone()));
+        return;
+    }
+    let channel = match notification.recipient {
+        Recipient::Channel(channel) => channel,
+        Recipient::User(user) => match user.create_dm_channel(ctx).await {
+            Ok(dm) => dm.id,
+            Err(e) => {
+                error!("Cannot create DM channel: {}", e);
+                return;
+            }
+        },
+    };
+    let mut message = CreateMessage::new()
+        .content(&notification.content)
+        .allowed_mentions(
+            CreateAllowedMentions::new()
+                .users(notification.mentions.iter().copied())
+                .roles(notification.roles.iter().copied()),
+        );
+    if let Some(attachment) = &notification.attachment {
+        message = message.add_file(CreateAttachment::bytes(
+            attachment.data.as_bytes(),
+            attachment.filename.clone(),
+        ));
+    }
+    if !notification.buttons.is_empty() {
+        let buttons = notification
+            .buttons
+            .iter()
+            .map(|button| {
+                CreateButton::new(&button.custom_id)
+                    .style(button.style)
+                    .label(&button.label)
+                    .disabled(button.disabled)
+            })
+            .collect();
+        message = message.components(vec![CreateActionRow::Buttons(buttons)]);
+    }
+    channel
+        .send_message(ctx, message)
+        .await
+        .map_err(|e| error!("Cannot send notification: {}", e))
+        .ok();
+}
@@ -0,0 +1,101 @@
+//! Schedulers prepared with `/schedule create post_at:`, held until they are due to be posted
+
+use crate::scheduler::Options;
+use crate::DATA_DIR;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::info;
+
+const POSTS_FILE: &str = "scheduled_posts.json";
+
+#[derive(Serialize, Deserialize)]
+pub struct ScheduledPost {
+    /// The id of the interaction that prepared it, which its preview's Cancel button carries
+    pub id: u64,
+    pub due: DateTime<Utc>,
+    pub owner: UserId,
+    pub guild: Option<GuildId>,
+    pub channel: ChannelId,
+    pub options: Options,
+}
+
+#[derive(Clone, Default)]
+pub struct Posts {
+    pending: Arc<Mutex<Vec<ScheduledPost>>>,
+}
+
+fn file_path() -> PathBuf {
+    let mut path: PathBuf = DATA_DIR.into();
+    path.push(POSTS_FILE);
+    path
+}
+
+fn save(pending: &[ScheduledPost]) {
+    let file = File::create(file_path()).expect("Cannot create file");
+    serde_json::to_writer(file, pending).expect("Cannot serialize scheduled posts");
+}
+
+impl Posts {
+    pub fn load() -> Self {
+        let path = file_path();
+        let pending: Vec<ScheduledPost> = if path.exists() {
+            let file = File::open(path).expect("Cannot open file");
+            serde_json::from_reader(file).expect("Cannot parse scheduled posts")
+        } else {
+            vec![]
+        };
+        info!("{} scheduled posts loaded", pending.len());
+        Posts {
+            pending: Arc::new(Mutex::new(pending)),
+        }
+    }
+
+    pub async fn add(&self, post: ScheduledPost) {
+        let mut pending = self.pending.lock().await;
+        pending.push(post);
+        save(&pending);
+    }
+
+    /// Drop the post `owner` prepared with `id`, returning whether there was one
+    pub async fn cancel(&self, id: u64, owner: UserId) -> bool {
+        let mut pending = self.pending.lock().await;
+        let before = pending.len();
+        pending.retain(|p| p.id != id || p.owner != owner);
+        let cancelled = pending.len() < before;
+        if cancelled {
+            save(&pending);
+        }
+        cancelled
+    }
+
+    /// Drop every post prepared in `guild`, returning how many there were
+    pub async fn purge_guild(&self, guild: GuildId) -> usize {
+        let mut pending = self.pending.lock().await;
+        let before = pending.len();
+        pending.retain(|p| p.guild != Some(guild));
+        let purged = before - pending.len();
+        if purged > 0 {
+            save(&pending);
+        }
+        purged
+    }
+
+    /// Remove and return every post that is due
+    pub async fn take_due(&self) -> Vec<ScheduledPost> {
+        let now = Utc::now();
+        let mut pending = self.pending.lock().await;
+        let (due, waiting): (Vec<ScheduledPost>, Vec<ScheduledPost>) =
+            pending.drain(..).partition(|p| p.due <= now);
+        *pending = waiting;
+        if !due.is_empty() {
+            save(&pending);
+        }
+        due
+    }
+}
@@ -0,0 +1,388 @@
+use crate::locale::DateStyle;
+use crate::responder::Responder;
+use crate::slot::Slot;
+use crate::timezones;
+
+use chrono::{Datelike, Duration, NaiveTime};
+use chrono_tz::Tz;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize, Serializer};
+use serenity::model::application::ButtonStyle;
+use serenity::model::id::UserId;
+use std::collections::{BTreeMap, HashMap};
+
+/// How the detailed results are arranged
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DetailsLayout {
+    #[default]
+    Chronological,
+    /// Group dates under headers by how many respondents are available
+    Tiers,
+}
+
+impl DetailsLayout {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "chronological" => Some(DetailsLayout::Chronological),
+            "tiers" => Some(DetailsLayout::Tiers),
+            _ => None,
+        }
+    }
+}
+
+/// A field of the scheduler message
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Section {
+    Responded,
+    Results,
+    Stats,
+    Notes,
+    /// The last few responses and blackouts; only shown when the layout asks for it
+    Activity,
+}
+
+impl Section {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "responded" => Some(Section::Responded),
+            "results" => Some(Section::Results),
+            "stats" => Some(Section::Stats),
+            "notes" => Some(Section::Notes),
+            "activity" => Some(Section::Activity),
+            _ => None,
+        }
+    }
+}
+
+pub fn default_layout() -> Vec<Section> {
+    vec![
+        Section::Responded,
+        Section::Results,
+        Section::Stats,
+        Section::Notes,
+    ]
+}
+
+/// Parse a comma separated list of sections, in the order they should appear
+pub fn parse_layout(s: &str) -> Option<Vec<Section>> {
+    let mut layout = vec![];
+    for section in s.split(',').filter(|s| !s.trim().is_empty()) {
+        let section = Section::parse(section)?;
+        if !layout.contains(&section) {
+            layout.push(section);
+        }
+    }
+    Some(layout)
+}
+
+/// A scheduler message as it is sent, kept as plain data so it can be compared in tests
+#[derive(Clone, Debug, Serialize)]
+pub struct Rendered {
+    /// The mentions above the embed
+    pub content: String,
+    pub title: String,
+    pub description: String,
+    pub footer: Option<String>,
+    pub fields: Vec<Field>,
+    pub buttons: Vec<Button>,
+}
+
+impl Rendered {
+    /// The characters Discord counts against `EMBED_TOTAL_LENGTH`
+    pub fn embed_length(&self) -> usize {
+        self.title.len()
+            + self.description.len()
+            + self.footer.as_ref().map_or(0, String::len)
+            + self.fields.iter().map(Field::length).sum::<usize>()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Field {
+    pub name: &'static str,
+    pub value: String,
+    pub inline: bool,
+}
+
+impl Field {
+    pub fn new(name: &'static str, value: String, inline: bool) -> Self {
+        Field {
+            name,
+            value,
+            inline,
+        }
+    }
+
+    /// The characters Discord counts for the field
+    pub fn length(&self) -> usize {
+        self.name.len() + self.value.len()
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Button {
+    #[serde(serialize_with = "style_name")]
+    pub style: ButtonStyle,
+    pub label: &'static str,
+    pub custom_id: &'static str,
+}
+
+impl Button {
+    pub fn new(style: ButtonStyle, label: &'static str, custom_id: &'static str) -> Self {
+        Button {
+            style,
+            label,
+            custom_id,
+        }
+    }
+}
+
+fn style_name<S: Serializer>(style: &ButtonStyle, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(match style {
+        ButtonStyle::Primary => "primary",
+        ButtonStyle::Secondary => "secondary",
+        ButtonStyle::Success => "success",
+        ButtonStyle::Danger => "danger",
+        _ => "link",
+    })
+}
+
+/// The users available in a single slot
+pub struct DateResult {
+    pub slot: Slot,
+    /// When a timed slot starts, shown in each reader's own timezone
+    pub timestamp: Option<i64>,
+    pub users: Vec<Responder>,
+    /// Users available only if needed
+    pub maybe: Vec<Responder>,
+    /// Points from respondents' rankings, shown instead of the count in ranked schedulers
+    pub score: Option<usize>,
+    /// A required participant can't make it, so the date is grayed out and never the best
+    pub excluded: bool,
+    /// Whether enough can make it, when the owner set a minimum attendance
+    pub meets_threshold: Option<bool>,
+}
+
+impl DateResult {
+    fn total(&self) -> usize {
+        self.users.len() + self.maybe.len()
+    }
+
+    /// Ranks dates by score, then by how many can make them, then by firm yeses
+    fn rank(&self) -> (usize, usize, usize) {
+        (self.score.unwrap_or(0), self.total(), self.users.len())
+    }
+}
+
+/// A count of firm yeses with any if-needed ones, e.g. "5 (+2 maybe)"
+pub fn count(firm: usize, maybe: usize) -> String {
+    if maybe == 0 {
+        firm.to_string()
+    } else {
+        format!("{} (+{} maybe)", firm, maybe)
+    }
+}
+
+fn max_rank(results: &[DateResult]) -> (usize, usize, usize) {
+    results
+        .iter()
+        .filter(|r| !r.excluded)
+        .map(DateResult::rank)
+        .max()
+        .unwrap_or((0, 0, 0))
+}
+
+fn mentions(users: &[Responder]) -> String {
+    users.iter().sorted().join(", ")
+}
+
+fn result_line(
+    result: &DateResult,
+    max: (usize, usize, usize),
+    detailed: bool,
+    style: &DateStyle,
+) -> String {
+    let count = match result.score {
+        Some(score) => format!("{} pts", score),
+        None => count(result.users.len(), result.maybe.len()),
+    };
+    let (date, shown) = match style.timestamp(result.slot) {
+        Some(timestamp) => (format!("{}:", timestamp), true),
+        None => (format!("`{}:`", style.long(result.slot)), false),
+    };
+    let mut line = if result.excluded {
+        format!("~~{} {}~~", date, count)
+    } else if max.1 > 0 && result.rank() == max {
+        format!("__{}__ {}", date, count)
+    } else {
+        format!("{} {}", date, count)
+    };
+    if let (Some(timestamp), false) = (result.timestamp, shown) {
+        line = format!("{} · <t:{}:f>", line, timestamp);
+    }
+    match result.meets_threshold {
+        Some(true) => line = format!("✅ {}", line),
+        // Subtext, which Discord shows small and gray
+        Some(false) => line = format!("-# {}", line),
+        None => (),
+    }
+    if detailed && !result.users.is_empty() {
+        line = format!("{} - {}", line, mentions(&result.users));
+    }
+    if detailed && !result.maybe.is_empty() {
+        line = format!("{} - maybe {}", line, mentions(&result.maybe));
+    }
+    line
+}
+
+/// One line per date, in chronological order
+pub fn results(results: &[DateResult], detailed: bool, style: &DateStyle) -> Vec<String> {
+    let max = max_rank(results);
+    results
+        .iter()
+        .map(|r| result_line(r, max, detailed, style))
+        .collect()
+}
+
+/// `lines` for each of `results` grouped under a header for the week, starting on Monday
+pub fn weeks(results: &[DateResult], lines: Vec<String>, style: &DateStyle) -> Vec<Vec<String>> {
+    let mut weeks = vec![];
+    for (monday, group) in &results.iter().zip(lines).group_by(|(r, _)| {
+        r.slot.date - Duration::days(r.slot.date.weekday().num_days_from_monday() as i64)
+    }) {
+        let header = format!("**Week of {}**", style.short(Slot::day(monday)));
+        weeks.push(
+            std::iter::once(header)
+                .chain(group.map(|(_, line)| line))
+                .collect(),
+        );
+    }
+    weeks
+}
+
+/// Detailed results laid out according to `layout`
+pub fn details(
+    results: &[DateResult],
+    respondents: usize,
+    layout: DetailsLayout,
+    style: &DateStyle,
+) -> Vec<String> {
+    match layout {
+        DetailsLayout::Chronological => self::results(results, true, style),
+        DetailsLayout::Tiers => tiers(results, respondents, style),
+    }
+}
+
+fn tiers(results: &[DateResult], respondents: usize, style: &DateStyle) -> Vec<String> {
+    let max = max_rank(results);
+    let tier = |count: usize| {
+        if count == 0 {
+            "Nobody available"
+        } else if count == respondents {
+            "All available"
+        } else if count + 1 == respondents {
+            "All but one available"
+        } else if count * 2 > respondents {
+            "Majority available"
+        } else {
+            "Others"
+        }
+    };
+    let mut lines = vec![];
+    for (header, group) in &results
+        .iter()
+        .sorted_by_key(|r| std::cmp::Reverse(r.rank()))
+        .group_by(|r| tier(r.total()))
+    {
+        lines.push(format!("**{}**", header));
+        lines.extend(
+            group
+                .sorted_by_key(|r| r.slot)
+                .map(|r| result_line(r, max, true, style)),
+        );
+    }
+    lines
+}
+
+/// Comma separated mentions of `users`, split into chunks of at most `limit` bytes
+pub fn mention_chunks(users: &[Responder], limit: usize) -> Vec<String> {
+    let mut chunks = vec![];
+    let mut chunk = String::new();
+    for user in users {
+        let mention = user.to_string();
+        if !chunk.is_empty() && chunk.len() + 2 + mention.len() > limit {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        if !chunk.is_empty() {
+            chunk += ", ";
+        }
+        chunk += &mention;
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// The local hours outside of which a timed slot is flagged as too early or too late
+const WAKING_HOURS: (u32, u32) = (8, 23);
+
+/// Respondents grouped by timezone region, then the timed `slots` that fall outside waking hours
+/// for some of them, or nothing if no respondent's timezone is known
+pub fn timezone_groups(
+    users: &[UserId],
+    tz: &HashMap<UserId, Tz>,
+    slots: &[Slot],
+    style: &DateStyle,
+) -> Vec<String> {
+    if !users.iter().any(|user| tz.contains_key(user)) {
+        return vec![];
+    }
+    let mut regions: BTreeMap<&str, Vec<UserId>> = BTreeMap::new();
+    for user in users {
+        let region = tz.get(user).map_or("Unknown", timezones::region);
+        regions.entry(region).or_default().push(*user);
+    }
+    let mut lines: Vec<String> = std::iter::once("**Respondents by timezone**".to_owned())
+        .chain(regions.into_iter().map(|(region, users)| {
+            format!(
+                "`{}:` {}",
+                region,
+                users
+                    .iter()
+                    .sorted()
+                    .map(|uid| format!("<@{}>", uid))
+                    .join(", ")
+            )
+        }))
+        .collect();
+    let (start, end) = WAKING_HOURS;
+    let waking =
+        NaiveTime::from_hms_opt(start, 0, 0).unwrap()..=NaiveTime::from_hms_opt(end, 0, 0).unwrap();
+    let odd_hours: Vec<String> = slots
+        .iter()
+        .filter(|slot| slot.time.is_some())
+        .filter_map(|slot| {
+            let outside = users
+                .iter()
+                .sorted()
+                .filter_map(|user| {
+                    let local = slot.in_timezone(style.timezone, *tz.get(user)?).time?;
+                    (!waking.contains(&local))
+                        .then(|| format!("<@{}> at {}", user, local.format("%H:%M")))
+                })
+                .join(", ");
+            (!outside.is_empty()).then(|| format!("{}: {}", style.short(*slot), outside))
+        })
+        .collect();
+    if !odd_hours.is_empty() {
+        lines.push(format!(
+            "**Outside {:02}:00–{:02}:00 for some**",
+            start, end
+        ));
+        lines.extend(odd_hours);
+    }
+    lines
+}
@@ -0,0 +1,63 @@
+//! Whoever a response belongs to: a Discord user, or a guest the owners answer for by name
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serenity::model::id::UserId;
+use std::fmt;
+
+const GUEST_PREFIX: &str = "guest:";
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Responder {
+    Discord(UserId),
+    /// Someone without Discord, like "Dave's friend"
+    Guest(String),
+}
+
+impl Responder {
+    /// The Discord user, for anything that pings or DMs
+    pub fn user(&self) -> Option<UserId> {
+        match self {
+            Responder::Discord(user) => Some(*user),
+            Responder::Guest(_) => None,
+        }
+    }
+}
+
+impl From<UserId> for Responder {
+    fn from(user: UserId) -> Self {
+        Responder::Discord(user)
+    }
+}
+
+/// A mention for users, the name in italics for guests
+impl fmt::Display for Responder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Responder::Discord(user) => write!(f, "<@{}>", user),
+            Responder::Guest(name) => write!(f, "*{}*", name),
+        }
+    }
+}
+
+// Users serialize as their bare id, so responses saved before guests still load
+impl Serialize for Responder {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Responder::Discord(user) => serializer.collect_str(&user.get()),
+            Responder::Guest(name) => serializer.collect_str(&format!("{}{}", GUEST_PREFIX, name)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Responder {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if let Some(name) = s.strip_prefix(GUEST_PREFIX) {
+            return Ok(Responder::Guest(name.to_owned()));
+        }
+        s.parse()
+            .map(|id| Responder::Discord(UserId::new(id)))
+            .map_err(|_| D::Error::custom(format!("invalid responder: {}", s)))
+    }
+}
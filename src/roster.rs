@@ -0,0 +1,102 @@
+//! Lists of expected respondents pasted or uploaded by a scheduler's owner
+
+use crate::limits;
+
+use itertools::Itertools;
+use serenity::client::Context;
+use serenity::model::guild::Member;
+use serenity::model::id::{GuildId, UserId};
+use std::collections::HashSet;
+use tracing::error;
+
+// Rosters are plain text, so anything larger is not a roster
+pub const MAX_FILE_SIZE: u32 = 64 * 1024;
+
+/// The members of `guild`, or `None` if they cannot be fetched
+pub async fn members(ctx: &Context, guild: GuildId) -> Option<Vec<Member>> {
+    let mut members = vec![];
+    let mut after = None;
+    loop {
+        let batch = guild
+            .members(ctx, Some(1000), after)
+            .await
+            .map_err(|e| error!("Cannot fetch members: {}", e))
+            .ok()?;
+        after = batch.last().map(|m| m.user.id);
+        let done = batch.len() < 1000;
+        members.extend(batch);
+        if done {
+            break;
+        }
+    }
+    Some(members)
+}
+
+pub struct Roster {
+    pub users: HashSet<UserId>,
+    /// Entries that matched no member
+    pub unknown: Vec<String>,
+}
+
+impl Roster {
+    pub fn summary(&self) -> String {
+        let mut text = format!("Roster set: {} players", self.users.len());
+        if !self.unknown.is_empty() {
+            text += &format!("\nNot found: {}", self.unknown.join(", "));
+        }
+        limits::truncate(&text, limits::MESSAGE_LENGTH)
+    }
+}
+
+fn parse_id(entry: &str) -> Option<UserId> {
+    let id = entry
+        .trim_start_matches("<@")
+        .trim_start_matches('!')
+        .trim_end_matches('>');
+    id.parse().ok().map(UserId::new)
+}
+
+fn matches(member: &Member, name: &str) -> bool {
+    let name = name.trim_start_matches('@');
+    member.user.name.eq_ignore_ascii_case(name)
+        || member.user.tag().eq_ignore_ascii_case(name)
+        || member
+            .nick
+            .as_ref()
+            .is_some_and(|nick| nick.eq_ignore_ascii_case(name))
+}
+
+/// Resolve a list of mentions, user IDs or names separated by commas or new lines
+pub async fn parse(ctx: &Context, guild: Option<GuildId>, text: &str) -> Roster {
+    let mut users = HashSet::new();
+    let mut names = vec![];
+    for entry in text
+        .split(['\n', ',', ';'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        match parse_id(entry) {
+            Some(id) => {
+                users.insert(id);
+            }
+            None => names.push(entry),
+        }
+    }
+    let mut unknown = vec![];
+    if !names.is_empty() {
+        // Members are only fetched when needed, since large guilds take a while
+        let members = match guild {
+            Some(guild) => members(ctx, guild).await.unwrap_or_default(),
+            None => vec![],
+        };
+        for name in names.into_iter().unique() {
+            match members.iter().find(|m| matches(m, name)) {
+                Some(member) => {
+                    users.insert(member.user.id);
+                }
+                None => unknown.push(name.to_owned()),
+            }
+        }
+    }
+    Roster { users, unknown }
+}
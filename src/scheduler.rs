@@ -1,19 +1,79 @@
+use crate::announce;
+use crate::debounce::{self, Debouncer, Edit};
+use crate::error::{Error, Result};
+use crate::events::{self, Event, Venue};
+use crate::gcal::{self, CalendarEvent};
+#[cfg(any(test, feature = "test-harness"))]
+use crate::harness::Recorder;
+use crate::heatmap::{self, Cell};
+use crate::ical;
+use crate::limits;
+use crate::locale::{DateFormat, DateStyle, Locale, Phrase, Text};
 use crate::message_shim::MessageShim;
-use crate::MAX_WEEKS;
+use crate::notify::{self, Notification, Recipient};
+use crate::render::{self, Button, DateResult, DetailsLayout, Field, Rendered, Section};
+use crate::responder::Responder;
+use crate::roster;
+use crate::shutdown;
+use crate::slot::Slot;
+use crate::unavailable::Calendar;
+use crate::webhook::{self, Payload};
+use crate::DEFAULT_WEEKS;
 
-use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
 use chronoutil::DateRule;
 use itertools::Itertools;
-use log::{error, info};
 use serde::{Deserialize, Serialize};
-use serenity::builder::{CreateActionRow, CreateButton, CreateComponents};
+use serenity::builder::{
+    CreateActionRow, CreateAttachment, CreateButton, CreateEmbed, CreateInputText,
+    CreateInteractionResponse, CreateInteractionResponseFollowup, CreateInteractionResponseMessage,
+    CreateMessage, CreateModal, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption,
+    CreateThread, EditInteractionResponse, EditMessage,
+};
 use serenity::client::Context;
-use serenity::model::channel::Message;
-use serenity::model::id::{RoleId, UserId};
-use serenity::model::interactions::message_component::{ButtonStyle, MessageComponentInteraction};
-use serenity::model::interactions::InteractionResponseType;
+use serenity::model::application::{ButtonStyle, InputTextStyle};
+use serenity::model::application::{ComponentInteraction, ComponentInteractionDataKind};
+use serenity::model::channel::{Message, ReactionType};
+use serenity::model::id::{ChannelId, GuildId, MessageId, RoleId, ScheduledEventId, UserId};
+use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::Mutex;
 use std::time::Instant;
+use tracing::{error, info};
+
+const REMINDER_MENTIONS_PER_LINE: usize = 40;
+/// Slots a scheduler can offer; the response flow pages through them and the results stay
+/// within the embed's length
+pub const MAX_SLOTS: usize = 60;
+/// Slots that fit on the response buttons; the last row is reserved for the control buttons
+const BUTTON_SLOTS: usize = (limits::ACTION_ROWS - 1) * limits::BUTTONS_PER_ROW;
+/// Slots per page of the response flow when they don't all fit, leaving a row for paging
+const PAGE_SLOTS: usize = (limits::ACTION_ROWS - 2) * limits::BUTTONS_PER_ROW;
+/// Dates a scheduler can post messages for reactions on, to keep the channel readable
+pub const MAX_REACTION_DATES: usize = 14;
+/// How many dates each respondent ranks in ranked schedulers
+pub const RANKED_CHOICES: usize = 3;
+/// The reaction on a date message that counts as being available
+pub const REACTION: &str = "👍";
+/// Entries kept for the Recent activity field
+const ACTIVITY_ENTRIES: usize = 3;
+/// Dates exported to calendars before one is finalized
+const EXPORT_DATES: usize = 3;
+pub const MATRIX_FILENAME: &str = "availability.csv";
+/// Longer names would run into the heatmap's cells
+const HEATMAP_NAME_LENGTH: usize = 24;
+/// Flags dates in the picker that the user's personal calendar rules out
+const UNAVAILABLE_MARK: &str = "🚫";
+/// Best dates of each scheduler that `/schedule intersect` compares
+pub const INTERSECT_DATES: usize = 5;
+/// Respondents mentioned on the message before the rest are collapsed, unless configured
+const DEFAULT_MENTION_LIMIT: usize = 40;
+// Room left in the first and last Responded fields for the count and the collapsed remainder
+const RESPONDED_RESERVED_LENGTH: usize = 32;
+/// How long the count of those expected to respond is reused for percentage embargoes
+const EXPECTED_COUNT_TTL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum ResponseType {
@@ -23,474 +83,6244 @@ pub enum ResponseType {
 
 #[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Response {
-    dates: HashSet<NaiveDate>,
+    dates: HashSet<Slot>,
+    /// Dates the user can make if needed, but would rather not
+    #[serde(default)]
+    maybe: HashSet<Slot>,
+    /// Dates among `dates` the user isn't sure of yet and will be asked to firm up
+    #[serde(default)]
+    tentative: HashSet<Slot>,
+    /// When the user last changed their response
+    #[serde(default)]
+    responded_at: Option<DateTime<Utc>>,
+    /// What the user added about their answers, shown with the details
+    #[serde(default)]
+    note: Option<String>,
+    /// The owner who last answered for the user, if it wasn't the user themselves
+    #[serde(default)]
+    proxied_by: Option<UserId>,
+    /// Favorite dates first, in ranked schedulers
+    #[serde(default)]
+    ranking: Vec<Slot>,
 }
 
-impl From<HashSet<NaiveDate>> for Response {
-    fn from(dates: HashSet<NaiveDate>) -> Self {
-        Response { dates }
+impl Response {
+    fn available(&self, slot: &Slot) -> bool {
+        self.dates.contains(slot) || self.maybe.contains(slot)
     }
-}
 
-#[derive(Serialize, Deserialize)]
-pub struct Scheduler {
-    owner: UserId,
-    title: String,
-    dates: Vec<NaiveDate>,
-    #[serde(default)]
-    blackout_dates: HashSet<NaiveDate>,
-    group: Option<RoleId>,
-    message: MessageShim,
-    responses: HashMap<UserId, Response>,
-    closed: bool,
-}
+    fn state(&self, slot: &Slot) -> (bool, bool, bool) {
+        (
+            self.dates.contains(slot),
+            self.maybe.contains(slot),
+            self.tentative.contains(slot),
+        )
+    }
 
-impl Scheduler {
-    pub fn new(
-        owner: UserId,
-        group: Option<RoleId>,
-        message: Message,
-        weeks: i64,
-        skip: Option<i64>,
-        title: &str,
-        days: HashSet<Weekday>,
-    ) -> Self {
-        let today = Local::today().naive_local();
-        let mut start_date = today.succ();
-        while start_date.weekday() != Weekday::Sat {
-            start_date = start_date.succ();
-        }
-        if let Some(skip) = skip {
-            start_date += Duration::weeks(skip);
-        }
-        let end_date = start_date + Duration::weeks(weeks);
-        let window = DateRule::daily(start_date).with_end(end_date);
-        let dates = window.filter(|day| days.contains(&day.weekday())).collect();
-        Self {
-            owner,
-            title: title.to_string(),
-            dates,
-            blackout_dates: Default::default(),
-            group,
-            message: message.into(),
-            responses: Default::default(),
-            closed: false,
+    fn set_state(&mut self, slot: Slot, (yes, maybe, tentative): (bool, bool, bool)) {
+        for (set, on) in [
+            (&mut self.dates, yes),
+            (&mut self.maybe, maybe),
+            (&mut self.tentative, tentative),
+        ] {
+            if on {
+                set.insert(slot);
+            } else {
+                set.remove(&slot);
+            }
         }
     }
 
-    pub fn get_dates(&self) -> Vec<NaiveDate> {
-        self.dates.clone()
+    /// The dates no longer and newly available compared to `previous`, in order
+    fn diff(&self, previous: &Response) -> (Vec<Slot>, Vec<Slot>) {
+        let available = |response: &Response| -> HashSet<Slot> {
+            response.dates.union(&response.maybe).copied().collect()
+        };
+        let (before, after) = (available(previous), available(self));
+        (
+            before.difference(&after).copied().sorted().collect(),
+            after.difference(&before).copied().sorted().collect(),
+        )
     }
 
-    pub fn get_blackout_dates(&self) -> HashSet<NaiveDate> {
-        self.blackout_dates.clone()
+    /// Apply the changes from `base` to `edited` onto this response, returning how many of the
+    /// changed slots had been changed here too since `base`
+    fn merge(&mut self, base: &Response, edited: &Response) -> usize {
+        let slots: HashSet<Slot> = [base, edited]
+            .iter()
+            .flat_map(|r| r.dates.iter().chain(&r.maybe))
+            .copied()
+            .collect();
+        let mut conflicts = 0;
+        for slot in slots {
+            let (before, wanted) = (base.state(&slot), edited.state(&slot));
+            if wanted == before {
+                continue;
+            }
+            let current = self.state(&slot);
+            if current != before && current != wanted {
+                conflicts += 1;
+            }
+            self.set_state(slot, wanted);
+        }
+        if edited.ranking != base.ranking {
+            self.ranking = edited.ranking.clone();
+        }
+        conflicts
     }
+}
 
-    pub fn get_user_response(&self, user: &UserId) -> Option<Response> {
-        self.responses.get(user).cloned()
-    }
+/// Something that happened on a scheduler, for the Recent activity field
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+enum Activity {
+    Responded(UserId),
+    /// A user changed which dates they can make
+    Changed {
+        user: UserId,
+        removed: Vec<Slot>,
+        added: Vec<Slot>,
+    },
+    Withdrew(UserId),
+    BlackedOut(Vec<Slot>),
+}
 
-    pub async fn can_respond(
-        &self,
-        ctx: &Context,
-        component: &MessageComponentInteraction,
-    ) -> bool {
-        match self.group {
-            Some(role) => {
-                let user = &component.user;
-                let guild = component.guild_id.expect("Cannot get guild");
-                let allowed = user
-                    .has_role(&ctx, guild, role)
-                    .await
-                    .expect("Cannot check role");
-                if !allowed {
-                    component
-                        .create_interaction_response(&ctx, |r| {
-                            r.kind(InteractionResponseType::ChannelMessageWithSource)
-                                .interaction_response_data(|m| {
-                                    m.content(format!("Only <@&{}> may respond", role))
-                                        .ephemeral(true)
-                                })
-                        })
-                        .await
-                        .expect("Cannot send response");
-                }
-                allowed
+/// Add `slots` to those `done`, unless they undo one of those `undone`
+fn fold_changes(slots: &[Slot], undone: &mut Vec<Slot>, done: &mut Vec<Slot>) {
+    for slot in slots {
+        match undone.iter().position(|s| s == slot) {
+            Some(i) => {
+                undone.remove(i);
             }
-            None => true,
+            None => done.push(*slot),
         }
     }
+    done.sort();
+}
 
-    pub async fn add_response(&mut self, ctx: &Context, user: UserId, response: Response) {
-        self.responses.insert(user, response);
-        self.update_message(ctx).await;
+impl From<HashSet<Slot>> for Response {
+    fn from(dates: HashSet<Slot>) -> Self {
+        Response {
+            dates,
+            ..Default::default()
+        }
     }
+}
 
-    pub async fn set_blackout(&mut self, ctx: &Context, response: Response) {
-        self.blackout_dates = response.dates;
-        self.update_message(ctx).await;
-    }
+/// What the response flow offers a user
+pub struct Prompt {
+    pub dates: Vec<Slot>,
+    pub labels: Vec<String>,
+    pub note: Option<String>,
+    /// Dates that can't be picked
+    pub blackout_dates: HashSet<Slot>,
+    pub quick: Vec<QuickAction>,
+    /// Whether picks can be marked tentative
+    pub tentative: bool,
+    /// Whether the user ranks their favorite dates instead of picking every date they can make
+    pub ranked: bool,
+    /// The language of the user's Discord client
+    pub locale: Locale,
+    /// Dates the user's personal calendar rules out; they start out unpicked and are flagged
+    pub unavailable: HashSet<Slot>,
+}
 
-    fn get_responses(&self) -> String {
-        if self.responses.is_empty() {
-            "**0**".to_owned()
-        } else {
-            format!(
-                "**{}** ({})",
-                self.responses.len(),
-                self.responses
-                    .iter()
-                    .map(|(id, _response)| format!("<@{}>", id))
-                    .collect::<Vec<String>>()
-                    .join(", ")
-            )
+/// A shortcut in the response flow that answers many slots at once
+pub enum QuickAction {
+    /// Mark all of the slots available, or clear them if they already all are
+    Toggle(String, Vec<Slot>),
+    /// Give every later week the answers of the first
+    CopyFirstWeek,
+}
+
+impl QuickAction {
+    fn label(&self) -> &str {
+        match self {
+            QuickAction::Toggle(label, _) => label,
+            QuickAction::CopyFirstWeek => "Copy week 1 to all",
         }
     }
 
-    fn get_results(&self, detailed: bool) -> impl Iterator<Item = String> + '_ {
-        let results = self.dates.iter().filter_map(|date| {
-            if self.blackout_dates.contains(date) {
-                None
-            } else {
-                let mut users = HashSet::new();
-                for (user_id, response) in self.responses.iter() {
-                    if response.dates.contains(date) {
-                        users.insert(user_id);
+    /// Apply the shortcut to `response`, leaving `blocked` slots alone
+    fn apply(&self, response: &mut Response, dates: &[Slot], blocked: &HashSet<Slot>) {
+        match self {
+            QuickAction::Toggle(_, slots) => {
+                let open: Vec<&Slot> = slots.iter().filter(|s| !blocked.contains(s)).collect();
+                if open.iter().all(|s| response.dates.contains(s)) {
+                    open.into_iter().for_each(|s| {
+                        response.dates.remove(s);
+                        response.tentative.remove(s);
+                    });
+                } else {
+                    for slot in open {
+                        response.maybe.remove(slot);
+                        response.dates.insert(*slot);
                     }
                 }
-                Some((date, users))
             }
-        });
-        let max = results
-            .clone()
-            .map(|(_, users)| users.len())
-            .max()
-            .unwrap_or(0);
-        results.map(move |(date, users)| {
-            let count = users.len();
-            let date = date.format("%a %Y-%m-%d");
-            let mut line = if max > 0 && count == max {
-                format!("__`{}:`__ {}", date, count)
-            } else {
-                format!("`{}:` {}", date, count)
-            };
-            if detailed && !users.is_empty() {
-                line = format!(
-                    "{} - {}",
-                    line,
-                    users
-                        .iter()
-                        .sorted()
-                        .map(|uid| format!("<@{}>", uid))
-                        .join(", ")
-                );
+            QuickAction::CopyFirstWeek => {
+                let first = match dates.first() {
+                    Some(first) => first.date,
+                    None => return,
+                };
+                for slot in dates.iter().filter(|s| !blocked.contains(s)) {
+                    let week = (slot.date - first).num_weeks();
+                    if week == 0 {
+                        continue;
+                    }
+                    let source = Slot {
+                        date: slot.date - Duration::weeks(week),
+                        time: slot.time,
+                    };
+                    if !dates.contains(&source) {
+                        continue;
+                    }
+                    response.dates.remove(slot);
+                    response.maybe.remove(slot);
+                    response.tentative.remove(slot);
+                    if response.dates.contains(&source) {
+                        response.dates.insert(*slot);
+                        if response.tentative.contains(&source) {
+                            response.tentative.insert(*slot);
+                        }
+                    } else if response.maybe.contains(&source) {
+                        response.maybe.insert(*slot);
+                    }
+                }
             }
-            line
-        })
+        }
     }
+}
 
-    pub async fn update_message(&self, ctx: &Context) {
-        let title = &self.title;
-        let responses = self.get_responses();
-        let results = self.get_results(false).join("\n");
-        let closed = self.closed;
-        let content = match &self.group {
-            Some(role) => format!("<@&{}>", role),
-            None => "".to_owned(),
-        };
-        self.message
-            .edit(ctx, |m| {
-                let mut ar = CreateActionRow::default();
-                let mut text = "";
-                if !closed {
-                    ar.create_button(|b| b.label("Add response").custom_id("response"));
-                    ar.create_button(|b| {
-                        b.style(ButtonStyle::Secondary)
-                            .label("Show details")
-                            .custom_id("details")
-                    });
-                    //ar.create_button(|b|
-                    //    b
-                    //        .style(ButtonStyle::Danger)
-                    //        .label("Close")
-                    //        .custom_id("close")
-                    //);
-                } else {
-                    ar.create_button(|b| {
-                        b.style(ButtonStyle::Secondary)
-                            .label("Show details")
-                            .custom_id("details")
-                    });
-                    text = "Final results";
-                }
-                m.content(content)
-                    .embed(|e| {
-                        e.title(title)
-                            .description(text)
-                            .field("Responded", responses, false)
-                            .field("Results", &results, true)
-                    })
-                    .components(|c| c.add_action_row(ar))
-                    .allowed_mentions(|am| am.roles(self.group))
-                    .suppress_embeds(false)
-            })
-            .await
-            .map_err(|e| error!("Cannot edit message: {}", e))
-            .ok();
-    }
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PollKind {
+    #[default]
+    Dates,
+    /// A poll without dates, asking only who is interested
+    Interest,
+    /// An RSVP for a single date that was already chosen
+    Confirmation,
+}
 
-    pub async fn show_details(&self, ctx: &Context, component: &MessageComponentInteraction) {
-        component.defer(ctx).await.unwrap();
-        let results = self.get_results(true);
-        let mut messages: Vec<String> = vec![];
-        let mut content = String::new();
-        for line in results {
-            assert!(line.len() < 2000);
-            if content.len() + line.len() >= 2000 {
-                messages.push(content);
-                content = String::new()
-            }
-            content += &line;
-            content.push('\n');
+/// What happens to answers on dates that get blacked out
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlackoutPolicy {
+    /// Keep them but leave them out of results and exports, so they return if the blackout is
+    /// lifted
+    #[default]
+    Hide,
+    /// Drop them from every response
+    Prune,
+}
+
+impl BlackoutPolicy {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "hide" => Some(BlackoutPolicy::Hide),
+            "prune" => Some(BlackoutPolicy::Prune),
+            _ => None,
         }
-        let last_content = content;
-        for content in messages {
-            component
-                .create_followup_message(ctx, |m| m.ephemeral(true).content(content))
-                .await
-                .expect("Cannot send message");
+    }
+}
+
+/// How many responses are needed before results are shown, to avoid anchoring early voters
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Embargo {
+    Count(usize),
+    /// A percentage of the players expected to respond
+    Percent(usize),
+}
+
+impl Embargo {
+    /// Parse a count like "4" or a percentage like "50%"
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        match s.strip_suffix('%') {
+            Some(percent) => match percent.trim().parse() {
+                Ok(percent) if percent <= 100 => Some(Embargo::Percent(percent)),
+                _ => None,
+            },
+            None => s.parse().ok().map(Embargo::Count),
         }
-        component
-            .create_followup_message(ctx, |m| {
-                if component.user.id == self.owner {
-                    let mut ar = CreateActionRow::default();
-                    ar.create_button(|b| b.label("Add blackout dates").custom_id("blackout"));
-                    m.components(|c| c.add_action_row(ar));
-                }
-                m.ephemeral(true).content(last_content)
-            })
-            .await
-            .expect("Cannot send message");
     }
+}
 
-    pub async fn close_prompt(&self, ctx: &Context, component: &MessageComponentInteraction) {
-        if component.user.id != self.owner {
-            component
-                .create_interaction_response(ctx, |response| {
-                    response
-                        .kind(InteractionResponseType::ChannelMessageWithSource)
-                        .interaction_response_data(|m| {
-                            m.ephemeral(true).content("Only owner can close")
-                        })
-                })
-                .await
-                .expect("Cannot send message");
-            return;
+/// What another scheduler contributes to this one's blackouts
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstraintKind {
+    /// The other scheduler's blackout dates
+    Blackouts,
+    /// Dates nobody who responded to the other scheduler can make
+    Unavailable,
+}
+
+impl ConstraintKind {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "blackouts" => Some(ConstraintKind::Blackouts),
+            "unavailable" => Some(ConstraintKind::Unavailable),
+            _ => None,
         }
+    }
+}
 
-        component
-            .create_interaction_response(ctx, |r| {
-                r.kind(InteractionResponseType::ChannelMessageWithSource)
-                    .interaction_response_data(|m| {
-                        m.ephemeral(true).content("Finalize?").components(|c| {
-                            c.create_action_row(|ar| {
-                                ar.create_button(|b| b.label("Yes").custom_id("close_yes"))
-                            })
-                        })
-                    })
-            })
-            .await
-            .expect("Cannot send message");
+/// How the owner orders dates tied for the most available
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TieBreak {
+    Earliest,
+    /// Fewest required players yet to say they can make it
+    Required,
+    /// Fewest who can only make it if needed
+    Maybe,
+}
+
+impl TieBreak {
+    const ALL: [TieBreak; 3] = [TieBreak::Earliest, TieBreak::Required, TieBreak::Maybe];
+
+    pub fn parse(action: &str) -> Option<Self> {
+        match action {
+            "rank_earliest" => Some(TieBreak::Earliest),
+            "rank_required" => Some(TieBreak::Required),
+            "rank_maybe" => Some(TieBreak::Maybe),
+            _ => None,
+        }
     }
 
-    pub async fn handle_close(&mut self, ctx: &Context, component: &MessageComponentInteraction) {
-        component
-            .defer(ctx)
-            .await
-            .expect("Cannot respond to button");
-        component
-            .edit_original_interaction_response(ctx, |m| m.content("Closed!").components(|c| c))
-            .await
-            .expect("Cannot edit message");
-        self.close(ctx).await;
+    fn action(self) -> &'static str {
+        match self {
+            TieBreak::Earliest => "rank_earliest",
+            TieBreak::Required => "rank_required",
+            TieBreak::Maybe => "rank_maybe",
+        }
     }
 
-    pub async fn close(&mut self, ctx: &Context) {
-        self.closed = true;
-        self.update_message(ctx).await;
+    fn label(self) -> Text {
+        match self {
+            TieBreak::Earliest => Text::Earliest,
+            TieBreak::Required => Text::RequiredPlayers,
+            TieBreak::Maybe => Text::FewestMaybes,
+        }
     }
-}
 
-fn create_dm_buttons<'a>(
-    dates: &Vec<NaiveDate>,
-    blackout_dates: &HashSet<NaiveDate>,
-    response: &Response,
-    components: &'a mut CreateComponents,
-    resp_type: ResponseType,
-) -> &'a mut CreateComponents {
-    let count = dates.len();
-    if count > 2 * MAX_WEEKS {
-        panic!("Too many dates!");
-    }
-    let per_row = std::cmp::max(2, (count as f32 / 4f32).ceil() as usize);
-
-    let mut ar = CreateActionRow::default();
-    for (i, date) in dates.iter().enumerate() {
-        if i > 0 && i % per_row == 0 {
-            components.add_action_row(ar);
-            ar = CreateActionRow::default();
-        }
-        let mut button = CreateButton::default();
-        button.label(date.format("%a %b %d"));
-        button.custom_id(format!("select {}", i));
-        match resp_type {
-            ResponseType::Normal => {
-                if blackout_dates.contains(date) {
-                    button.style(ButtonStyle::Danger);
-                    button.disabled(true);
-                } else {
-                    button.style(if response.dates.contains(date) {
-                        ButtonStyle::Success
-                    } else {
-                        ButtonStyle::Secondary
-                    });
-                }
-            }
-            ResponseType::Blackout => {
-                button.style(if response.dates.contains(date) {
-                    ButtonStyle::Danger
-                } else {
-                    ButtonStyle::Secondary
-                });
-            }
+    fn heading(self) -> Text {
+        match self {
+            TieBreak::Earliest => Text::RankedByEarliest,
+            TieBreak::Required => Text::RankedByRequired,
+            TieBreak::Maybe => Text::RankedByMaybe,
         }
-        ar.add_button(button);
     }
-    components.add_action_row(ar);
+}
 
-    ar = CreateActionRow::default();
+/// A link to another scheduler whose dates are blacked out here, kept up to date as it changes
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Constraint {
+    pub source: MessageId,
+    pub kind: ConstraintKind,
+}
 
-    if resp_type == ResponseType::Blackout {
-        let mut button = CreateButton::default();
-        button.label("Select all");
-        button.custom_id("select_all");
-        button.style(ButtonStyle::Success);
-        ar.add_button(button);
+/// What `/schedule edit` changes; anything left out stays as it is
+#[derive(Default)]
+pub struct Changes {
+    pub title: Option<String>,
+    pub group: Option<RoleId>,
+    pub dates: Option<Vec<Slot>>,
+    pub recurring: Option<bool>,
+    /// Zero takes the threshold off
+    pub threshold: Option<usize>,
+    pub show_blackouts: Option<bool>,
+    pub ranked: Option<bool>,
+    /// Zero takes the limit off
+    pub capacity: Option<usize>,
+}
 
-        let mut button = CreateButton::default();
-        button.label("Clear all");
-        button.custom_id("clear_all");
-        button.style(ButtonStyle::Secondary);
-        ar.add_button(button);
+impl Changes {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.group.is_none()
+            && self.dates.is_none()
+            && self.recurring.is_none()
+            && self.threshold.is_none()
+            && self.show_blackouts.is_none()
+            && self.ranked.is_none()
+            && self.capacity.is_none()
     }
+}
 
-    let mut button = CreateButton::default();
-    button.label("Submit");
-    button.custom_id("submit");
-    ar.add_button(button);
+/// Settings chosen when a scheduler is created
+#[derive(Serialize, Deserialize)]
+pub struct Options {
+    pub title: String,
+    /// Markdown shown under the title
+    pub body: Option<String>,
+    pub group: Option<RoleId>,
+    pub weeks: i64,
+    pub skip: Option<i64>,
+    pub days: HashSet<Weekday>,
+    /// Times of day to offer on each day; whole days when empty
+    pub times: Vec<NaiveTime>,
+    /// Dates to offer instead of `weeks` of `days`, for events that aren't weekly
+    #[serde(default)]
+    pub dates: Vec<NaiveDate>,
+    pub details_layout: DetailsLayout,
+    #[serde(default)]
+    pub blackout_policy: BlackoutPolicy,
+    pub embargo: Option<Embargo>,
+    pub locale: Locale,
+    pub date_format: Option<DateFormat>,
+    pub layout: Vec<Section>,
+    pub notes: Option<String>,
+    pub mention_limit: Option<usize>,
+    pub quorum: Option<usize>,
+    /// Players a date needs to count as workable
+    #[serde(default)]
+    pub threshold: Option<usize>,
+    /// When to close if the quorum hasn't closed it first
+    #[serde(default)]
+    pub deadline: Option<DateTime<Utc>>,
+    /// Days after posting to remind everyone who hasn't responded
+    #[serde(default)]
+    pub remind_after: Option<i64>,
+    /// Days before the deadline or date to ask respondents to firm up tentative picks
+    #[serde(default)]
+    pub firm_up: Option<i64>,
+    /// The voice or stage channel sessions are held in
+    #[serde(default)]
+    pub venue: Option<Venue>,
+    /// Also post a message per date whose 👍 reactions count as responses
+    #[serde(default)]
+    pub reactions: bool,
+    /// Template for the announcement when a date is finalized
+    #[serde(default)]
+    pub announcement: Option<String>,
+    /// The timezone slot times are in
+    pub timezone: Option<Tz>,
+    /// Leave the message without buttons, for channels where not everyone can use them
+    pub read_only: bool,
+    /// Only show counts; who responded is left to the owner's details
+    #[serde(default)]
+    pub anonymous: bool,
+    /// Open a thread off the message that activity is posted to
+    #[serde(default)]
+    pub thread: bool,
+    /// Post a fresh scheduler for the following weeks once the last date has passed
+    #[serde(default)]
+    pub recurring: bool,
+    /// Respondents rank their favorite dates rather than picking every date they can make
+    #[serde(default)]
+    pub ranked: bool,
+}
 
-    components.add_action_row(ar)
+pub fn default_days() -> HashSet<Weekday> {
+    HashSet::from([Weekday::Sat, Weekday::Sun])
 }
 
-// Ephemeral messages can only be edited for a limited time after they are initally created;
-// testing indicates that this limit is 15 minutes
-const RESP_TIMEOUT: std::time::Duration = std::time::Duration::new(60 * 14, 0);
+/// Firm and maybe counts of a date
+type Counts = (usize, usize);
 
-pub async fn get_response(
-    ctx: &Context,
-    component: &MessageComponentInteraction,
-    mut response: Response,
-    dates: Vec<NaiveDate>,
-    blackout_dates: HashSet<NaiveDate>,
-    resp_type: ResponseType,
-) -> Option<Response> {
-    component
-        .create_interaction_response(ctx, |r| {
-            r.kind(InteractionResponseType::ChannelMessageWithSource)
-                .interaction_response_data(|m| {
-                    m.ephemeral(true).components(|c| {
-                        create_dm_buttons(&dates, &blackout_dates, &response, c, resp_type)
-                    })
-                })
+/// The dates in the top results of two schedulers with the firm and maybe counts of each, in
+/// order; a whole day matches the times on it, which are the more specific
+pub fn intersect(
+    a: &[(Slot, usize, usize)],
+    b: &[(Slot, usize, usize)],
+) -> Vec<(Slot, Counts, Counts)> {
+    a.iter()
+        .cartesian_product(b)
+        .filter(|((x, _, _), (y, _, _))| x.covers(y) || y.covers(x))
+        .map(|((x, xf, xm), (y, yf, ym))| {
+            let slot = if x.time.is_some() { *x } else { *y };
+            (slot, (*xf, *xm), (*yf, *ym))
         })
-        .await
-        .expect("Cannot send DM");
+        .sorted_by_key(|(slot, _, _)| *slot)
+        .collect()
+}
 
-    let expiration = Instant::now() + RESP_TIMEOUT;
+/// The slots a scheduler created on `today` with `options` offers: its dates if it lists them,
+/// otherwise its weekdays starting the next Saturday
+pub fn slots(options: &Options, today: NaiveDate) -> Vec<Slot> {
+    if !options.dates.is_empty() {
+        return options
+            .dates
+            .iter()
+            .flat_map(|date| Slot::on(*date, &options.times))
+            .collect();
+    }
+    let mut start_date = today.succ_opt().unwrap();
+    while start_date.weekday() != Weekday::Sat {
+        start_date = start_date.succ_opt().unwrap();
+    }
+    if let Some(skip) = options.skip {
+        start_date += Duration::weeks(skip);
+    }
+    let end_date = start_date + Duration::weeks(options.weeks);
+    let window = DateRule::daily(start_date).with_end(end_date);
+    window
+        .filter(|day| options.days.contains(&day.weekday()))
+        .flat_map(|day| Slot::on(day, &options.times))
+        .collect()
+}
 
-    let message = component
-        .get_interaction_response(ctx)
-        .await
-        .expect("Cannot get response message");
-    loop {
-        let interaction = message
-            .await_component_interaction(ctx)
-            .timeout(expiration - Instant::now())
-            .await;
-        let interaction = match interaction {
-            Some(i) => i,
-            None => {
-                info!("Response timed out");
-                component
-                    .edit_original_interaction_response(ctx, |m| {
-                        m.content("Response timed out").components(|c| c)
-                    })
-                    .await
-                    .expect("Cannot update message");
-                return None;
-            }
-        };
-        interaction
-            .defer(ctx)
-            .await
-            .expect("Cannot respond to button");
-        let button_id = interaction.data.custom_id.as_str();
-        match button_id {
-            "submit" => {
-                if matches!(
-                    component
-                        .edit_original_interaction_response(ctx, |m| {
-                            m.content("Response submitted").components(|c| c)
-                        })
-                        .await,
-                    Err(_)
-                ) {
-                    error!("Cannot update message");
-                }
-                return Some(response);
-            }
-            "select_all" => {
-                response.dates = dates
-                    .iter()
-                    .filter(|d| !blackout_dates.contains(d))
-                    .cloned()
-                    .collect()
-            }
-            "clear_all" => response.dates.clear(),
-            _ => {
-                let (button_id, data) = button_id.split_once(' ').unwrap();
-                match button_id {
-                    "select" => {
-                        let index: usize = data.parse().expect("Cannot parse index");
-                        let date = &dates[index];
-                        let resp_dates = &mut response.dates;
-                        if resp_dates.contains(date) {
-                            resp_dates.remove(date);
-                        } else {
-                            resp_dates.insert(*date);
-                        }
-                    }
-                    _ => panic!("Unexpected button: {button_id}"),
-                }
-            }
-        }
-        component
-            .edit_original_interaction_response(ctx, |m| {
-                m.components(|c| {
-                    create_dm_buttons(&dates, &blackout_dates, &response, c, resp_type)
-                })
-            })
-            .await
-            .expect("Cannot update message");
+#[derive(Serialize, Deserialize)]
+pub struct Scheduler {
+    #[serde(default)]
+    kind: PollKind,
+    /// Who created the scheduler; digests and alerts go to them
+    owner: UserId,
+    /// Everyone who can manage the scheduler, besides `owner`
+    #[serde(default)]
+    owners: HashSet<UserId>,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    /// Whole days, or times of day when the scheduler was created with times
+    dates: Vec<Slot>,
+    #[serde(default)]
+    blackout_dates: HashSet<Slot>,
+    /// Why the owner blacked out a date, shown with it
+    #[serde(default)]
+    blackout_reasons: HashMap<Slot, String>,
+    group: Option<RoleId>,
+    #[serde(default)]
+    guild: Option<GuildId>,
+    message: MessageShim,
+    responses: HashMap<Responder, Response>,
+    closed: bool,
+    #[serde(default)]
+    finalized: Option<Slot>,
+    #[serde(default)]
+    details_layout: DetailsLayout,
+    #[serde(default)]
+    blackout_policy: BlackoutPolicy,
+    /// Users invited to respond; anyone in the group may respond when empty
+    #[serde(default)]
+    respondents: HashSet<UserId>,
+    /// The other half of an interest poll and the scheduler created from it
+    #[serde(default)]
+    linked: Option<MessageId>,
+    /// Players expected to respond who aren't captured by the group
+    #[serde(default)]
+    roster: HashSet<UserId>,
+    #[serde(default)]
+    embargo: Option<Embargo>,
+    /// Language of weekday and month names
+    #[serde(default)]
+    locale: Locale,
+    /// The guild's own way of writing dates, if it set one
+    #[serde(default)]
+    date_format: Option<DateFormat>,
+    /// Which fields the message shows, in order
+    #[serde(default = "render::default_layout")]
+    layout: Vec<Section>,
+    #[serde(default)]
+    notes: Option<String>,
+    /// How many respondents to mention on the message
+    #[serde(default)]
+    mention_limit: Option<usize>,
+    /// Players needed for a date to go ahead; a majority of respondents when unset
+    #[serde(default)]
+    quorum: Option<usize>,
+    /// When to close automatically, if a date hasn't reached the quorum before then
+    #[serde(default)]
+    deadline: Option<DateTime<Utc>>,
+    /// Days after posting to remind everyone who hasn't responded
+    #[serde(default)]
+    remind_after: Option<i64>,
+    /// Whether the automatic reminder has gone out
+    #[serde(default)]
+    reminded: bool,
+    /// Days before the deadline or date to ask respondents to firm up tentative picks; picks
+    /// can only be tentative when set
+    #[serde(default)]
+    firm_up: Option<i64>,
+    /// Respondents asked to firm up since they last responded
+    #[serde(default)]
+    firm_up_asked: HashSet<UserId>,
+    #[serde(default)]
+    constraint: Option<Constraint>,
+    /// Blackouts taken from the constraint's source, kept apart from the owner's own
+    #[serde(default)]
+    imported_blackouts: HashSet<Slot>,
+    /// The timezone slot times are in; UTC when unset
+    #[serde(default)]
+    timezone: Option<Tz>,
+    /// Responses come in through /respond rather than buttons on the message
+    #[serde(default)]
+    read_only: bool,
+    /// The guild's scheduled event for the finalized date
+    #[serde(default)]
+    event: Option<ScheduledEventId>,
+    /// The Google Calendar copy of the finalized date, when a calendar is configured
+    #[serde(default)]
+    calendar_event: Option<CalendarEvent>,
+    /// Saved but not yet rendered over its placeholder message; retried at startup
+    #[serde(default)]
+    pending: bool,
+    /// Keep mentioning the group or invitees on every edit, not only when first posted
+    #[serde(default)]
+    edit_pings: bool,
+    /// List respondents on the message newest first rather than in a fixed order
+    #[serde(default)]
+    by_recency: bool,
+    /// Owners who get a DM whenever someone responds or changes their response
+    #[serde(default)]
+    notify_owners: HashSet<UserId>,
+    /// The voice or stage channel sessions are held in, which the event is linked to
+    #[serde(default)]
+    venue: Option<Venue>,
+    /// Messages posted per date whose 👍 reactions count as responses
+    #[serde(default)]
+    date_messages: HashMap<MessageId, Slot>,
+    /// Created from a list of dates, so there's no weekly pattern to resize or extend
+    #[serde(default)]
+    explicit_dates: bool,
+    /// Template for the announcement when a date is finalized; the default when unset
+    #[serde(default)]
+    announcement: Option<String>,
+    /// Availability counts the owner wants a DM about once a date reaches them; removed once sent
+    #[serde(default)]
+    alerts: Vec<usize>,
+    /// Copies of the message posted in other channels or guilds, answered and kept in sync alike
+    #[serde(default)]
+    mirrors: Vec<MessageShim>,
+    /// The most recent events, oldest first
+    #[serde(default)]
+    activity: Vec<Activity>,
+    /// Only counts are shown; names are left to the owner's details
+    #[serde(default)]
+    anonymous: bool,
+    /// Where activity is posted as it happens
+    #[serde(default)]
+    thread: Option<ChannelId>,
+    /// Post a fresh scheduler for the following weeks once the last date has passed
+    #[serde(default)]
+    recurring: bool,
+    /// The scheduler this one was rolled over from
+    #[serde(default)]
+    previous: Option<MessageId>,
+    /// The scheduler this one rolled over to
+    #[serde(default)]
+    next: Option<MessageId>,
+    /// Dates before this have passed and are no longer offered; their answers are kept
+    #[serde(default)]
+    expired_before: Option<NaiveDate>,
+    /// Players a date can't go ahead without, once they have responded
+    #[serde(default)]
+    required: HashSet<UserId>,
+    /// Players a date needs to be marked as workable; final results list only those dates
+    #[serde(default)]
+    threshold: Option<usize>,
+    /// Whether the blackout dates are left off the message
+    #[serde(default)]
+    hide_blackouts: bool,
+    /// Whether respondents rank their favorite dates, which are scored by Borda count
+    #[serde(default)]
+    ranked: bool,
+    /// How many can attend the final date; once it is full, more can join its waitlist
+    #[serde(default)]
+    capacity: Option<usize>,
+    /// Who is waiting for a spot on each full date, first come first served
+    #[serde(default)]
+    waitlists: HashMap<Slot, Vec<UserId>>,
+    /// How many are expected to respond and when they were counted, so an embargo on a share of
+    /// them doesn't look the group up on every render
+    #[serde(skip)]
+    expected_count: Mutex<Option<(Instant, usize)>>,
+}
+
+impl Scheduler {
+    pub fn new(
+        owner: UserId,
+        guild: Option<GuildId>,
+        message: impl Into<MessageShim>,
+        options: Options,
+    ) -> Self {
+        let dates = slots(&options, Local::now().date_naive());
+        Self {
+            kind: PollKind::Dates,
+            owner,
+            owners: HashSet::new(),
+            title: options.title,
+            body: options.body,
+            dates,
+            blackout_dates: Default::default(),
+            blackout_reasons: Default::default(),
+            group: options.group,
+            guild,
+            message: message.into(),
+            responses: Default::default(),
+            closed: false,
+            finalized: None,
+            details_layout: options.details_layout,
+            blackout_policy: options.blackout_policy,
+            respondents: Default::default(),
+            linked: None,
+            roster: Default::default(),
+            embargo: options.embargo,
+            locale: options.locale,
+            date_format: options.date_format,
+            layout: options.layout,
+            notes: options.notes,
+            mention_limit: options.mention_limit,
+            quorum: options.quorum,
+            deadline: options.deadline,
+            remind_after: options.remind_after,
+            reminded: false,
+            firm_up: options.firm_up,
+            firm_up_asked: Default::default(),
+            venue: options.venue,
+            constraint: None,
+            imported_blackouts: Default::default(),
+            timezone: options.timezone,
+            read_only: options.read_only,
+            event: None,
+            calendar_event: None,
+            pending: false,
+            edit_pings: false,
+            notify_owners: Default::default(),
+            date_messages: Default::default(),
+            explicit_dates: !options.dates.is_empty(),
+            announcement: options.announcement,
+            alerts: vec![],
+            mirrors: vec![],
+            activity: vec![],
+            thread: None,
+            by_recency: false,
+            anonymous: options.anonymous,
+            recurring: options.recurring,
+            previous: None,
+            next: None,
+            expired_before: None,
+            required: Default::default(),
+            threshold: options.threshold,
+            hide_blackouts: false,
+            ranked: options.ranked,
+            capacity: None,
+            waitlists: Default::default(),
+            expected_count: Default::default(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_interest(
+        owner: UserId,
+        guild: Option<GuildId>,
+        message: Message,
+        title: String,
+        body: Option<String>,
+        group: Option<RoleId>,
+        locale: Locale,
+        date_format: Option<DateFormat>,
+    ) -> Self {
+        Self {
+            kind: PollKind::Interest,
+            owner,
+            owners: HashSet::new(),
+            title,
+            body,
+            dates: vec![],
+            blackout_dates: Default::default(),
+            blackout_reasons: Default::default(),
+            group,
+            guild,
+            message: message.into(),
+            responses: Default::default(),
+            closed: false,
+            finalized: None,
+            details_layout: Default::default(),
+            blackout_policy: Default::default(),
+            respondents: Default::default(),
+            linked: None,
+            roster: Default::default(),
+            embargo: None,
+            locale,
+            date_format,
+            layout: render::default_layout(),
+            notes: None,
+            mention_limit: None,
+            quorum: None,
+            deadline: None,
+            remind_after: None,
+            reminded: false,
+            firm_up: None,
+            firm_up_asked: Default::default(),
+            venue: None,
+            constraint: None,
+            imported_blackouts: Default::default(),
+            timezone: None,
+            read_only: false,
+            event: None,
+            calendar_event: None,
+            pending: false,
+            edit_pings: false,
+            notify_owners: Default::default(),
+            date_messages: Default::default(),
+            explicit_dates: false,
+            announcement: None,
+            alerts: vec![],
+            mirrors: vec![],
+            activity: vec![],
+            thread: None,
+            by_recency: false,
+            anonymous: false,
+            recurring: false,
+            previous: None,
+            next: None,
+            expired_before: None,
+            required: Default::default(),
+            threshold: None,
+            hide_blackouts: false,
+            ranked: false,
+            capacity: None,
+            waitlists: Default::default(),
+            expected_count: Default::default(),
+        }
+    }
+
+    /// A scheduler for a single, already chosen date that only asks who is coming
+    pub fn new_confirmation(
+        owner: UserId,
+        guild: Option<GuildId>,
+        message: impl Into<MessageShim>,
+        options: Options,
+        slot: Slot,
+    ) -> Self {
+        let mut scheduler = Scheduler::new(owner, guild, message, options);
+        scheduler.kind = PollKind::Confirmation;
+        scheduler.dates = vec![slot];
+        scheduler
+    }
+
+    /// Close an interest poll, creating a date scheduler for everyone interested
+    pub fn schedule_interest(&mut self, message: Message) -> Scheduler {
+        let options = Options {
+            title: self.title.clone(),
+            body: self.body.clone(),
+            group: self.group,
+            weeks: DEFAULT_WEEKS,
+            skip: None,
+            days: default_days(),
+            times: vec![],
+            dates: vec![],
+            details_layout: Default::default(),
+            blackout_policy: Default::default(),
+            embargo: None,
+            locale: self.locale,
+            date_format: self.date_format.clone(),
+            layout: render::default_layout(),
+            notes: None,
+            mention_limit: self.mention_limit,
+            quorum: self.quorum,
+            threshold: self.threshold,
+            deadline: None,
+            remind_after: None,
+            firm_up: None,
+            venue: self.venue,
+            reactions: false,
+            announcement: self.announcement.clone(),
+            timezone: self.timezone,
+            read_only: false,
+            anonymous: self.anonymous,
+            thread: false,
+            recurring: false,
+            ranked: false,
+        };
+        let mut scheduler = Scheduler::new(self.owner, self.guild, message, options);
+        scheduler.respondents = self.responses.keys().filter_map(Responder::user).collect();
+        scheduler.linked = Some(self.message.id());
+        scheduler.roster = self.roster.clone();
+        self.linked = Some(scheduler.message.id());
+        self.closed = true;
+        scheduler
+    }
+
+    /// Whether the last date of a recurring scheduler has passed without it rolling over yet
+    pub fn due_to_roll_over(&self, today: NaiveDate) -> bool {
+        self.recurring
+            && self.next.is_none()
+            && self.kind == PollKind::Dates
+            && !self.explicit_dates
+            && self.dates.last().is_some_and(|last| last.date < today)
+    }
+
+    /// A scheduler for as many weeks as this one covered, starting the next Saturday, with the
+    /// same title, group, days and blackout rules
+    pub fn roll_over(&mut self, message: impl Into<MessageShim>) -> Scheduler {
+        let pattern = self.pattern();
+        let weeks = match (self.dates.first(), self.dates.last()) {
+            (Some(first), Some(last)) => {
+                let mut start = first.date;
+                while start.weekday() != Weekday::Sat {
+                    start = start.pred_opt().unwrap();
+                }
+                (last.date - start).num_days() / 7 + 1
+            }
+            _ => DEFAULT_WEEKS,
+        };
+        let options = Options {
+            title: self.title.clone(),
+            body: self.body.clone(),
+            group: self.group,
+            weeks,
+            skip: None,
+            days: pattern.iter().map(|(weekday, _)| *weekday).collect(),
+            times: pattern
+                .iter()
+                .filter_map(|(_, time)| *time)
+                .unique()
+                .collect(),
+            dates: vec![],
+            details_layout: self.details_layout,
+            blackout_policy: self.blackout_policy,
+            embargo: None,
+            locale: self.locale,
+            date_format: self.date_format.clone(),
+            layout: self.layout.clone(),
+            notes: self.notes.clone(),
+            mention_limit: self.mention_limit,
+            quorum: self.quorum,
+            threshold: self.threshold,
+            deadline: None,
+            remind_after: self.remind_after,
+            firm_up: self.firm_up,
+            venue: self.venue,
+            reactions: false,
+            announcement: self.announcement.clone(),
+            timezone: self.timezone,
+            read_only: self.read_only,
+            anonymous: self.anonymous,
+            thread: false,
+            recurring: true,
+            ranked: self.ranked,
+        };
+        let mut scheduler = Scheduler::new(self.owner, self.guild, message, options);
+        scheduler.respondents = self.respondents.clone();
+        scheduler.roster = self.roster.clone();
+        scheduler.constraint = self.constraint;
+        scheduler.imported_blackouts = self.imported_blackouts.clone();
+        scheduler.owners = self.owners.clone();
+        scheduler.previous = Some(self.message.id());
+        self.next = Some(scheduler.message.id());
+        scheduler
+    }
+
+    #[cfg(test)]
+    pub fn owner(&self) -> UserId {
+        self.owner
+    }
+
+    /// Whether `user` created the scheduler or was made an owner of it
+    pub fn is_owner(&self, user: UserId) -> bool {
+        user == self.owner || self.owners.contains(&user)
+    }
+
+    /// Let `user` manage the scheduler too, returning a reply
+    pub fn add_owner(&mut self, user: UserId) -> String {
+        if self.is_owner(user) {
+            return format!("<@{}> already owns this scheduler", user);
+        }
+        self.owners.insert(user);
+        format!("<@{}> can now manage this scheduler", user)
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn channel_id(&self) -> ChannelId {
+        self.message.channel_id()
+    }
+
+    pub fn guild(&self) -> Option<GuildId> {
+        self.guild
+    }
+
+    pub fn group(&self) -> Option<RoleId> {
+        self.group
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    pub fn ranked(&self) -> bool {
+        self.ranked
+    }
+
+    pub fn finalized(&self) -> Option<Slot> {
+        self.finalized
+    }
+
+    pub fn response_count(&self) -> usize {
+        self.responses.len()
+    }
+
+    pub fn has_thread(&self) -> bool {
+        self.thread.is_some()
+    }
+
+    /// For each date, how many were available out of how many responded
+    pub fn turnout(&self) -> Vec<(Slot, usize, usize)> {
+        if self.responses.is_empty() {
+            return vec![];
+        }
+        self.tally()
+            .into_iter()
+            .map(|(slot, firm, maybe)| (slot, firm + maybe, self.responses.len()))
+            .collect()
+    }
+
+    pub fn get_dates(&self) -> Vec<Slot> {
+        self.dates.clone()
+    }
+
+    /// The dates that haven't passed, for the response flow
+    pub fn offered_dates(&self) -> Vec<Slot> {
+        self.dates
+            .iter()
+            .filter(|date| !self.is_expired(date))
+            .copied()
+            .collect()
+    }
+
+    fn is_expired(&self, slot: &Slot) -> bool {
+        self.expired_before.is_some_and(|before| slot.date < before)
+    }
+
+    /// Whether an open scheduler still offers dates from before `now`'s day
+    pub fn due_to_expire(&self, now: DateTime<Utc>) -> bool {
+        let today = now.with_timezone(&self.timezone()).date_naive();
+        !self.closed
+            && self
+                .dates
+                .iter()
+                .any(|date| date.date < today && !self.is_expired(date))
+    }
+
+    /// Stop offering dates from before `now`'s day
+    pub async fn expire_past(&mut self, ctx: &Context, now: DateTime<Utc>) {
+        self.expired_before = Some(now.with_timezone(&self.timezone()).date_naive());
+        self.update_message(ctx).await;
+    }
+
+    /// Shortcuts for the response flow: each weekday, each month when there are several, and
+    /// copying the first week onto the rest
+    pub fn quick_actions(&self) -> Vec<QuickAction> {
+        let locale = self.locale;
+        let weekdays = self
+            .dates
+            .iter()
+            .copied()
+            .into_group_map_by(|slot| slot.date.weekday().num_days_from_monday());
+        let months = self
+            .dates
+            .iter()
+            .copied()
+            .into_group_map_by(|slot| (slot.date.year(), slot.date.month()));
+        let mut actions = vec![];
+        if weekdays.len() > 1 {
+            actions.extend(
+                weekdays
+                    .into_iter()
+                    .sorted_by_key(|(day, _)| *day)
+                    .filter(|(_, slots)| slots.len() > 1)
+                    .map(|(_, slots)| {
+                        let label = format!("All {}", locale.weekday(slots[0].date));
+                        QuickAction::Toggle(label, slots)
+                    }),
+            );
+        }
+        if months.len() > 1 {
+            actions.extend(months.into_iter().sorted_by_key(|(month, _)| *month).map(
+                |(_, slots)| {
+                    let label = format!("All of {}", locale.month(slots[0].date));
+                    QuickAction::Toggle(label, slots)
+                },
+            ));
+        }
+        let weeks = match (self.dates.first(), self.dates.last()) {
+            (Some(first), Some(last)) => (last.date - first.date).num_weeks(),
+            _ => 0,
+        };
+        if weeks > 0 {
+            actions.push(QuickAction::CopyFirstWeek);
+        }
+        actions
+    }
+
+    pub fn get_blackout_dates(&self) -> HashSet<Slot> {
+        self.blackout_dates.clone()
+    }
+
+    fn is_blacked_out(&self, slot: &Slot) -> bool {
+        self.blackout_dates.contains(slot) || self.imported_blackouts.iter().any(|b| b.covers(slot))
+    }
+
+    /// The owner's blackouts together with those imported from the constraint's source
+    pub fn blocked_dates(&self) -> HashSet<Slot> {
+        self.dates
+            .iter()
+            .filter(|date| self.is_blacked_out(date))
+            .copied()
+            .collect()
+    }
+
+    pub fn constraint(&self) -> Option<Constraint> {
+        self.constraint
+    }
+
+    /// Follow the constraint's source when it is rebound from `from` onto `to`
+    pub fn retarget_constraint(&mut self, from: MessageId, to: MessageId) {
+        if let Some(constraint) = self.constraint.as_mut().filter(|c| c.source == from) {
+            constraint.source = to;
+        }
+    }
+
+    /// The dates this scheduler passes on to schedulers constrained by it
+    pub fn exported(&self, kind: ConstraintKind) -> HashSet<Slot> {
+        match kind {
+            ConstraintKind::Blackouts => self.blackout_dates.clone(),
+            ConstraintKind::Unavailable if self.responses.is_empty() => HashSet::new(),
+            ConstraintKind::Unavailable => self
+                .dates
+                .iter()
+                .filter(|date| !self.responses.values().any(|r| r.available(date)))
+                .copied()
+                .collect(),
+        }
+    }
+
+    /// Link this scheduler to `constraint`, or unlink it, blacking out `slots`
+    pub async fn set_constraint(
+        &mut self,
+        ctx: &Context,
+        constraint: Option<Constraint>,
+        slots: HashSet<Slot>,
+    ) {
+        self.constraint = constraint;
+        self.imported_blackouts = slots;
+        self.update_message(ctx).await;
+    }
+
+    /// Replace the blackouts imported from the constraint's source
+    pub async fn import_blackouts(&mut self, ctx: &Context, slots: HashSet<Slot>) {
+        if self.imported_blackouts != slots {
+            self.imported_blackouts = slots;
+            self.apply_blackout_policy();
+            self.update_message(ctx).await;
+        }
+    }
+
+    pub fn date_style(&self) -> DateStyle {
+        DateStyle {
+            locale: self.locale,
+            format: self.date_format.clone(),
+            timezone: self.timezone(),
+        }
+    }
+
+    fn timezone(&self) -> Tz {
+        self.timezone.unwrap_or(chrono_tz::UTC)
+    }
+
+    fn has_times(&self) -> bool {
+        self.dates.iter().any(|date| date.time.is_some())
+    }
+
+    /// Where sessions are held, if in a voice or stage channel
+    fn venue_line(&self) -> Option<String> {
+        self.venue
+            .map(|venue| format!("Where: <#{}>", venue.channel))
+    }
+
+    /// A slot as shown on the message, with a timestamp Discord shows in each reader's timezone
+    fn slot_label(&self, slot: Slot) -> String {
+        let style = self.date_style();
+        if let Some(timestamp) = style.timestamp(slot) {
+            return timestamp;
+        }
+        let label = style.long(slot);
+        match slot.timestamp(self.timezone()) {
+            Some(timestamp) => format!("{} (<t:{}:f>)", label, timestamp),
+            None => label,
+        }
+    }
+
+    /// Labels for the response buttons, with times converted to `tz` when it is known
+    pub fn slot_labels(&self, tz: Option<Tz>) -> Vec<String> {
+        let style = self.date_style();
+        self.dates
+            .iter()
+            .map(|date| {
+                let date = match tz {
+                    Some(tz) => date.in_timezone(self.timezone(), tz),
+                    None => *date,
+                };
+                style.short(date)
+            })
+            .collect()
+    }
+
+    /// Which timezone the response buttons show times in, if there are any times
+    pub fn timezone_note(&self, tz: Option<Tz>, locale: Locale) -> Option<String> {
+        if !self.has_times() {
+            return None;
+        }
+        Some(match tz {
+            Some(tz) => locale.fill(Text::TimesInYourTimezone, &[&tz.name()]),
+            None => locale.fill(Text::TimesInSetYours, &[&self.timezone().name()]),
+        })
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending
+    }
+
+    pub fn set_pending(&mut self, pending: bool) {
+        self.pending = pending;
+    }
+
+    /// Whether this is an open date poll, which /respond can answer
+    pub fn takes_responses(&self) -> bool {
+        self.kind == PollKind::Dates && !self.closed
+    }
+
+    pub fn get_user_response(&self, user: &UserId) -> Option<Response> {
+        self.response_of(&Responder::from(*user))
+    }
+
+    pub fn response_of(&self, responder: &Responder) -> Option<Response> {
+        self.responses.get(responder).cloned()
+    }
+
+    pub async fn can_respond(
+        &self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+    ) -> Result<bool> {
+        let user = &component.user;
+        let locale = Locale::parse(&component.locale).unwrap_or_default();
+        let denied = if !self.respondents.is_empty() {
+            (!self.respondents.contains(&user.id))
+                .then(|| locale.text(Text::InvitedOnly).to_owned())
+        } else if let Some(role) = self.group.filter(|_| component.guild_id == self.guild) {
+            // The group is a role in the scheduler's own guild; anyone may respond on copies
+            let guild = component.guild_id.ok_or(Error::Missing("guild"))?;
+            let allowed = user.has_role(&ctx, guild, role).await?;
+            (!allowed).then(|| locale.fill(Text::RoleOnly, &[&role]))
+        } else {
+            None
+        };
+        match denied {
+            Some(msg) => {
+                component
+                    .create_response(
+                        &ctx,
+                        CreateInteractionResponse::Message(
+                            CreateInteractionResponseMessage::new()
+                                .content(msg)
+                                .ephemeral(true),
+                        ),
+                    )
+                    .await?;
+                Ok(false)
+            }
+            None => Ok(true),
+        }
+    }
+
+    /// Whether the owner used `component`, telling anyone else that only the owner can `action`
+    pub async fn check_owner(
+        &self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+        denied: Text,
+    ) -> Result<bool> {
+        if self.is_owner(component.user.id) {
+            return Ok(true);
+        }
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(
+                            Locale::parse(&component.locale)
+                                .unwrap_or_default()
+                                .text(denied),
+                        ),
+                ),
+            )
+            .await?;
+        Ok(false)
+    }
+
+    /// Apply the changes a response session made from `base` to `user`'s current response,
+    /// returning how many dates had been changed elsewhere in the meantime
+    pub async fn merge_response(
+        &mut self,
+        ctx: &Context,
+        user: UserId,
+        base: &Response,
+        edited: &Response,
+    ) -> usize {
+        let mut response = self
+            .responses
+            .get(&Responder::from(user))
+            .cloned()
+            .unwrap_or_default();
+        let conflicts = response.merge(base, edited);
+        self.add_response(ctx, user, response).await;
+        conflicts
+    }
+
+    /// Like `merge_response`, for an owner answering on a player's or guest's behalf
+    pub async fn merge_proxied_response(
+        &mut self,
+        ctx: &Context,
+        responder: Responder,
+        by: UserId,
+        base: &Response,
+        edited: &Response,
+    ) -> usize {
+        let conflicts = match responder {
+            Responder::Discord(user) => self.merge_response(ctx, user, base, edited).await,
+            // Guests have nobody to ping or DM, so only their dates are kept
+            Responder::Guest(_) => {
+                let mut response = self.response_of(&responder).unwrap_or_default();
+                let conflicts = response.merge(base, edited);
+                response.responded_at = Some(Utc::now());
+                self.responses.insert(responder.clone(), response);
+                self.check_alerts(ctx).await;
+                self.update_message(ctx).await;
+                conflicts
+            }
+        };
+        if let Some(response) = self.responses.get_mut(&responder) {
+            response.proxied_by = Some(by);
+        }
+        conflicts
+    }
+
+    /// Like `merge_response`, for a session editing the blackout dates
+    pub async fn merge_blackout(
+        &mut self,
+        ctx: &Context,
+        base: &Response,
+        edited: &Response,
+    ) -> usize {
+        let mut blackouts: Response = self.blackout_dates.clone().into();
+        let conflicts = blackouts.merge(base, edited);
+        self.set_blackout(ctx, blackouts).await;
+        conflicts
+    }
+
+    /// Black out every date `user` can't make, so the poll only offers dates they can
+    pub async fn black_out_unavailable(&mut self, ctx: &Context, user: UserId) {
+        let response = self
+            .responses
+            .get(&Responder::from(user))
+            .cloned()
+            .unwrap_or_default();
+        self.blackout_dates.extend(
+            self.dates
+                .iter()
+                .filter(|date| !response.available(date))
+                .copied(),
+        );
+        self.apply_blackout_policy();
+        self.update_message(ctx).await;
+    }
+
+    /// Post a message per date for people to react to, returning the ids of those posted
+    pub async fn post_date_messages(&mut self, ctx: &Context) -> Vec<MessageId> {
+        let channel = self.channel_id();
+        let mut posted = vec![];
+        for slot in self.get_dates() {
+            let content = self.date_style().code(slot);
+            let sent = channel
+                .send_message(
+                    ctx,
+                    CreateMessage::new()
+                        .content(content)
+                        .reactions([ReactionType::Unicode(REACTION.to_owned())]),
+                )
+                .await;
+            match sent {
+                Ok(message) => {
+                    self.date_messages.insert(message.id, slot);
+                    posted.push(message.id);
+                }
+                Err(e) => error!("Cannot post date message for {}: {}", slot, e),
+            }
+        }
+        posted
+    }
+
+    /// Move the scheduler onto a replacement for its message
+    pub fn rebind(&mut self, message: MessageShim) {
+        self.message = message;
+    }
+
+    pub async fn add_mirror(&mut self, ctx: &Context, message: MessageShim) {
+        self.mirrors.push(message);
+        self.update_message(ctx).await;
+    }
+
+    pub fn remove_mirror(&mut self, id: MessageId) {
+        self.mirrors.retain(|mirror| mirror.id() != id);
+    }
+
+    pub fn mirror_ids(&self) -> Vec<MessageId> {
+        self.mirrors.iter().map(MessageShim::id).collect()
+    }
+
+    pub fn date_message_ids(&self) -> Vec<MessageId> {
+        self.date_messages.keys().copied().collect()
+    }
+
+    /// Count `user` reacting to a date message as being available on its date. Like the buttons,
+    /// only invitees or members of the group (going by `roles`) may respond.
+    pub async fn react(
+        &mut self,
+        ctx: &Context,
+        message: MessageId,
+        user: UserId,
+        roles: &[RoleId],
+    ) {
+        let slot = match self.date_messages.get(&message) {
+            Some(slot) if !self.closed => *slot,
+            _ => return,
+        };
+        let allowed = if !self.respondents.is_empty() {
+            self.respondents.contains(&user)
+        } else {
+            self.group.is_none_or(|role| roles.contains(&role))
+        };
+        if !allowed {
+            return;
+        }
+        let mut response = self
+            .responses
+            .get(&Responder::from(user))
+            .cloned()
+            .unwrap_or_default();
+        response.set_state(slot, (true, false, false));
+        self.add_response(ctx, user, response).await;
+    }
+
+    /// Take back a reaction to a date message
+    pub async fn unreact(&mut self, ctx: &Context, message: MessageId, user: UserId) {
+        let slot = match self.date_messages.get(&message) {
+            Some(slot) if !self.closed => *slot,
+            _ => return,
+        };
+        let mut response = match self.responses.get(&Responder::from(user)) {
+            Some(response) if response.dates.contains(&slot) => response.clone(),
+            _ => return,
+        };
+        response.set_state(slot, (false, false, false));
+        self.add_response(ctx, user, response).await;
+    }
+
+    pub async fn add_response(&mut self, ctx: &Context, user: UserId, mut response: Response) {
+        response.responded_at = Some(Utc::now());
+        response.proxied_by = None;
+        let previous = self.responses.get(&Responder::from(user)).cloned();
+        // The response flow no longer offers past dates, so keep what was answered for them
+        if let Some(previous) = &previous {
+            for date in self.dates.iter().filter(|date| self.is_expired(date)) {
+                response.set_state(*date, previous.state(date));
+            }
+        }
+        let payload = Payload::Response {
+            scheduler: self.message.id(),
+            title: self.title.clone(),
+            user: (!self.anonymous).then_some(user),
+            dates: response
+                .dates
+                .iter()
+                .sorted()
+                .map(Slot::to_string)
+                .collect(),
+            maybe: response
+                .maybe
+                .iter()
+                .sorted()
+                .map(Slot::to_string)
+                .collect(),
+        };
+        let activity = match &previous {
+            Some(previous) => match response.diff(previous) {
+                (removed, added) if removed.is_empty() && added.is_empty() => {
+                    Activity::Responded(user)
+                }
+                (removed, added) => Activity::Changed {
+                    user,
+                    removed,
+                    added,
+                },
+            },
+            None => Activity::Responded(user),
+        };
+        self.responses.insert(user.into(), response);
+        webhook::send(ctx, self.guild, payload).await;
+        self.notify_owners(ctx, user, previous.as_ref()).await;
+        self.record(ctx, activity).await;
+        self.firm_up_asked.remove(&user);
+        self.check_alerts(ctx).await;
+        self.update_message(ctx).await;
+    }
+
+    /// DM the owners who asked for it what `user` answered, or what they changed since
+    /// `previous`
+    async fn notify_owners(&self, ctx: &Context, user: UserId, previous: Option<&Response>) {
+        let response = match self.responses.get(&Responder::from(user)) {
+            Some(response) => response,
+            None => return,
+        };
+        let locale = self.locale;
+        let summary = match previous {
+            Some(previous) => match response.diff(previous) {
+                (removed, added) if removed.is_empty() && added.is_empty() => {
+                    locale.text(Text::SameDates).to_owned()
+                }
+                (removed, added) => self.diff_label(&removed, &added),
+            },
+            None => self
+                .dates
+                .iter()
+                .filter(|date| response.available(date))
+                .map(|date| {
+                    let label = self.date_style().short(*date);
+                    if response.maybe.contains(date) {
+                        locale.fill(Text::Maybe, &[&label])
+                    } else {
+                        label
+                    }
+                })
+                .join(", "),
+        };
+        let content = locale.fill(
+            if previous.is_some() {
+                Text::ChangedResponse
+            } else {
+                Text::RespondedTo
+            },
+            &[
+                &user,
+                &self.title,
+                &if summary.is_empty() {
+                    locale.text(Text::NoDatesPicked)
+                } else {
+                    summary.as_str()
+                },
+                &self.link(),
+            ],
+        );
+        for owner in self.notify_owners.iter().filter(|owner| **owner != user) {
+            let notification = Notification {
+                recipient: Recipient::User(*owner),
+                content: limits::truncate(&content, limits::MESSAGE_LENGTH),
+                mentions: vec![],
+                roles: vec![],
+                attachment: None,
+                buttons: vec![],
+            };
+            notify::send(ctx, self.guild, notification).await;
+        }
+    }
+
+    /// DM the owner once any date has `available` people who can make it, returning a reply
+    pub async fn add_alert(&mut self, ctx: &Context, available: usize) -> Phrase {
+        if self.kind != PollKind::Dates {
+            return Text::AlertsDatesOnly.into();
+        }
+        if !self.alerts.contains(&available) {
+            self.alerts.push(available);
+        }
+        self.check_alerts(ctx).await;
+        if self.alerts.contains(&available) {
+            Phrase::new(Text::AlertSet, &[&available])
+        } else {
+            Text::AlertAlreadyMet.into()
+        }
+    }
+
+    /// Send the alerts whose threshold the best date has reached, as a single DM
+    async fn check_alerts(&mut self, ctx: &Context) {
+        if self.closed || self.alerts.is_empty() {
+            return;
+        }
+        let best = self.best_dates();
+        let available = match best.first() {
+            Some(date) => {
+                let (firm, maybe) = self.count(date);
+                firm + maybe
+            }
+            None => return,
+        };
+        if !self.alerts.iter().any(|alert| *alert <= available) {
+            return;
+        }
+        self.alerts.retain(|alert| *alert > available);
+        let content = self.locale.fill(
+            Text::AlertReached,
+            &[
+                &self.title,
+                &available,
+                &best.iter().map(|d| self.slot_label(*d)).join(", "),
+                &self.link(),
+            ],
+        );
+        let notification = Notification {
+            recipient: Recipient::User(self.owner),
+            content: limits::truncate(&content, limits::MESSAGE_LENGTH),
+            mentions: vec![],
+            roles: vec![],
+            attachment: None,
+            buttons: vec![notify::Button {
+                label: self.locale.text(Text::Finalize).to_owned(),
+                custom_id: format!("alert_finalize {}", self.message.id()),
+                style: ButtonStyle::Primary,
+                disabled: false,
+            }],
+        };
+        notify::send(ctx, self.guild, notification).await;
+    }
+
+    pub async fn set_interest(&mut self, ctx: &Context, user: UserId, interested: bool) {
+        if interested {
+            self.responses.insert(user.into(), Response::default());
+        } else {
+            self.responses.remove(&Responder::from(user));
+        }
+        self.update_message(ctx).await;
+    }
+
+    /// Record whether `user` can make it to a confirmation's date
+    pub async fn set_attendance(&mut self, ctx: &Context, user: UserId, going: bool) {
+        let dates = if going {
+            self.dates.iter().copied().collect()
+        } else {
+            HashSet::new()
+        };
+        self.responses.insert(user.into(), dates.into());
+        self.update_message(ctx).await;
+    }
+
+    fn quorum(&self) -> usize {
+        self.quorum.unwrap_or(self.responses.len() / 2 + 1)
+    }
+
+    /// How many can make `date`, maybes included
+    fn attending(&self, date: &Slot) -> usize {
+        self.responses
+            .values()
+            .filter(|r| r.available(date))
+            .count()
+    }
+
+    fn is_full(&self, date: &Slot) -> bool {
+        self.capacity
+            .is_some_and(|capacity| self.attending(date) >= capacity)
+    }
+
+    /// Join the waitlist for the full final date, or leave it if already on it, returning a reply
+    pub async fn toggle_waitlist(&mut self, ctx: &Context, user: UserId) -> Phrase {
+        let date = match self.finalized {
+            Some(date) => date,
+            None => return Text::NoFinalToWait.into(),
+        };
+        if self
+            .get_user_response(&user)
+            .is_some_and(|r| r.available(&date))
+        {
+            return Text::AlreadyAttending.into();
+        }
+        let full = self.is_full(&date);
+        let waitlist = self.waitlists.entry(date).or_default();
+        let reply = if let Some(position) = waitlist.iter().position(|id| *id == user) {
+            waitlist.remove(position);
+            Text::LeftWaitlist.into()
+        } else if !full {
+            Text::FinalNotFull.into()
+        } else {
+            waitlist.push(user);
+            Phrase::new(Text::OnWaitlist, &[&waitlist.len()])
+        };
+        self.update_message(ctx).await;
+        reply
+    }
+
+    /// Give spots that opened up on `date` to those waiting for it, in order, and DM them
+    async fn promote_waitlisted(&mut self, ctx: &Context, date: Slot) {
+        while !self.is_full(&date) {
+            let waitlist = self.waitlists.entry(date).or_default();
+            if waitlist.is_empty() {
+                return;
+            }
+            let user = waitlist.remove(0);
+            info!(
+                "promoting {} from the waitlist: {}",
+                user,
+                self.message.id()
+            );
+            self.responses
+                .entry(user.into())
+                .or_default()
+                .set_state(date, (true, false, false));
+            let content = self.locale.fill(
+                Text::SpotOpened,
+                &[&self.slot_label(date), &self.title, &self.link()],
+            );
+            let notification = Notification {
+                recipient: Recipient::User(user),
+                content,
+                mentions: vec![],
+                roles: vec![],
+                attachment: None,
+                buttons: vec![],
+            };
+            notify::send(ctx, self.guild, notification).await;
+        }
+    }
+
+    /// Take `user` off the finalized date, reopening the scheduler without that date when too few
+    /// are left. Returns a reply for the user.
+    pub async fn withdraw(&mut self, ctx: &Context, user: UserId) -> Phrase {
+        let date = match self.finalized {
+            Some(date) => date,
+            None => return Text::NoFinalToWithdraw.into(),
+        };
+        let removed = match self.responses.get_mut(&Responder::from(user)) {
+            // Not short-circuiting, so the date is taken off both tiers
+            Some(response) => response.dates.remove(&date) | response.maybe.remove(&date),
+            None => false,
+        };
+        if !removed {
+            return Text::NotOnFinal.into();
+        }
+        self.record(ctx, Activity::Withdrew(user)).await;
+        self.promote_waitlisted(ctx, date).await;
+        let attending = self.attending(&date);
+        let quorum = self.quorum();
+        if attending >= quorum {
+            self.update_message(ctx).await;
+            return Text::Withdrawn.into();
+        }
+        info!("final date fell through: {}", self.message.id());
+        self.blackout_dates.insert(date);
+        self.apply_blackout_policy();
+        self.finalized = None;
+        self.closed = false;
+        self.sync_event(ctx).await;
+        self.update_message(ctx).await;
+        let content = self.locale.fill(
+            Text::FellThrough,
+            &[
+                &self.title,
+                &self.slot_label(date),
+                &attending,
+                &quorum,
+                &self.link(),
+                &self.respondents_sorted().iter().join(" "),
+            ],
+        );
+        let notification = Notification {
+            recipient: Recipient::Channel(self.message.channel_id()),
+            content: limits::truncate(&content, limits::MESSAGE_LENGTH),
+            mentions: self
+                .respondents_sorted()
+                .iter()
+                .filter_map(Responder::user)
+                .collect(),
+            roles: vec![],
+            attachment: None,
+            buttons: vec![],
+        };
+        notify::send(ctx, self.guild, notification).await;
+        Text::WithdrawReopened.into()
+    }
+
+    pub async fn set_blackout(&mut self, ctx: &Context, response: Response) {
+        let added: Vec<Slot> = response
+            .dates
+            .difference(&self.blackout_dates)
+            .copied()
+            .sorted()
+            .collect();
+        if !added.is_empty() {
+            self.record(ctx, Activity::BlackedOut(added)).await;
+        }
+        let expired = self
+            .blackout_dates
+            .iter()
+            .filter(|date| self.is_expired(date));
+        self.blackout_dates = expired.copied().chain(response.dates).collect();
+        let blackout_dates = &self.blackout_dates;
+        self.blackout_reasons
+            .retain(|date, _| blackout_dates.contains(date));
+        self.apply_blackout_policy();
+        let payload = Payload::Blackout {
+            scheduler: self.message.id(),
+            title: self.title.clone(),
+            dates: self
+                .blackout_dates
+                .iter()
+                .sorted()
+                .map(Slot::to_string)
+                .collect(),
+        };
+        webhook::send(ctx, self.guild, payload).await;
+        self.update_message(ctx).await;
+    }
+
+    /// Drop answers on blacked out dates, if that is the policy; otherwise they stay hidden
+    fn apply_blackout_policy(&mut self) {
+        if self.blackout_policy != BlackoutPolicy::Prune {
+            return;
+        }
+        let blocked = self.blocked_dates();
+        for response in self.responses.values_mut() {
+            for set in [
+                &mut response.dates,
+                &mut response.maybe,
+                &mut response.tentative,
+            ] {
+                set.retain(|slot| !blocked.contains(slot));
+            }
+        }
+    }
+
+    pub async fn set_roster(&mut self, ctx: &Context, roster: HashSet<UserId>) {
+        self.roster = roster;
+        *self.expected_count.get_mut().unwrap() = None;
+        self.update_message(ctx).await;
+    }
+
+    /// Offered blackout dates, struck through with their reasons
+    fn blackout_lines(&self) -> Vec<String> {
+        self.offered_dates()
+            .into_iter()
+            .filter(|date| self.blackout_dates.contains(date))
+            .map(|date| {
+                let label = format!("~~{}~~", self.date_style().short(date));
+                match self.blackout_reasons.get(&date) {
+                    Some(reason) => format!("{} {}", label, reason),
+                    None => label,
+                }
+            })
+            .collect()
+    }
+
+    /// Completion and the best date so far, if there is anything to show
+    fn stats(&self, embargoed: bool) -> Option<String> {
+        let mut lines = vec![];
+        lines.extend(self.completion());
+        if !embargoed && self.kind != PollKind::Interest {
+            if let Some(date) = self.best_dates().first() {
+                lines.push(self.locale.fill(
+                    Text::BestSoFar,
+                    &[&self.slot_label(*date), &self.count_label(date)],
+                ));
+            }
+        }
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// How many of the roster and invited players responded, if there are any
+    fn completion(&self) -> Option<String> {
+        let expected: HashSet<&UserId> = self.roster.union(&self.respondents).collect();
+        if expected.is_empty() {
+            return None;
+        }
+        let responded = expected
+            .iter()
+            .filter(|id| self.responses.contains_key(&Responder::from(***id)))
+            .count();
+        let percent = format!("{:.0}", responded as f64 * 100.0 / expected.len() as f64);
+        Some(self.locale.fill(
+            Text::ExpectedShare,
+            &[&responded, &expected.len(), &percent],
+        ))
+    }
+
+    fn get_responses(&self) -> String {
+        if self.responses.is_empty() {
+            "**0**".to_owned()
+        } else {
+            format!(
+                "**{}** ({})",
+                self.responses.len(),
+                self.responses
+                    .keys()
+                    .map(|responder| responder.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        }
+    }
+
+    async fn record(&mut self, ctx: &Context, activity: Activity) {
+        // Reacting to several date messages in a row is one response
+        if self.activity.last() == Some(&activity) {
+            return;
+        }
+        if let Activity::Changed {
+            user,
+            removed,
+            added,
+        } = &activity
+        {
+            let last = self.activity.last_mut();
+            if matches!(last, Some(Activity::Responded(responded)) if responded == user) {
+                return;
+            }
+            if let Some(Activity::Changed {
+                user: last,
+                removed: was_removed,
+                added: was_added,
+            }) = last
+            {
+                if last == user {
+                    fold_changes(removed, was_added, was_removed);
+                    fold_changes(added, was_removed, was_added);
+                    return;
+                }
+            }
+        }
+        self.post_to_thread(ctx, self.describe(&activity)).await;
+        self.activity.push(activity);
+        if self.activity.len() > ACTIVITY_ENTRIES {
+            self.activity.remove(0);
+        }
+    }
+
+    /// A line about `activity`; respondents aren't named on anonymous schedulers
+    fn describe(&self, activity: &Activity) -> String {
+        let locale = self.locale;
+        let who = |user: &UserId| {
+            if self.anonymous {
+                locale.text(Text::Someone).to_owned()
+            } else {
+                format!("<@{}>", user)
+            }
+        };
+        match activity {
+            Activity::Responded(user) => locale.fill(Text::ActivityResponded, &[&who(user)]),
+            // Changes that undid each other
+            Activity::Changed {
+                user,
+                removed,
+                added,
+            } if removed.is_empty() && added.is_empty() => {
+                locale.fill(Text::ActivityResponded, &[&who(user)])
+            }
+            Activity::Changed {
+                user,
+                removed,
+                added,
+            } => format!("{} {}", who(user), self.diff_label(removed, added)),
+            Activity::Withdrew(user) => locale.fill(Text::ActivityWithdrew, &[&who(user)]),
+            Activity::BlackedOut(slots) => locale.fill(
+                Text::OwnerBlackedOut,
+                &[&slots.iter().map(|s| self.date_style().short(*s)).join(", ")],
+            ),
+        }
+    }
+
+    /// What changed in a response, e.g. "removed Sat Jun 8, added Sun Jun 16"
+    fn diff_label(&self, removed: &[Slot], added: &[Slot]) -> String {
+        let style = self.date_style();
+        let dates = |slots: &[Slot]| slots.iter().map(|s| style.short(*s)).join(", ");
+        [(Text::RemovedDates, removed), (Text::AddedDates, added)]
+            .into_iter()
+            .filter(|(_, slots)| !slots.is_empty())
+            .map(|(text, slots)| self.locale.fill(text, &[&dates(slots)]))
+            .join(", ")
+    }
+
+    /// Recent events, newest first
+    fn recent_activity(&self) -> Option<String> {
+        let lines: Vec<String> = self
+            .activity
+            .iter()
+            .rev()
+            .map(|activity| self.describe(activity))
+            .collect();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+
+    /// Open a thread off the scheduler message for activity to be posted to
+    pub async fn start_thread(&mut self, ctx: &Context) {
+        let name = limits::truncate(&self.title, limits::THREAD_NAME_LENGTH);
+        match self
+            .message
+            .channel_id()
+            .create_thread_from_message(ctx, self.message.id(), CreateThread::new(name))
+            .await
+        {
+            Ok(thread) => self.thread = Some(thread.id),
+            Err(e) => error!("Cannot start thread for {}: {}", self.message.id(), e),
+        }
+    }
+
+    /// Post `content` to the scheduler's thread, if it has one, without pinging anyone
+    async fn post_to_thread(&self, ctx: &Context, content: String) {
+        let thread = match self.thread {
+            Some(thread) => thread,
+            None => return,
+        };
+        let notification = Notification {
+            recipient: Recipient::Channel(thread),
+            content: limits::truncate(&content, limits::MESSAGE_LENGTH),
+            mentions: vec![],
+            roles: vec![],
+            attachment: None,
+            buttons: vec![],
+        };
+        notify::send(ctx, self.guild, notification).await;
+    }
+
+    /// The dates `user` picked and when, shown to them in the language of their Discord client
+    pub fn my_response(&self, user: UserId, locale: Locale) -> String {
+        let response = match self.responses.get(&Responder::from(user)) {
+            Some(response) => response,
+            None => return locale.text(Text::NotResponded).to_owned(),
+        };
+        let title = locale.text(Text::YourResponse);
+        let style = DateStyle {
+            locale,
+            ..self.date_style()
+        };
+        let mut lines = vec![match response.responded_at {
+            Some(at) => format!("**{}**, updated <t:{}:R>", title, at.timestamp()),
+            None => format!("**{}**", title),
+        }];
+        for slot in self
+            .dates
+            .iter()
+            .filter(|slot| response.available(slot) && !self.is_blacked_out(slot))
+        {
+            let mut line = style.code(*slot);
+            if response.maybe.contains(slot) {
+                line += &format!(" {}", locale.text(Text::IfNeeded));
+            }
+            if response.tentative.contains(slot) {
+                line += &format!(" ({})", locale.text(Text::Tentative));
+            }
+            lines.push(line);
+        }
+        if lines.len() == 1 {
+            lines.push(locale.text(Text::NoDates).to_owned());
+        }
+        limits::truncate(&lines.join("\n"), limits::MESSAGE_LENGTH)
+    }
+
+    fn respondents_sorted(&self) -> Vec<Responder> {
+        self.responses.keys().cloned().sorted().collect()
+    }
+
+    /// Respondents in the order the owner chose to list them
+    fn respondents_listed(&self) -> Vec<Responder> {
+        if !self.by_recency {
+            return self.respondents_sorted();
+        }
+        self.responses
+            .iter()
+            .sorted_by_key(|(id, response)| (Reverse(response.responded_at), *id))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    fn mention_limit(&self) -> usize {
+        self.mention_limit.unwrap_or(DEFAULT_MENTION_LIMIT)
+    }
+
+    /// The count of respondents followed by their mentions, split across as many field values as
+    /// needed and collapsed past the mention limit. Anonymous schedulers only show the count.
+    fn responded_fields(&self) -> Vec<String> {
+        let users = self.respondents_listed();
+        if self.anonymous {
+            return vec![format!("**{}**", users.len())];
+        }
+        let shown = &users[..users.len().min(self.mention_limit())];
+        let mut fields = render::mention_chunks(
+            shown,
+            limits::EMBED_FIELD_VALUE_LENGTH - RESPONDED_RESERVED_LENGTH,
+        );
+        let count = format!("**{}**", users.len());
+        match fields.first_mut() {
+            Some(first) => *first = format!("{}\n{}", count, first),
+            None => fields.push(count),
+        }
+        if shown.len() < users.len() {
+            let more = format!("and {} more…", users.len() - shown.len());
+            let last = fields.last_mut().unwrap();
+            *last = format!("{}\n{}", last, more);
+        }
+        fields
+    }
+
+    fn results(&self) -> Vec<DateResult> {
+        let scores = self.scores();
+        self.dates
+            .iter()
+            .filter(|date| !self.is_blacked_out(date) && !self.is_expired(date))
+            .map(|date| DateResult {
+                slot: *date,
+                timestamp: date.timestamp(self.timezone()),
+                users: self
+                    .responses
+                    .iter()
+                    .filter(|(_, response)| response.dates.contains(date))
+                    .map(|(responder, _)| responder.clone())
+                    .collect(),
+                maybe: self
+                    .responses
+                    .iter()
+                    .filter(|(_, response)| response.maybe.contains(date))
+                    .map(|(responder, _)| responder.clone())
+                    .collect(),
+                score: self.ranked.then(|| scores.get(date).copied().unwrap_or(0)),
+                excluded: self.missing_required(date),
+                meets_threshold: self.threshold.map(|threshold| {
+                    let (firm, maybe) = self.count(date);
+                    firm + maybe >= threshold
+                }),
+            })
+            .collect()
+    }
+
+    /// Whether a required player who has responded can't make `date`
+    fn missing_required(&self, date: &Slot) -> bool {
+        self.required.iter().any(|user| {
+            self.responses
+                .get(&Responder::from(*user))
+                .is_some_and(|response| !response.available(date))
+        })
+    }
+
+    /// Mark `user` as required, or no longer required if they were, returning a reply
+    pub async fn toggle_required(&mut self, ctx: &Context, user: UserId) -> String {
+        let reply = if self.required.remove(&user) {
+            format!("<@{}> is no longer required", user)
+        } else {
+            self.required.insert(user);
+            format!(
+                "<@{}> is required; dates they can't make won't be picked as the best",
+                user
+            )
+        };
+        self.update_message(ctx).await;
+        reply
+    }
+
+    /// How many can make `date` for sure and how many only if needed
+    fn count(&self, date: &Slot) -> (usize, usize) {
+        let firm = self
+            .responses
+            .values()
+            .filter(|response| response.dates.contains(date))
+            .count();
+        let maybe = self
+            .responses
+            .values()
+            .filter(|response| response.maybe.contains(date))
+            .count();
+        (firm, maybe)
+    }
+
+    fn count_label(&self, date: &Slot) -> String {
+        let (firm, maybe) = self.count(date);
+        render::count(firm, maybe)
+    }
+
+    /// Borda count of the rankings: a respondent's first choice scores `RANKED_CHOICES` points,
+    /// their second one less and so on
+    fn scores(&self) -> HashMap<Slot, usize> {
+        let mut scores = HashMap::new();
+        if !self.ranked {
+            return scores;
+        }
+        for response in self.responses.values() {
+            for (i, slot) in response.ranking.iter().take(RANKED_CHOICES).enumerate() {
+                *scores.entry(*slot).or_default() += RANKED_CHOICES - i;
+            }
+        }
+        scores
+    }
+
+    /// What dates are ordered by: the score in ranked schedulers, then how many can make them,
+    /// then firm yeses
+    fn rank_key(
+        &self,
+        scores: &HashMap<Slot, usize>,
+        (date, firm, maybe): &(Slot, usize, usize),
+    ) -> (usize, usize, usize) {
+        let score = scores.get(date).copied().unwrap_or(0);
+        (score, firm + maybe, *firm)
+    }
+
+    /// How many can make each date still offered, for sure and only if needed
+    pub fn tally(&self) -> Vec<(Slot, usize, usize)> {
+        self.dates
+            .iter()
+            .filter(|date| !self.is_blacked_out(date) && !self.is_expired(date))
+            .map(|date| {
+                let (firm, maybe) = self.count(date);
+                (*date, firm, maybe)
+            })
+            .collect()
+    }
+
+    /// The dates most can make, preferring those with more firm yeses, among those every
+    /// required player can make. Ranked schedulers go by score first.
+    fn best_dates(&self) -> Vec<Slot> {
+        let scores = self.scores();
+        let tally: Vec<_> = self
+            .tally()
+            .into_iter()
+            .filter(|(date, _, _)| !self.missing_required(date))
+            .collect();
+        let max = tally
+            .iter()
+            .map(|entry| self.rank_key(&scores, entry))
+            .max()
+            .unwrap_or((0, 0, 0));
+        if max.1 == 0 {
+            return vec![];
+        }
+        tally
+            .into_iter()
+            .filter(|entry| self.rank_key(&scores, entry) == max)
+            .map(|(date, _, _)| date)
+            .collect()
+    }
+
+    /// Dates tied for the most available, among those every required player can make, ordered
+    /// by `by` and then by date
+    pub fn ranked_ties(&self, by: TieBreak) -> Vec<(Slot, usize, usize)> {
+        let scores = self.scores();
+        // Firm yeses are left to the tie-break
+        let key = |entry: &(Slot, usize, usize)| {
+            let (score, available, _) = self.rank_key(&scores, entry);
+            (score, available)
+        };
+        let tally: Vec<_> = self
+            .tally()
+            .into_iter()
+            .filter(|(date, _, _)| !self.missing_required(date))
+            .collect();
+        let max = tally.iter().map(key).max().unwrap_or((0, 0));
+        if max.1 == 0 {
+            return vec![];
+        }
+        tally
+            .into_iter()
+            .filter(|entry| key(entry) == max)
+            .sorted_by_key(|(date, _, maybe)| {
+                let key = match by {
+                    TieBreak::Earliest => 0,
+                    TieBreak::Required => self.required.len() - self.required_available(date),
+                    TieBreak::Maybe => *maybe,
+                };
+                (key, *date)
+            })
+            .collect()
+    }
+
+    /// How many required players said they can make `date`
+    fn required_available(&self, date: &Slot) -> usize {
+        self.required
+            .iter()
+            .filter(|user| {
+                self.responses
+                    .get(&Responder::from(**user))
+                    .is_some_and(|response| response.available(date))
+            })
+            .count()
+    }
+
+    /// Up to `n` dates with the most responses, best first, leaving out dates nobody can make
+    fn top_dates(&self, n: usize) -> Vec<Slot> {
+        let scores = self.scores();
+        self.tally()
+            .into_iter()
+            .filter(|(date, firm, maybe)| firm + maybe > 0 && !self.missing_required(date))
+            .sorted_by_key(|entry| (Reverse(self.rank_key(&scores, entry)), entry.0))
+            .take(n)
+            .map(|(date, _, _)| date)
+            .collect()
+    }
+
+    /// The best dates with their firm and maybe counts, unless results are still hidden
+    pub async fn top_results(&self, ctx: &Context) -> Option<Vec<(Slot, usize, usize)>> {
+        if self.embargoed(ctx).await.is_some() {
+            return None;
+        }
+        let top = self.top_dates(INTERSECT_DATES).into_iter().map(|date| {
+            let (firm, maybe) = self.count(&date);
+            (date, firm, maybe)
+        });
+        Some(top.collect())
+    }
+
+    /// Whether `user` must wait for more responses before seeing the best dates outside the
+    /// message, which owners never do and nobody does once a date is final
+    pub async fn hides_results_from(&self, ctx: &Context, user: UserId) -> bool {
+        !self.is_owner(user) && self.finalized.is_none() && self.embargoed(ctx).await.is_some()
+    }
+
+    /// An iCalendar file of the finalized date, or of the best dates until there is one
+    pub fn ics(&self) -> Option<String> {
+        let dates = match self.finalized {
+            Some(slot) => vec![slot],
+            None => self.top_dates(EXPORT_DATES),
+        };
+        if dates.is_empty() {
+            return None;
+        }
+        let events: Vec<Event> = dates
+            .into_iter()
+            .map(|slot| Event {
+                name: self.title.clone(),
+                description: self.body.clone(),
+                location: self.link(),
+                venue: self.venue,
+                slot,
+                timezone: self.timezone(),
+            })
+            .collect();
+        Some(ical::calendar(self.message.id(), &events))
+    }
+
+    /// Members of the group role who are not bots, or `None` if there is no group
+    async fn group_members(&self, ctx: &Context) -> Option<Vec<UserId>> {
+        let (guild, role) = (self.guild?, self.group?);
+        let members = roster::members(ctx, guild).await?;
+        Some(
+            members
+                .into_iter()
+                .filter(|m| !m.user.bot && m.roles.contains(&role))
+                .map(|m| m.user.id)
+                .collect(),
+        )
+    }
+
+    /// Everyone expected to respond
+    async fn expected(&self, ctx: &Context) -> HashSet<UserId> {
+        let mut expected: HashSet<UserId> = self.roster.union(&self.respondents).copied().collect();
+        if let Some(members) = self.group_members(ctx).await {
+            expected.extend(members);
+        }
+        expected
+    }
+
+    /// How many are expected to respond, counted again once the last count is stale
+    async fn expected_count(&self, ctx: &Context) -> usize {
+        let cached = *self.expected_count.lock().unwrap();
+        match cached {
+            Some((at, count)) if at.elapsed() < EXPECTED_COUNT_TTL => count,
+            _ => {
+                let count = self.expected(ctx).await.len();
+                *self.expected_count.lock().unwrap() = Some((Instant::now(), count));
+                count
+            }
+        }
+    }
+
+    /// Everyone expected to respond who hasn't, or `None` if nobody in particular is expected
+    async fn non_responders(&self, ctx: &Context) -> Option<Vec<UserId>> {
+        let expected = self.expected(ctx).await;
+        if expected.is_empty() {
+            return None;
+        }
+        Some(
+            expected
+                .into_iter()
+                .filter(|id| !self.responses.contains_key(&Responder::from(*id)))
+                .sorted()
+                .collect(),
+        )
+    }
+
+    /// A CSV with a row per respondent and a column per date; blacked out dates are marked x
+    pub async fn availability_matrix(&self, ctx: &Context) -> String {
+        let style = self.date_style();
+        let mut csv = std::iter::once("user".to_owned())
+            .chain(self.dates.iter().map(|d| csv_field(&style.export(*d))))
+            .join(",");
+        csv.push('\n');
+        for (responder, response) in self.responses.iter().sorted_by_key(|(id, _)| *id) {
+            let name = match responder {
+                Responder::Discord(user) => user_tag(ctx, *user).await,
+                Responder::Guest(name) => name.clone(),
+            };
+            let name = csv_field(&name);
+            let row = std::iter::once(name)
+                .chain(self.cells(response).into_iter().map(|cell| {
+                    match cell {
+                        Cell::BlackedOut => "x",
+                        Cell::Available => "1",
+                        Cell::Maybe => "maybe",
+                        Cell::Unavailable => "0",
+                    }
+                    .to_owned()
+                }))
+                .join(",");
+            csv += &row;
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// The same grid as `availability_matrix`, drawn as a PNG
+    pub async fn heatmap(&self, ctx: &Context) -> Option<Vec<u8>> {
+        let style = self.date_style();
+        let dates: Vec<String> = self.dates.iter().map(|d| style.short(*d)).collect();
+        let responses: Vec<_> = self.responses.iter().sorted_by_key(|(id, _)| *id).collect();
+        // Looked up together rather than one request after another
+        let names = futures::future::join_all(responses.iter().map(|(responder, _)| async move {
+            match responder {
+                Responder::Discord(user) => user_tag(ctx, *user).await,
+                Responder::Guest(name) => name.clone(),
+            }
+        }))
+        .await;
+        let rows: Vec<_> = names
+            .into_iter()
+            .zip(responses)
+            .map(|(name, (_, response))| {
+                (
+                    limits::truncate(&name, HEATMAP_NAME_LENGTH),
+                    self.cells(response),
+                )
+            })
+            .collect();
+        heatmap::render(&dates, &rows)
+    }
+
+    /// How `response` answered each date
+    fn cells(&self, response: &Response) -> Vec<Cell> {
+        self.dates
+            .iter()
+            .map(|d| {
+                if self.is_blacked_out(d) {
+                    Cell::BlackedOut
+                } else if response.dates.contains(d) {
+                    Cell::Available
+                } else if response.maybe.contains(d) {
+                    Cell::Maybe
+                } else {
+                    Cell::Unavailable
+                }
+            })
+            .collect()
+    }
+
+    /// How many have responded and how many are needed, while results are still hidden
+    async fn embargoed(&self, ctx: &Context) -> Option<(usize, usize)> {
+        if self.closed {
+            return None;
+        }
+        let required = match self.embargo? {
+            Embargo::Count(count) => count,
+            Embargo::Percent(percent) => (self.expected_count(ctx).await * percent).div_ceil(100),
+        };
+        let responded = self.responses.len();
+        (responded < required).then_some((responded, required))
+    }
+
+    /// Whether the tally is hidden, for callers without a context to look the group up with, so
+    /// an embargo on a share of the group is taken to be in force until it has been counted
+    pub fn is_embargoed(&self) -> bool {
+        let required = match self.embargo {
+            _ if self.closed => return false,
+            None => return false,
+            Some(Embargo::Count(count)) => count,
+            Some(Embargo::Percent(percent)) => match *self.expected_count.lock().unwrap() {
+                Some((_, expected)) => (expected * percent).div_ceil(100),
+                None => return true,
+            },
+        };
+        self.responses.len() < required
+    }
+
+    fn can_extend(&self) -> bool {
+        !self.explicit_dates && self.dates.len() + self.extension_dates().len() <= MAX_SLOTS
+    }
+
+    /// The slots one week past the end of the scheduler, on the weekdays and times already in use
+    fn extension_dates(&self) -> Vec<Slot> {
+        let pattern = self.pattern();
+        match self.dates.last() {
+            Some(last) => (1..=7)
+                .map(|i| last.date + Duration::days(i))
+                .flat_map(|date| {
+                    pattern
+                        .iter()
+                        .filter(move |(weekday, _)| *weekday == date.weekday())
+                        .map(move |(_, time)| Slot { date, time: *time })
+                })
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// The weekdays and times of day the scheduler offers, in order of time
+    fn pattern(&self) -> Vec<(Weekday, Option<NaiveTime>)> {
+        self.dates
+            .iter()
+            .map(|slot| (slot.date.weekday(), slot.time))
+            .unique()
+            .sorted_by_key(|(_, time)| *time)
+            .collect()
+    }
+
+    /// The slots the scheduler would offer if it ran for `weeks` from its first week, or `None`
+    /// if it isn't a weekly date scheduler
+    pub fn resized_dates(&self, weeks: i64) -> Option<Vec<Slot>> {
+        if self.kind != PollKind::Dates || self.explicit_dates {
+            return None;
+        }
+        let pattern = self.pattern();
+        let mut start = self.dates.first()?.date;
+        while start.weekday() != Weekday::Sat {
+            start = start.pred_opt().unwrap();
+        }
+        let window = DateRule::daily(start).with_end(start + Duration::weeks(weeks));
+        Some(
+            window
+                .flat_map(|date| {
+                    pattern
+                        .iter()
+                        .filter(move |(weekday, _)| *weekday == date.weekday())
+                        .map(move |(_, time)| Slot { date, time: *time })
+                })
+                .collect(),
+        )
+    }
+
+    /// Apply the owner's changes from `/schedule edit`. Answers and blackouts on dates that are no
+    /// longer offered are dropped; new dates show up the next time someone responds.
+    pub async fn edit(&mut self, ctx: &Context, changes: Changes) -> String {
+        let mut changed = vec![];
+        if let Some(title) = changes.title {
+            self.title = title;
+            changed.push("description".to_owned());
+        }
+        if let Some(group) = changes.group {
+            self.group = Some(group);
+            changed.push(format!("group to <@&{}>", group));
+        }
+        if let Some(dates) = changes.dates {
+            let offered: HashSet<Slot> = dates.iter().copied().collect();
+            let dropped = self.dates.iter().filter(|d| !offered.contains(d)).count();
+            let added = dates.len() + dropped - self.dates.len();
+            for response in self.responses.values_mut() {
+                response.dates.retain(|d| offered.contains(d));
+                response.maybe.retain(|d| offered.contains(d));
+            }
+            self.blackout_dates.retain(|d| offered.contains(d));
+            self.blackout_reasons.retain(|d, _| offered.contains(d));
+            self.dates = dates;
+            changed.push(format!("dates: {} added, {} removed", added, dropped));
+        }
+        if let Some(recurring) = changes.recurring {
+            self.recurring = recurring;
+            changed.push(format!("recurring to {}", recurring));
+        }
+        if let Some(threshold) = changes.threshold {
+            self.threshold = (threshold > 0).then_some(threshold);
+            changed.push(format!("threshold to {}", threshold));
+        }
+        if let Some(show) = changes.show_blackouts {
+            self.hide_blackouts = !show;
+            changed.push(format!("unavailable dates shown to {}", show));
+        }
+        if let Some(ranked) = changes.ranked {
+            self.ranked = ranked;
+            changed.push(format!(
+                "mode to {}",
+                if ranked { "ranked" } else { "dates" }
+            ));
+        }
+        if let Some(capacity) = changes.capacity {
+            self.capacity = (capacity > 0).then_some(capacity);
+            changed.push(format!("capacity to {}", capacity));
+        }
+        self.update_message(ctx).await;
+        format!("Updated {}", changed.join("; "))
+    }
+
+    fn link(&self) -> String {
+        self.message_link(self.message.id())
+    }
+
+    /// A line linking to the scheduler for `/schedule list`
+    pub fn listing(&self) -> String {
+        format!(
+            "[{}]({}) by <@{}>: {} responded",
+            self.title,
+            self.link(),
+            self.owner,
+            self.responses.len()
+        )
+    }
+
+    /// A line linking to the scheduler with the dates `user` picked, if they responded and
+    /// `viewer` may see who did
+    pub fn availability_of(&self, user: UserId, viewer: UserId) -> Option<String> {
+        if self.anonymous && !self.is_owner(viewer) && user != viewer {
+            return None;
+        }
+        let response = self.responses.get(&Responder::from(user))?;
+        let style = self.date_style();
+        let dates = self
+            .dates
+            .iter()
+            .filter(|date| {
+                response.available(date) && !self.is_blacked_out(date) && !self.is_expired(date)
+            })
+            .map(|date| {
+                if response.maybe.contains(date) {
+                    format!("{} (maybe)", style.short(*date))
+                } else {
+                    style.short(*date)
+                }
+            })
+            .join(", ");
+        Some(format!(
+            "[{}]({}): {}",
+            self.title,
+            self.link(),
+            if dates.is_empty() {
+                "none of the dates"
+            } else {
+                &dates
+            }
+        ))
+    }
+
+    /// Link to a message in the same channel as this scheduler
+    fn message_link(&self, message_id: MessageId) -> String {
+        let guild = match self.guild {
+            Some(guild) => guild.to_string(),
+            None => "@me".to_owned(),
+        };
+        format!(
+            "https://discord.com/channels/{}/{}/{}",
+            guild,
+            self.message.channel_id(),
+            message_id
+        )
+    }
+
+    /// DM the owner a summary of the results along with follow-up actions
+    async fn send_digest(&self, ctx: &Context) {
+        let best = self.best_dates();
+        let locale = self.locale;
+        let mut lines = vec![locale.fill(Text::HasClosed, &[&self.title, &self.link()])];
+        if self.kind == PollKind::Interest {
+            lines.push(locale.fill(Text::DigestInterested, &[&self.get_responses()]));
+        } else if best.is_empty() {
+            lines.push(locale.text(Text::NoResponsesReceived).to_owned());
+        } else {
+            lines.push(locale.fill(
+                Text::DigestBestDates,
+                &[
+                    &self.count_label(&best[0]),
+                    &best.iter().map(|d| self.slot_label(*d)).join(", "),
+                ],
+            ));
+        }
+        if let Some(threshold) = self.threshold {
+            let met = self
+                .results()
+                .iter()
+                .filter(|r| r.meets_threshold == Some(true))
+                .count();
+            lines.push(locale.fill(Text::ThresholdMet, &[&met, &threshold]));
+        }
+        if let Some(missing) = self.non_responders(ctx).await {
+            if missing.is_empty() {
+                lines.push(locale.text(Text::EveryoneResponded).to_owned());
+            } else {
+                let missing = missing.iter().map(|id| format!("<@{}>", id)).join(", ");
+                lines.push(locale.fill(Text::NotRespondedList, &[&missing]));
+            }
+        }
+        let content = limits::truncate(&lines.join("\n"), limits::MESSAGE_LENGTH);
+        let matrix = self.availability_matrix(ctx).await;
+        let message_id = self.message.id();
+        let can_extend = self.kind == PollKind::Dates && self.can_extend();
+        let button = |label: Text, action: &str, style, disabled| notify::Button {
+            label: locale.text(label).to_owned(),
+            custom_id: format!("{} {}", action, message_id),
+            style,
+            disabled,
+        };
+        let notification = Notification {
+            recipient: Recipient::User(self.owner),
+            content,
+            mentions: vec![],
+            roles: vec![],
+            attachment: (matrix.len() <= limits::UPLOAD_SIZE).then(|| notify::Attachment {
+                filename: MATRIX_FILENAME.to_owned(),
+                data: matrix,
+            }),
+            buttons: vec![
+                button(
+                    Text::FinalizeBest,
+                    "digest_finalize",
+                    ButtonStyle::Primary,
+                    best.is_empty(),
+                ),
+                button(
+                    Text::Extend,
+                    "digest_extend",
+                    ButtonStyle::Secondary,
+                    !can_extend,
+                ),
+                button(Text::Reopen, "digest_reopen", ButtonStyle::Secondary, false),
+            ],
+        };
+        notify::send(ctx, self.guild, notification).await;
+    }
+
+    pub async fn handle_digest(
+        &mut self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+        action: &str,
+    ) -> Result {
+        if !self
+            .check_owner(ctx, component, Text::OwnerOnlyDigest)
+            .await?
+        {
+            return Ok(());
+        }
+        let locale = self.locale;
+        let text = match action {
+            "digest_finalize" | "alert_finalize" => match self.best_dates().first() {
+                Some(date) => {
+                    // Alerts come in while the scheduler is still open
+                    self.closed = true;
+                    self.finalized = Some(*date);
+                    self.announce_final(ctx).await;
+                    locale.fill(Text::Finalized, &[&self.slot_label(*date)])
+                }
+                None => locale.text(Text::NoDatesToFinalize).to_owned(),
+            },
+            "digest_extend" => {
+                if self.can_extend() {
+                    let dates = self.extension_dates();
+                    self.dates.extend(dates);
+                    self.closed = false;
+                    self.finalized = None;
+                    locale.text(Text::Extended).to_owned()
+                } else {
+                    locale.text(Text::CannotExtend).to_owned()
+                }
+            }
+            "digest_reopen" => {
+                self.closed = false;
+                self.finalized = None;
+                locale.text(Text::Reopened).to_owned()
+            }
+            _ => return Err(Error::Unexpected(format!("digest action: {}", action))),
+        };
+        self.post_to_thread(ctx, text.clone()).await;
+        let content = format!("{}\n**{}**", component.message.content, text);
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content(content)
+                        .components(vec![]),
+                ),
+            )
+            .await?;
+        self.sync_event(ctx).await;
+        self.update_message(ctx).await;
+        Ok(())
+    }
+
+    /// Announce the finalized date in the scheduler's channel, mentioning everyone who can make it
+    async fn announce_final(&self, ctx: &Context) {
+        let slot = match self.finalized {
+            Some(slot) => slot,
+            None => return,
+        };
+        let attendees: Vec<&Responder> = self
+            .responses
+            .iter()
+            .filter(|(_, response)| response.available(&slot))
+            .map(|(responder, _)| responder)
+            .sorted()
+            .collect();
+        let template = self
+            .announcement
+            .as_deref()
+            .unwrap_or(announce::DEFAULT_TEMPLATE);
+        let content = announce::render(
+            template,
+            &[
+                ("title", self.title.clone()),
+                ("date", self.date_style().long(Slot::day(slot.date))),
+                ("time", slot.time_label().unwrap_or_default()),
+                ("when", self.slot_label(slot)),
+                ("attendees", attendees.iter().join(" ")),
+                (
+                    "venue",
+                    self.venue
+                        .map(|venue| format!("<#{}>", venue.channel))
+                        .unwrap_or_default(),
+                ),
+                ("link", self.link()),
+            ],
+        );
+        let notification = Notification {
+            recipient: Recipient::Channel(self.message.channel_id()),
+            content,
+            mentions: attendees
+                .iter()
+                .filter_map(|responder| responder.user())
+                .collect(),
+            roles: vec![],
+            attachment: None,
+            buttons: vec![],
+        };
+        notify::send(ctx, self.guild, notification).await;
+    }
+
+    /// Create, move or cancel the guild's scheduled event to match the finalized date
+    async fn sync_event(&mut self, ctx: &Context) {
+        self.sync_calendar(ctx).await;
+        let guild = match self.guild {
+            Some(guild) => guild,
+            None => return,
+        };
+        let slot = match self.finalized {
+            Some(slot) => slot,
+            None => {
+                if let Some(id) = self.event.take() {
+                    events::delete(ctx, guild, id).await;
+                }
+                return;
+            }
+        };
+        let event = self.final_event(slot);
+        if let Some(id) = self.event {
+            if events::edit(ctx, guild, id, &event).await {
+                return;
+            }
+        }
+        // The event was never created or has since been deleted in Discord
+        self.event = events::create(ctx, guild, &event).await;
+    }
+
+    fn final_event(&self, slot: Slot) -> Event {
+        Event {
+            name: self.title.clone(),
+            description: self.body.clone(),
+            location: self.link(),
+            venue: self.venue,
+            slot,
+            timezone: self.timezone(),
+        }
+    }
+
+    /// Move the Google Calendar copy along with the final date, showing its link once there is one
+    async fn sync_calendar(&mut self, ctx: &Context) {
+        if self.calendar_event.as_ref().map(|event| event.slot) == self.finalized {
+            return;
+        }
+        if let Some(event) = self.calendar_event.take() {
+            gcal::delete(&event).await;
+        }
+        if let Some(slot) = self.finalized {
+            self.calendar_event = gcal::insert(&self.final_event(slot)).await;
+            if self.calendar_event.is_some() {
+                self.update_message(ctx).await;
+            }
+        }
+    }
+
+    /// What the scheduler message should say and which buttons it should have
+    pub async fn render(&self, ctx: &Context) -> Rendered {
+        let title = limits::truncate(&self.title, limits::EMBED_TITLE_LENGTH);
+        let closed = self.closed;
+        let interest = self.kind == PollKind::Interest;
+        let embargoed = self.embargoed(ctx).await;
+        let locale = self.locale;
+        let mut fields = vec![];
+        let mut results_at = None;
+        let mut results_chunks = vec![];
+        for section in self.layout.iter() {
+            let field = match section {
+                Section::Responded => {
+                    let name = locale.text(if interest {
+                        Text::InterestedField
+                    } else {
+                        Text::RespondedField
+                    });
+                    for (i, value) in self.responded_fields().into_iter().enumerate() {
+                        // Continuation fields have a blank name
+                        let name = if i == 0 { name } else { "\u{200b}" };
+                        fields.push(Field::new(name, value, false));
+                    }
+                    None
+                }
+                Section::Results if !interest => {
+                    let groups = match embargoed {
+                        Some((responded, required)) => vec![vec![
+                            locale.fill(Text::WaitingForResponses, &[&responded, &required])
+                        ]],
+                        None => {
+                            let mut results = self.results();
+                            // Final results only list the dates that met the threshold
+                            if closed {
+                                results.retain(|r| r.meets_threshold != Some(false));
+                            }
+                            let style = self.date_style();
+                            let lines = render::results(&results, false, &style);
+                            let length = lines.iter().map(|line| line.len() + 1).sum::<usize>();
+                            // Long date ranges are broken up by week
+                            if length > limits::EMBED_FIELD_VALUE_LENGTH {
+                                render::weeks(&results, lines, &style)
+                            } else {
+                                vec![lines]
+                            }
+                        }
+                    };
+                    // Placed once the rest of the message is known, so they can take what's left
+                    results_at = Some(fields.len());
+                    results_chunks = limits::chunk_groups(groups, limits::EMBED_FIELD_VALUE_LENGTH);
+                    // Blacked out dates sit beside the results instead of vanishing from them
+                    let blackouts = self.blackout_lines();
+                    (!self.hide_blackouts && !blackouts.is_empty())
+                        .then(|| (Text::UnavailableDates, blackouts.join("\n"), true))
+                }
+                Section::Results => None,
+                Section::Stats => self
+                    .stats(embargoed.is_some())
+                    .map(|stats| (Text::Stats, stats, false)),
+                Section::Notes => self.notes.clone().map(|notes| (Text::Notes, notes, false)),
+                Section::Activity => self
+                    .recent_activity()
+                    .map(|activity| (Text::RecentActivity, activity, false)),
+            };
+            if let Some((name, value, inline)) = field {
+                let value = limits::truncate(&value, limits::EMBED_FIELD_VALUE_LENGTH);
+                fields.push(Field::new(locale.text(name), value, inline));
+            }
+        }
+        let finalized = self.finalized.map(|date| {
+            let line = locale.fill(Text::FinalDate, &[&self.slot_label(date)]);
+            match &self.calendar_event {
+                Some(event) => format!("{}\n[Google Calendar]({})", line, event.link),
+                None => line,
+            }
+        });
+        let confirmation = self.kind == PollKind::Confirmation;
+        let can_withdraw = self.kind == PollKind::Dates && self.finalized.is_some();
+        let confirmed_date = self
+            .dates
+            .first()
+            .map(|date| locale.fill(Text::ConfirmedDate, &[&self.slot_label(*date)]))
+            .unwrap_or_default();
+        let best = self.best_dates();
+        let winners = (!best.is_empty()).then(|| {
+            best.iter()
+                .map(|date| format!("**{}**", self.slot_label(*date)))
+                .join(", ")
+        });
+        let linked = self
+            .linked
+            .map(|id| match self.kind {
+                PollKind::Interest => locale.fill(Text::ScheduledIn, &[&self.message_link(id)]),
+                _ => locale.fill(Text::FromInterestCheck, &[&self.message_link(id)]),
+            })
+            .into_iter()
+            .chain(
+                self.previous
+                    .map(|id| locale.fill(Text::PreviousScheduler, &[&self.message_link(id)])),
+            )
+            .chain(
+                self.next
+                    .map(|id| locale.fill(Text::NextScheduler, &[&self.message_link(id)])),
+            )
+            .join("\n");
+        let content = match &self.group {
+            _ if !self.pending && !self.edit_pings => String::new(),
+            Some(role) => format!("<@&{}>", role),
+            None => self
+                .respondents
+                .iter()
+                .sorted()
+                .map(|id| format!("<@{}>", id))
+                .join(" "),
+        };
+        let mut buttons = vec![];
+        let mut text = String::new();
+        if interest {
+            if closed {
+                text = self.locale.text(Text::Closed).to_owned();
+            } else {
+                buttons.push(Button::new(
+                    ButtonStyle::Primary,
+                    self.locale.text(Text::Interested),
+                    "interest",
+                ));
+                buttons.push(Button::new(
+                    ButtonStyle::Secondary,
+                    self.locale.text(Text::NotInterested),
+                    "not_interested",
+                ));
+                buttons.push(Button::new(
+                    ButtonStyle::Secondary,
+                    self.locale.text(Text::ScheduleIt),
+                    "schedule_interest",
+                ));
+            }
+        } else if !closed {
+            if confirmation {
+                buttons.push(Button::new(
+                    ButtonStyle::Success,
+                    self.locale.text(Text::ImIn),
+                    "confirm_yes",
+                ));
+                buttons.push(Button::new(
+                    ButtonStyle::Secondary,
+                    self.locale.text(Text::CantMakeIt),
+                    "confirm_no",
+                ));
+                text = confirmed_date.clone();
+            } else {
+                buttons.push(Button::new(
+                    ButtonStyle::Primary,
+                    self.locale.text(Text::AddResponse),
+                    "response",
+                ));
+                buttons.push(Button::new(
+                    ButtonStyle::Secondary,
+                    self.locale.text(Text::MyResponse),
+                    "my_response",
+                ));
+            }
+            buttons.push(Button::new(
+                ButtonStyle::Secondary,
+                self.locale.text(Text::ShowDetails),
+                "details",
+            ));
+            buttons.push(Button::new(
+                ButtonStyle::Danger,
+                self.locale.text(Text::Close),
+                "close",
+            ));
+        } else {
+            buttons.push(Button::new(
+                ButtonStyle::Secondary,
+                self.locale.text(Text::ShowDetails),
+                "details",
+            ));
+            if can_withdraw {
+                buttons.push(Button::new(
+                    ButtonStyle::Secondary,
+                    self.locale.text(Text::CantMakeItAnymore),
+                    "withdraw",
+                ));
+            }
+            let waitlisting = self.finalized.is_some_and(|date| {
+                self.is_full(&date) || self.waitlists.get(&date).is_some_and(|w| !w.is_empty())
+            });
+            if can_withdraw && waitlisting {
+                buttons.push(Button::new(
+                    ButtonStyle::Secondary,
+                    self.locale.text(Text::JoinWaitlist),
+                    "waitlist",
+                ));
+            }
+            text = if confirmation {
+                confirmed_date
+            } else {
+                finalized.unwrap_or_else(|| match winners {
+                    Some(winners) => format!(
+                        "{}\n{}: {}",
+                        self.locale.text(Text::FinalResults),
+                        self.locale.text(Text::BestDates),
+                        winners
+                    ),
+                    None => self.locale.text(Text::FinalResults).to_owned(),
+                })
+            };
+        }
+        if self.read_only {
+            buttons.clear();
+            if !closed {
+                text = locale.text(Text::RespondWithCommand).to_owned();
+            }
+        }
+        if let (false, Some(deadline)) = (closed, self.deadline) {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text += &locale.fill(Text::ClosesAt, &[&deadline.timestamp()]);
+        }
+        if let Some(venue) = self.venue_line() {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text += &venue;
+        }
+        if !linked.is_empty() {
+            if !text.is_empty() {
+                text.push('\n');
+            }
+            text += &linked;
+        }
+        if let Some(body) = &self.body {
+            text = if text.is_empty() {
+                body.clone()
+            } else {
+                format!("{}\n\n{}", body, text)
+            };
+        }
+        let mut rendered = Rendered {
+            content,
+            title,
+            description: limits::truncate(&text, limits::EMBED_DESCRIPTION_LENGTH),
+            footer: self
+                .has_times()
+                .then(|| locale.fill(Text::TimesIn, &[&self.timezone().name()])),
+            fields,
+            buttons,
+        };
+        if let Some(at) = results_at {
+            let room = limits::EMBED_TOTAL_LENGTH.saturating_sub(rendered.embed_length());
+            let results = self.results_fields(results_chunks, room);
+            rendered.fields.splice(at..at, results);
+        }
+        rendered
+    }
+
+    /// Results fields, continuing in untitled ones, cut short to fit in `room` with a pointer
+    /// to the details for the rest
+    fn results_fields(&self, chunks: Vec<String>, room: usize) -> Vec<Field> {
+        let names =
+            std::iter::once(self.locale.text(Text::Results)).chain(std::iter::repeat("\u{200b}"));
+        let mut fields: Vec<Field> = names
+            .zip(chunks)
+            .map(|(name, chunk)| Field::new(name, chunk.trim_end().to_owned(), true))
+            .collect();
+        let length = |fields: &[Field]| fields.iter().map(Field::length).sum::<usize>();
+        if length(&fields) <= room {
+            return fields;
+        }
+        let more = self.locale.fill(
+            Text::MoreDatesUnder,
+            &[&self.locale.text(Text::ShowDetails)],
+        );
+        while fields.len() > 1 && length(&fields) + more.len() > room {
+            fields.pop();
+        }
+        let last = fields.pop().unwrap();
+        let keep = room.saturating_sub(length(&fields) + last.name.len() + more.len() + 1);
+        let value = limits::truncate(&last.value, keep);
+        fields.push(Field::new(last.name, format!("{}\n{}", value, more), true));
+        fields
+    }
+
+    /// Render the scheduler onto its message and any copies. Edits are held back to coalesce
+    /// bursts when running as the bot, so this only says whether the message could be edited
+    /// when they aren't.
+    pub async fn update_message(&self, ctx: &Context) -> bool {
+        let edits = self.edits(ctx).await;
+        match Debouncer::get(ctx).await {
+            Some(debouncer) => {
+                for edit in edits {
+                    debouncer.queue(ctx, edit);
+                }
+                true
+            }
+            None => debounce::apply_all(ctx, edits).await,
+        }
+    }
+
+    /// Like `update_message`, without holding back the edits
+    pub async fn update_message_now(&self, ctx: &Context) -> bool {
+        let edits = self.edits(ctx).await;
+        debounce::apply_all(ctx, edits).await
+    }
+
+    /// Edits for the copies, then the message itself
+    async fn edits(&self, ctx: &Context) -> Vec<Edit> {
+        let rendered = self.render(ctx).await;
+        let users: Vec<UserId> = self.respondents.iter().copied().collect();
+        let mut edits: Vec<Edit> = self
+            .mirrors
+            .iter()
+            .map(|mirror| Edit {
+                message: mirror.clone(),
+                // The group and invitees belong to the scheduler's own guild, so copies don't ping
+                rendered: Rendered {
+                    content: String::new(),
+                    ..rendered.clone()
+                },
+                group: self.group,
+                users: users.clone(),
+            })
+            .collect();
+        edits.push(Edit {
+            message: self.message.clone(),
+            rendered,
+            group: self.group,
+            users,
+        });
+        edits
+    }
+
+    pub async fn show_details(
+        &self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+        timezones: &HashMap<UserId, Tz>,
+        calendars: &HashMap<UserId, Calendar>,
+    ) -> Result {
+        let locale = Locale::parse(&component.locale).unwrap_or_default();
+        if !self.is_owner(component.user.id) {
+            if let Some((responded, required)) = self.embargoed(ctx).await {
+                component
+                    .create_response(
+                        ctx,
+                        CreateInteractionResponse::Message(
+                            CreateInteractionResponseMessage::new()
+                                .ephemeral(true)
+                                .content(
+                                    locale.fill(Text::ResultsHiddenUntil, &[&responded, &required]),
+                                ),
+                        ),
+                    )
+                    .await?;
+                return Ok(());
+            }
+        }
+        component.defer(ctx).await?;
+        let (details, heatmap) = if self.anonymous && !self.is_owner(component.user.id) {
+            (
+                render::results(&self.results(), false, &self.date_style()),
+                None,
+            )
+        } else {
+            (
+                self.named_details(timezones, calendars, locale),
+                self.heatmap(ctx).await,
+            )
+        };
+        let mut messages = limits::chunk_lines(details, limits::MESSAGE_LENGTH);
+        let last_content = messages.pop().unwrap_or_default();
+        for content in messages {
+            component
+                .create_followup(
+                    ctx,
+                    CreateInteractionResponseFollowup::new()
+                        .ephemeral(true)
+                        .content(content),
+                )
+                .await?;
+        }
+        let mut followup = CreateInteractionResponseFollowup::new()
+            .ephemeral(true)
+            .content(last_content);
+        if self.is_owner(component.user.id) {
+            let message_id = self.message.id();
+            let secondary = |label: Text, custom_id: String| {
+                CreateButton::new(custom_id)
+                    .style(ButtonStyle::Secondary)
+                    .label(locale.text(label))
+            };
+            let row = CreateActionRow::Buttons(vec![
+                CreateButton::new("blackout").label(locale.text(Text::AddBlackoutDates)),
+                secondary(Text::EditRoster, format!("roster {}", message_id)),
+                secondary(Text::RemindNonResponders, format!("remind {}", message_id))
+                    .disabled(self.closed),
+                secondary(
+                    if self.edit_pings {
+                        Text::StopEditPings
+                    } else {
+                        Text::EditPings
+                    },
+                    format!("edit_pings {}", message_id),
+                ),
+                secondary(
+                    if self.by_recency {
+                        Text::ListInOrder
+                    } else {
+                        Text::ListNewestFirst
+                    },
+                    format!("by_recency {}", message_id),
+                ),
+            ]);
+            let more = CreateActionRow::Buttons(vec![
+                secondary(Text::AnswerForGuest, format!("guest {}", message_id))
+                    .disabled(!self.takes_responses()),
+                secondary(
+                    if self.notify_owners.contains(&component.user.id) {
+                        Text::StopResponseDms
+                    } else {
+                        Text::DmOnResponses
+                    },
+                    format!("notify_me {}", message_id),
+                ),
+                secondary(Text::RankDates, format!("rank {}", message_id))
+                    .disabled(self.closed || self.ranked_ties(TieBreak::Earliest).len() < 2),
+            ]);
+            followup = followup.components(vec![row, more]);
+        }
+        if let Some(png) = heatmap {
+            followup = followup.add_file(CreateAttachment::bytes(png, heatmap::FILENAME));
+        }
+        component.create_followup(ctx, followup).await?;
+        Ok(())
+    }
+
+    /// Results with who can make each date, who responded and where they are
+    fn named_details(
+        &self,
+        timezones: &HashMap<UserId, Tz>,
+        calendars: &HashMap<UserId, Calendar>,
+        locale: Locale,
+    ) -> Vec<String> {
+        let mut details = render::details(
+            &self.results(),
+            self.responses.len(),
+            self.details_layout,
+            &self.date_style(),
+        );
+        let users = self.respondents_listed();
+        if users.len() > self.mention_limit() {
+            details.push(locale.fill(Text::RespondedCount, &[&users.len()]));
+            details.extend(render::mention_chunks(&users, limits::MESSAGE_LENGTH));
+        }
+        let updated: Vec<String> = self
+            .responses
+            .iter()
+            .filter_map(|(id, response)| Some((response.responded_at?, id, response.proxied_by)))
+            .sorted_by_key(|(at, id, _)| (Reverse(*at), *id))
+            .map(|(at, id, by)| {
+                let line = format!("{} <t:{}:R>", id, at.timestamp());
+                match by {
+                    Some(by) => locale.fill(Text::EnteredBy, &[&line, &by]),
+                    None => line,
+                }
+            })
+            .collect();
+        if !updated.is_empty() {
+            details.push(locale.text(Text::LastUpdated).to_owned());
+            details.extend(updated);
+        }
+        let notes: Vec<String> = self
+            .responses
+            .iter()
+            .filter_map(|(id, response)| Some((id, response.note.as_ref()?)))
+            .sorted_by_key(|(id, _)| *id)
+            .map(|(id, note)| format!("{}: {}", id, note))
+            .collect();
+        if !notes.is_empty() {
+            details.push(locale.text(Text::NotesHeading).to_owned());
+            details.extend(notes);
+        }
+        if let Some(waitlist) = self.finalized.and_then(|date| self.waitlists.get(&date)) {
+            if !waitlist.is_empty() {
+                details.push(locale.fill(Text::WaitlistCount, &[&waitlist.len()]));
+                details.push(
+                    waitlist
+                        .iter()
+                        .enumerate()
+                        .map(|(i, id)| format!("{}. <@{}>", i + 1, id))
+                        .join(", "),
+                );
+            }
+        }
+        let users: Vec<UserId> = users.iter().filter_map(Responder::user).collect();
+        let style = self.date_style();
+        let conflicts: Vec<String> = users
+            .iter()
+            .filter_map(|user| {
+                let calendar = calendars.get(user)?;
+                let response = self.responses.get(&Responder::from(*user))?;
+                let dates = self
+                    .dates
+                    .iter()
+                    .filter(|date| response.available(date) && calendar.blocks(date))
+                    .map(|date| style.short(*date))
+                    .join(", ");
+                (!dates.is_empty()).then(|| format!("<@{}>: {}", user, dates))
+            })
+            .collect();
+        if !conflicts.is_empty() {
+            details.push(locale.text(Text::CalendarConflicts).to_owned());
+            details.extend(conflicts);
+        }
+        details.extend(render::timezone_groups(
+            &users,
+            timezones,
+            &self.dates,
+            &style,
+        ));
+        details
+    }
+
+    /// Ask the owner for the roster, prefilled with the current one
+    pub async fn roster_prompt(&self, ctx: &Context, component: &ComponentInteraction) -> Result {
+        if !self
+            .check_owner(ctx, component, Text::OwnerOnlyRoster)
+            .await?
+        {
+            return Ok(());
+        }
+        let current = self
+            .roster
+            .iter()
+            .sorted()
+            .map(|id| format!("<@{}>", id))
+            .join("\n");
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Modal(
+                    CreateModal::new(format!("roster {}", self.message.id()), "Roster").components(
+                        vec![CreateActionRow::InputText(
+                            CreateInputText::new(
+                                InputTextStyle::Paragraph,
+                                "Mentions, IDs or names, one per line",
+                                "roster",
+                            )
+                            .value(current)
+                            .required(false),
+                        )],
+                    ),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Ask the owner who they're answering for, to add or update someone without Discord
+    pub async fn guest_prompt(&self, ctx: &Context, component: &ComponentInteraction) -> Result {
+        if !self
+            .check_owner(ctx, component, Text::OwnerOnlyGuests)
+            .await?
+        {
+            return Ok(());
+        }
+        let guests = self
+            .responses
+            .keys()
+            .filter_map(|responder| match responder {
+                Responder::Guest(name) => Some(name),
+                Responder::Discord(_) => None,
+            })
+            .sorted()
+            .join(", ");
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Modal(
+                    CreateModal::new(format!("guest {}", self.message.id()), "Guest").components(
+                        vec![CreateActionRow::InputText(
+                            CreateInputText::new(
+                                InputTextStyle::Short,
+                                "Name, or an existing guest's to edit",
+                                "guest",
+                            )
+                            .placeholder(if guests.is_empty() {
+                                "Dave's friend".to_owned()
+                            } else {
+                                limits::truncate(&guests, 100)
+                            })
+                            .max_length(limits::GUEST_NAME_LENGTH as u16)
+                            .required(true),
+                        )],
+                    ),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Ask `component`'s user for a note on their response, prefilled with the current one
+    pub async fn note_prompt(&self, ctx: &Context, component: &ComponentInteraction) -> Result {
+        let current = self
+            .responses
+            .get(&Responder::from(component.user.id))
+            .and_then(|response| response.note.clone())
+            .unwrap_or_default();
+        let locale = Locale::parse(&component.locale).unwrap_or_default();
+        let title = locale.text(Text::Note);
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Modal(
+                    CreateModal::new(format!("note {}", self.message.id()), title).components(
+                        vec![CreateActionRow::InputText(
+                            CreateInputText::new(
+                                InputTextStyle::Short,
+                                locale.text(Text::NoteLabel),
+                                "note",
+                            )
+                            .placeholder(locale.text(Text::NotePlaceholder))
+                            .max_length(limits::NOTE_LENGTH as u16)
+                            .value(current)
+                            .required(false),
+                        )],
+                    ),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Set or, when `note` is blank, clear the note on `user`'s response, returning a reply
+    pub fn set_note(&mut self, user: UserId, note: &str) -> Text {
+        let response = match self.responses.get_mut(&Responder::from(user)) {
+            Some(response) => response,
+            None => return Text::RespondBeforeNote,
+        };
+        let note = note.trim();
+        if note.is_empty() {
+            response.note = None;
+            return Text::NoteRemoved;
+        }
+        response.note = Some(limits::truncate(note, limits::NOTE_LENGTH));
+        Text::NoteSaved
+    }
+
+    /// Offered blackout dates to give reasons for, those without one first
+    pub fn blackouts_to_label(&self) -> Vec<Slot> {
+        self.offered_dates()
+            .into_iter()
+            .filter(|date| self.blackout_dates.contains(date))
+            .sorted_by_key(|date| (self.blackout_reasons.contains_key(date), *date))
+            .take(limits::ACTION_ROWS)
+            .collect()
+    }
+
+    /// Ask the owner why dates are blacked out, prefilled with the current reasons
+    pub async fn blackout_reason_prompt(
+        &self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+    ) -> Result {
+        if !self
+            .check_owner(ctx, component, Text::OwnerOnlyBlackoutReasons)
+            .await?
+        {
+            return Ok(());
+        }
+        let dates = self.blackouts_to_label();
+        if dates.is_empty() {
+            return Err(Error::user(Text::NoBlackoutsToExplain));
+        }
+        let rows = dates
+            .into_iter()
+            .map(|date| {
+                let label = self.date_style().long(date);
+                let current = self.blackout_reasons.get(&date).cloned();
+                CreateActionRow::InputText(
+                    CreateInputText::new(
+                        InputTextStyle::Short,
+                        limits::truncate(&label, limits::INPUT_LABEL_LENGTH),
+                        date.to_string(),
+                    )
+                    .placeholder("DM on vacation")
+                    .max_length(limits::NOTE_LENGTH as u16)
+                    .value(current.unwrap_or_default())
+                    .required(false),
+                )
+            })
+            .collect();
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Modal(
+                    CreateModal::new(
+                        format!("blackout_reasons {}", self.message.id()),
+                        "Blackout reasons",
+                    )
+                    .components(rows),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Set the reasons for blackout dates, clearing blank ones, returning a reply
+    pub async fn set_blackout_reasons(
+        &mut self,
+        ctx: &Context,
+        reasons: Vec<(Slot, String)>,
+    ) -> String {
+        let mut set = 0;
+        for (date, reason) in reasons {
+            if !self.blackout_dates.contains(&date) {
+                continue;
+            }
+            if reason.is_empty() {
+                self.blackout_reasons.remove(&date);
+            } else {
+                self.blackout_reasons
+                    .insert(date, limits::truncate(&reason, limits::NOTE_LENGTH));
+                set += 1;
+            }
+        }
+        self.update_message(ctx).await;
+        format!("{} blackout dates have reasons", set)
+    }
+
+    /// Ping everyone expected who hasn't responded yet
+    pub async fn remind(&self, ctx: &Context, component: &ComponentInteraction) -> Result {
+        if !self
+            .check_owner(ctx, component, Text::OwnerOnlyRemind)
+            .await?
+        {
+            return Ok(());
+        }
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Defer(
+                    CreateInteractionResponseMessage::new().ephemeral(true),
+                ),
+            )
+            .await?;
+        let missing = self.non_responders(ctx).await.unwrap_or_default();
+        let locale = Locale::parse(&component.locale).unwrap_or_default();
+        let text = if missing.is_empty() {
+            locale.text(Text::NobodyMissing).to_owned()
+        } else if self.send_reminder(ctx, &missing).await {
+            locale.fill(Text::Reminded, &[&missing.len()])
+        } else {
+            locale.fill(Text::ReminderQueued, &[&missing.len()])
+        };
+        component
+            .edit_response(ctx, EditInteractionResponse::new().content(text))
+            .await?;
+        Ok(())
+    }
+
+    /// Switch whether edits to the message keep mentioning the group or invitees
+    pub async fn toggle_edit_pings(
+        &mut self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+    ) -> Result {
+        if !self
+            .check_owner(ctx, component, Text::OwnerOnlyPings)
+            .await?
+        {
+            return Ok(());
+        }
+        self.edit_pings = !self.edit_pings;
+        let content =
+            Locale::parse(&component.locale)
+                .unwrap_or_default()
+                .text(if self.edit_pings {
+                    Text::EditPingsOn
+                } else {
+                    Text::EditPingsOff
+                });
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(content),
+                ),
+            )
+            .await?;
+        self.update_message(ctx).await;
+        Ok(())
+    }
+
+    /// Start or stop DMing `component`'s user, an owner, about every response
+    pub async fn toggle_notify(
+        &mut self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+    ) -> Result {
+        if !self
+            .check_owner(ctx, component, Text::OwnerOnlyNotify)
+            .await?
+        {
+            return Ok(());
+        }
+        let user = component.user.id;
+        let content = Locale::parse(&component.locale).unwrap_or_default().text(
+            if self.notify_owners.remove(&user) {
+                Text::NotifyOff
+            } else {
+                self.notify_owners.insert(user);
+                Text::NotifyOn
+            },
+        );
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(content),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn toggle_recency(
+        &mut self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+    ) -> Result {
+        if !self
+            .check_owner(ctx, component, Text::OwnerOnlyRecency)
+            .await?
+        {
+            return Ok(());
+        }
+        self.by_recency = !self.by_recency;
+        let content =
+            Locale::parse(&component.locale)
+                .unwrap_or_default()
+                .text(if self.by_recency {
+                    Text::RecencyOn
+                } else {
+                    Text::RecencyOff
+                });
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(content),
+                ),
+            )
+            .await?;
+        self.update_message(ctx).await;
+        Ok(())
+    }
+
+    /// Ping `missing` in the scheduler's channel, returning whether it went out now rather than
+    /// after quiet hours
+    async fn send_reminder(&self, ctx: &Context, missing: &[UserId]) -> bool {
+        let lines: Vec<String> = std::iter::once(
+            self.locale
+                .fill(Text::ReminderLine, &[&self.title, &self.link()]),
+        )
+        .chain(self.venue_line())
+        .chain(
+            missing
+                .chunks(REMINDER_MENTIONS_PER_LINE)
+                .map(|users| users.iter().map(|id| format!("<@{}>", id)).join(" ")),
+        )
+        .collect();
+        let mut sent = true;
+        for content in limits::chunk_lines(lines, limits::MESSAGE_LENGTH) {
+            let notification = Notification {
+                recipient: Recipient::Channel(self.message.channel_id()),
+                content,
+                mentions: missing.to_vec(),
+                roles: vec![],
+                attachment: None,
+                buttons: vec![],
+            };
+            sent &= notify::send(ctx, self.guild, notification).await;
+        }
+        sent
+    }
+
+    /// Whether the automatic reminder is due
+    pub fn due_to_remind(&self, now: DateTime<Utc>) -> bool {
+        let days = match self.remind_after {
+            Some(days) if !self.closed && !self.reminded => days,
+            _ => return false,
+        };
+        let posted = self.message.id().created_at().unix_timestamp();
+        now.timestamp() >= posted + Duration::days(days).num_seconds()
+    }
+
+    pub fn allows_tentative(&self) -> bool {
+        self.firm_up.is_some() && self.kind == PollKind::Dates
+    }
+
+    /// Respondents with tentative picks who are due to be asked about them: the deadline, or
+    /// else their earliest tentative date, is no more than `firm_up` days away
+    fn tentative_due(&self, now: DateTime<Utc>) -> Vec<UserId> {
+        let days = match self.firm_up {
+            Some(days) if !self.closed => days,
+            _ => return vec![],
+        };
+        let cutoff = now.timestamp() + Duration::days(days).num_seconds();
+        let midnight = NaiveTime::from_hms_opt(0, 0, 0).unwrap();
+        self.responses
+            .iter()
+            .filter_map(|(responder, response)| Some((responder.user()?, response)))
+            .filter(|(user, _)| !self.firm_up_asked.contains(user))
+            .filter(|(_, response)| {
+                let start = match self.deadline {
+                    Some(deadline) => Some(deadline.timestamp()),
+                    None => response
+                        .tentative
+                        .iter()
+                        .filter_map(|slot| {
+                            Slot {
+                                date: slot.date,
+                                time: Some(slot.time.unwrap_or(midnight)),
+                            }
+                            .timestamp(self.timezone())
+                        })
+                        .min(),
+                };
+                !response.tentative.is_empty() && start.is_some_and(|start| start <= cutoff)
+            })
+            .map(|(user, _)| user)
+            .sorted()
+            .collect()
+    }
+
+    pub fn due_to_firm_up(&self, now: DateTime<Utc>) -> bool {
+        !self.tentative_due(now).is_empty()
+    }
+
+    /// DM everyone due with the tentative picks they should confirm or drop
+    pub async fn ask_firm_up(&mut self, ctx: &Context, now: DateTime<Utc>) {
+        let message_id = self.message.id();
+        for user in self.tentative_due(now) {
+            let dates = self.responses[&Responder::from(user)]
+                .tentative
+                .iter()
+                .sorted()
+                .map(|date| self.slot_label(*date))
+                .join(", ");
+            let content = self
+                .locale
+                .fill(Text::FirmUpAsk, &[&dates, &self.title, &self.link()]);
+            let button = |label: Text, action: &str, style| notify::Button {
+                label: self.locale.text(label).to_owned(),
+                custom_id: format!("{} {}", action, message_id),
+                style,
+                disabled: false,
+            };
+            let notification = Notification {
+                recipient: Recipient::User(user),
+                content: limits::truncate(&content, limits::MESSAGE_LENGTH),
+                mentions: vec![],
+                roles: vec![],
+                attachment: None,
+                buttons: vec![
+                    button(Text::ConfirmTentative, "firm_yes", ButtonStyle::Success),
+                    button(Text::DropTentative, "firm_no", ButtonStyle::Secondary),
+                ],
+            };
+            notify::send(ctx, self.guild, notification).await;
+            self.firm_up_asked.insert(user);
+        }
+    }
+
+    /// Confirm or drop the tentative picks of the user answering a firm up DM
+    pub async fn firm_up(
+        &mut self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+        confirm: bool,
+    ) -> Result {
+        let text = match self.responses.get_mut(&Responder::from(component.user.id)) {
+            Some(response) if !response.tentative.is_empty() && !self.closed => {
+                let tentative = std::mem::take(&mut response.tentative);
+                if confirm {
+                    Text::Confirmed
+                } else {
+                    response.dates.retain(|date| !tentative.contains(date));
+                    Text::TakenOff
+                }
+            }
+            _ => Text::NothingToConfirm,
+        };
+        // The DM was written in the scheduler's language, so the answer is too
+        let content = format!(
+            "{}\n**{}**",
+            component.message.content,
+            self.locale.text(text)
+        );
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::UpdateMessage(
+                    CreateInteractionResponseMessage::new()
+                        .content(content)
+                        .components(vec![]),
+                ),
+            )
+            .await?;
+        self.update_message(ctx).await;
+        Ok(())
+    }
+
+    /// Remind everyone expected who hasn't responded, once
+    pub async fn auto_remind(&mut self, ctx: &Context) {
+        self.reminded = true;
+        let missing = self.non_responders(ctx).await.unwrap_or_default();
+        info!(
+            "reminding {} players automatically: {}",
+            missing.len(),
+            self.message.id()
+        );
+        if !missing.is_empty() {
+            self.send_reminder(ctx, &missing).await;
+        }
+    }
+
+    /// Show the owner the dates tied for best ordered by `by`, with buttons to pick one and
+    /// close. Switching tie-breaks `update`s the ranking in place.
+    pub async fn rank_prompt(
+        &self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+        by: TieBreak,
+        update: bool,
+    ) -> Result {
+        if !self
+            .check_owner(ctx, component, Text::OwnerOnlyRank)
+            .await?
+        {
+            return Ok(());
+        }
+        let locale = Locale::parse(&component.locale).unwrap_or_default();
+        let mut ties = self.ranked_ties(by);
+        ties.truncate(limits::BUTTONS_PER_ROW);
+        let mut lines = vec![locale.text(by.heading()).to_owned()];
+        lines.extend(ties.iter().enumerate().map(|(i, (date, firm, maybe))| {
+            let mut line = format!(
+                "{}. {} - {}",
+                i + 1,
+                self.slot_label(*date),
+                render::count(*firm, *maybe)
+            );
+            if !self.required.is_empty() {
+                line = locale.fill(
+                    Text::RequiredAvailable,
+                    &[&line, &self.required_available(date), &self.required.len()],
+                );
+            }
+            line
+        }));
+        if ties.len() < 2 {
+            lines.push(locale.text(Text::NoTies).to_owned());
+        }
+        let content = lines.join("\n");
+        let message_id = self.message.id();
+        let mut rows = vec![CreateActionRow::Buttons(
+            TieBreak::ALL
+                .into_iter()
+                .map(|tie_break| {
+                    CreateButton::new(format!("{} {}", tie_break.action(), message_id))
+                        .style(if tie_break == by {
+                            ButtonStyle::Primary
+                        } else {
+                            ButtonStyle::Secondary
+                        })
+                        .label(locale.text(tie_break.label()))
+                })
+                .collect(),
+        )];
+        if !ties.is_empty() {
+            rows.push(CreateActionRow::Buttons(
+                ties.iter()
+                    .map(|(date, _, _)| {
+                        CreateButton::new(format!("pick_date {} {}", message_id, date))
+                            .style(ButtonStyle::Success)
+                            .label(locale.fill(Text::PickDate, &[&self.date_style().short(*date)]))
+                    })
+                    .collect(),
+            ));
+        }
+        let message = CreateInteractionResponseMessage::new()
+            .ephemeral(true)
+            .content(content)
+            .components(rows);
+        let response = if update {
+            CreateInteractionResponse::UpdateMessage(message)
+        } else {
+            CreateInteractionResponse::Message(message)
+        };
+        component.create_response(ctx, response).await?;
+        Ok(())
+    }
+
+    /// Close with `slot` as the final date, as the owner picked from the ranking
+    pub async fn pick(
+        &mut self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+        slot: Slot,
+    ) -> Result {
+        if !self
+            .check_owner(ctx, component, Text::OwnerOnlyPick)
+            .await?
+        {
+            return Ok(());
+        }
+        component.defer(ctx).await?;
+        let reply = self.finalize(ctx, slot).await;
+        let content = Locale::parse(&component.locale)
+            .unwrap_or_default()
+            .phrase(&reply);
+        component
+            .edit_response(
+                ctx,
+                EditInteractionResponse::new()
+                    .content(content)
+                    .components(vec![]),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Close with `slot` as the final date and announce it, returning a reply
+    async fn finalize(&mut self, ctx: &Context, slot: Slot) -> Phrase {
+        if self.closed {
+            return Text::SchedulerAlreadyClosed.into();
+        }
+        if !self.tally().iter().any(|(date, _, _)| *date == slot) {
+            return Text::DateNotOffered.into();
+        }
+        self.finalized = Some(slot);
+        self.close(ctx).await;
+        self.announce_final(ctx).await;
+        self.sync_event(ctx).await;
+        Phrase::new(Text::ClosedWithFinal, &[&self.slot_label(slot)])
+    }
+
+    pub async fn close_prompt(&self, ctx: &Context, component: &ComponentInteraction) -> Result {
+        if !self
+            .check_owner(ctx, component, Text::OwnerOnlyClose)
+            .await?
+        {
+            return Ok(());
+        }
+        let locale = Locale::parse(&component.locale).unwrap_or_default();
+        component
+            .create_response(
+                ctx,
+                CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .ephemeral(true)
+                        .content(locale.text(Text::FinalizePrompt))
+                        .components(vec![CreateActionRow::Buttons(vec![
+                            CreateButton::new("close_yes").label(locale.text(Text::Yes)),
+                            CreateButton::new("cancel_yes")
+                                .style(ButtonStyle::Danger)
+                                .label(locale.text(Text::CancelWithoutResults)),
+                        ])]),
+                ),
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn handle_close(
+        &mut self,
+        ctx: &Context,
+        component: &ComponentInteraction,
+    ) -> Result {
+        component.defer(ctx).await?;
+        component
+            .edit_response(
+                ctx,
+                EditInteractionResponse::new()
+                    .content(
+                        Locale::parse(&component.locale)
+                            .unwrap_or_default()
+                            .text(Text::ClosedReply),
+                    )
+                    .components(vec![]),
+            )
+            .await?;
+        self.close(ctx).await;
+        Ok(())
+    }
+
+    /// Mark the message as cancelled and take its buttons away, ahead of the scheduler being
+    /// deleted. Returns whether the message and its copies could be edited.
+    pub async fn cancel(&mut self, ctx: &Context) -> bool {
+        self.closed = true;
+        if let (Some(guild), Some(id)) = (self.guild, self.event.take()) {
+            events::delete(ctx, guild, id).await;
+        }
+        if let Some(event) = self.calendar_event.take() {
+            gcal::delete(&event).await;
+        }
+        let title = limits::truncate(&self.title, limits::EMBED_TITLE_LENGTH);
+        let mut edited = true;
+        for message in std::iter::once(&self.message).chain(&self.mirrors) {
+            edited &= message
+                .edit(
+                    ctx,
+                    EditMessage::new()
+                        .embed(CreateEmbed::new().title(&title).description("Cancelled"))
+                        .components(vec![]),
+                )
+                .await
+                .map_err(|e| error!("Cannot edit message: {}", e))
+                .is_ok();
+        }
+        edited
+    }
+
+    pub async fn close(&mut self, ctx: &Context) {
+        self.closed = true;
+        let payload = Payload::Closed {
+            scheduler: self.message.id(),
+            title: self.title.clone(),
+            best: self.best_dates().iter().map(Slot::to_string).collect(),
+        };
+        webhook::send(ctx, self.guild, payload).await;
+        self.post_to_thread(ctx, format!("**{}** has closed", self.title))
+            .await;
+        self.update_message(ctx).await;
+        self.send_digest(ctx).await;
+    }
+
+    /// Whether a date has firm yeses from the quorum the owner set, or the deadline has passed
+    pub fn due_to_close(&self, now: DateTime<Utc>) -> bool {
+        if self.closed || self.kind != PollKind::Dates {
+            return false;
+        }
+        let quorum_met = self
+            .quorum
+            .is_some_and(|quorum| self.tally().iter().any(|(_, firm, _)| *firm >= quorum));
+        quorum_met || self.deadline.is_some_and(|deadline| deadline <= now)
+    }
+
+    /// Close without the owner, announcing the best dates to the group
+    pub async fn auto_close(&mut self, ctx: &Context) {
+        info!("scheduler closing automatically: {}", self.message.id());
+        self.close(ctx).await;
+        let best = self.best_dates();
+        let locale = self.locale;
+        let result = if best.is_empty() {
+            locale.text(Text::NobodyCouldMakeIt).to_owned()
+        } else {
+            locale.fill(
+                if best.len() == 1 {
+                    Text::BestDateIs
+                } else {
+                    Text::BestDatesAre
+                },
+                &[
+                    &best
+                        .iter()
+                        .map(|date| format!("**{}**", self.slot_label(*date)))
+                        .join(", "),
+                    &self.count_label(&best[0]),
+                ],
+            )
+        };
+        let (ping, mentions) = match self.group {
+            Some(role) => (format!("<@&{}>", role), vec![]),
+            None => {
+                let users = self.respondents_sorted();
+                let ping = users.iter().join(" ");
+                (ping, users.iter().filter_map(Responder::user).collect())
+            }
+        };
+        let mut content = locale.fill(Text::ClosedWith, &[&self.title, &result, &self.link()]);
+        if let Some(venue) = self.venue_line() {
+            content = format!("{}\n{}", content, venue);
+        }
+        content = format!("{}\n{}", content, ping);
+        let notification = Notification {
+            recipient: Recipient::Channel(self.message.channel_id()),
+            content: limits::truncate(&content, limits::MESSAGE_LENGTH),
+            mentions,
+            roles: self.group.into_iter().collect(),
+            attachment: None,
+            buttons: vec![],
+        };
+        notify::send(ctx, self.guild, notification).await;
+    }
+}
+
+/// Quote `value` for a CSV cell if it needs it
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// A user's tag, or their id if it can't be looked up
+async fn user_tag(ctx: &Context, user: UserId) -> String {
+    #[cfg(any(test, feature = "test-harness"))]
+    if Recorder::get(ctx).await.is_some() {
+        return user.to_string();
+    }
+    match user.to_user(ctx).await {
+        Ok(user) => user.tag(),
+        Err(_) => user.to_string(),
+    }
+}
+
+/// The index ranges of the dates on each page of the response flow: all of them when they fit,
+/// otherwise as many whole weeks as fit on a page
+fn pages(dates: &[Slot]) -> Vec<Range<usize>> {
+    if dates.len() <= BUTTON_SLOTS {
+        return vec![Range {
+            start: 0,
+            end: dates.len(),
+        }];
+    }
+    let first = dates[0].date;
+    let week = |i: usize| (dates[i].date - first).num_weeks();
+    let mut pages = vec![];
+    let (mut start, mut i) = (0, 0);
+    while i < dates.len() {
+        let mut end = i;
+        while end < dates.len() && week(end) == week(i) {
+            end += 1;
+        }
+        if end - start > PAGE_SLOTS {
+            if i > start {
+                pages.push(start..i);
+                start = i;
+            }
+            // A week that doesn't fit on a page by itself is split
+            while end - start > PAGE_SLOTS {
+                pages.push(start..start + PAGE_SLOTS);
+                start += PAGE_SLOTS;
+            }
+        }
+        i = end;
+    }
+    pages.push(start..dates.len());
+    pages
+}
+
+fn create_dm_buttons(
+    prompt: &Prompt,
+    response: &Response,
+    page: usize,
+    resp_type: ResponseType,
+) -> Vec<CreateActionRow> {
+    let pages = pages(&prompt.dates);
+    let paged = pages.len() > 1;
+    let range = pages[page.min(pages.len() - 1)].clone();
+    let date_rows = limits::ACTION_ROWS - if paged { 2 } else { 1 };
+    let per_row =
+        ((range.len() as f32 / date_rows as f32).ceil() as usize).clamp(2, limits::BUTTONS_PER_ROW);
+
+    let mut rows = vec![];
+    let mut ar = vec![];
+    for (n, i) in range.enumerate() {
+        if n > 0 && n % per_row == 0 {
+            rows.push(CreateActionRow::Buttons(std::mem::take(&mut ar)));
+        }
+        let date = &prompt.dates[i];
+        let label = if response.tentative.contains(date) {
+            format!("{} ?", prompt.labels[i])
+        } else {
+            prompt.labels[i].clone()
+        };
+        let mut button = CreateButton::new(format!("select {}", i)).label(label);
+        match resp_type {
+            ResponseType::Normal => {
+                if prompt.blackout_dates.contains(date) {
+                    button = button.style(ButtonStyle::Danger).disabled(true);
+                } else {
+                    button = button.style(if response.dates.contains(date) {
+                        ButtonStyle::Success
+                    } else if response.maybe.contains(date) {
+                        ButtonStyle::Primary
+                    } else {
+                        ButtonStyle::Secondary
+                    });
+                    if prompt.unavailable.contains(date) {
+                        button = button.emoji(ReactionType::Unicode(UNAVAILABLE_MARK.to_owned()));
+                    }
+                }
+            }
+            ResponseType::Blackout => {
+                button = button.style(if response.dates.contains(date) {
+                    ButtonStyle::Danger
+                } else {
+                    ButtonStyle::Secondary
+                });
+            }
+        }
+        ar.push(button);
+    }
+    rows.push(CreateActionRow::Buttons(ar));
+
+    if paged {
+        rows.push(CreateActionRow::Buttons(vec![
+            CreateButton::new("page_prev")
+                .label(prompt.locale.text(Text::Previous))
+                .style(ButtonStyle::Secondary)
+                .disabled(page == 0),
+            CreateButton::new("page")
+                .label(format!(
+                    "{} {}/{}",
+                    prompt.locale.text(Text::Page),
+                    page + 1,
+                    pages.len()
+                ))
+                .style(ButtonStyle::Secondary)
+                .disabled(true),
+            CreateButton::new("page_next")
+                .label(prompt.locale.text(Text::Next))
+                .style(ButtonStyle::Secondary)
+                .disabled(page + 1 >= pages.len()),
+        ]));
+    }
+
+    let mut ar = vec![];
+
+    if resp_type == ResponseType::Blackout {
+        ar.push(
+            CreateButton::new("select_all")
+                .label(prompt.locale.text(Text::SelectAll))
+                .style(ButtonStyle::Success),
+        );
+        ar.push(
+            CreateButton::new("clear_all")
+                .label(prompt.locale.text(Text::ClearAll))
+                .style(ButtonStyle::Secondary),
+        );
+    }
+
+    if !prompt.quick.is_empty() {
+        ar.push(
+            CreateButton::new("quick")
+                .label(prompt.locale.text(Text::QuickSelect))
+                .style(ButtonStyle::Secondary),
+        );
+    }
+
+    ar.push(CreateButton::new("submit").label(prompt.locale.text(Text::Submit)));
+    rows.push(CreateActionRow::Buttons(ar));
+    rows
+}
+
+/// The quick actions in place of the date buttons, with a way back
+fn create_quick_buttons(actions: &[QuickAction], locale: Locale) -> Vec<CreateActionRow> {
+    let mut rows = vec![];
+    for chunk in &actions
+        .iter()
+        .take(BUTTON_SLOTS)
+        .enumerate()
+        .chunks(limits::BUTTONS_PER_ROW)
+    {
+        rows.push(CreateActionRow::Buttons(
+            chunk
+                .map(|(i, action)| {
+                    CreateButton::new(format!("quick {}", i))
+                        .label(action.label())
+                        .style(ButtonStyle::Secondary)
+                })
+                .collect(),
+        ));
+    }
+
+    rows.push(CreateActionRow::Buttons(vec![
+        CreateButton::new("quick_back")
+            .label(locale.text(Text::Back))
+            .style(ButtonStyle::Secondary),
+        CreateButton::new("submit").label(locale.text(Text::Submit)),
+    ]));
+    rows
+}
+
+// Ephemeral messages can only be edited for a limited time after they are initally created;
+// testing indicates that this limit is 15 minutes
+const RESP_TIMEOUT: std::time::Duration = std::time::Duration::new(60 * 14, 0);
+
+pub async fn get_response(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    mut response: Response,
+    prompt: Prompt,
+    resp_type: ResponseType,
+) -> Result<Option<Response>> {
+    if prompt.ranked {
+        return get_ranking(ctx, component, response, prompt).await;
+    }
+    for date in &prompt.unavailable {
+        response.set_state(*date, (false, false, false));
+    }
+    let mut content = vec![];
+    if prompt.tentative {
+        content.push(prompt.locale.text(Text::PickDatesTentative));
+    } else if resp_type == ResponseType::Normal {
+        content.push(prompt.locale.text(Text::PickDates));
+    }
+    content.extend(prompt.note.as_deref());
+    let unavailable = format!(
+        "{} marks dates your personal calendar rules out; see /unavailable",
+        UNAVAILABLE_MARK
+    );
+    if !prompt.unavailable.is_empty() {
+        content.push(unavailable.as_str());
+    }
+    let mut message = CreateInteractionResponseMessage::new()
+        .ephemeral(true)
+        .components(create_dm_buttons(&prompt, &response, 0, resp_type));
+    if !content.is_empty() {
+        message = message.content(content.join("\n"));
+    }
+    component
+        .create_response(ctx, CreateInteractionResponse::Message(message))
+        .await?;
+    let _picker = shutdown::track(ctx, component.id, &component.token, prompt.locale).await;
+
+    let expiration = Instant::now() + RESP_TIMEOUT;
+
+    let message = component.get_response(ctx).await?;
+    // Whether the quick actions are showing instead of the dates
+    let mut quick_shown = false;
+    let (mut page, last_page) = (0usize, pages(&prompt.dates).len() - 1);
+    loop {
+        let interaction = message
+            .await_component_interaction(&ctx.shard)
+            .timeout(expiration - Instant::now())
+            .await;
+        let interaction = match interaction {
+            Some(i) => i,
+            None => {
+                info!("Response timed out");
+                component
+                    .edit_response(
+                        ctx,
+                        EditInteractionResponse::new()
+                            .content(prompt.locale.text(Text::ResponseTimedOut))
+                            .components(vec![]),
+                    )
+                    .await?;
+                return Ok(None);
+            }
+        };
+        interaction.defer(ctx).await?;
+        let button_id = interaction.data.custom_id.as_str();
+        match button_id {
+            "submit" => {
+                if component
+                    .edit_response(
+                        ctx,
+                        EditInteractionResponse::new()
+                            .content(prompt.locale.text(Text::ResponseSubmitted))
+                            .components(vec![]),
+                    )
+                    .await
+                    .is_err()
+                {
+                    error!("Cannot update message");
+                }
+                return Ok(Some(response));
+            }
+            "select_all" => {
+                response.dates = prompt
+                    .dates
+                    .iter()
+                    .filter(|d| !prompt.blackout_dates.contains(d))
+                    .cloned()
+                    .collect()
+            }
+            "clear_all" => {
+                response.dates.clear();
+                response.maybe.clear();
+                response.tentative.clear();
+            }
+            "quick" => quick_shown = true,
+            "quick_back" => quick_shown = false,
+            "page_prev" => page = page.saturating_sub(1),
+            "page_next" => page = (page + 1).min(last_page),
+            _ => {
+                let unexpected = || Error::Unexpected(format!("button: {}", button_id));
+                let (button_id, data) = button_id.split_once(' ').ok_or_else(unexpected)?;
+                match button_id {
+                    "select" => {
+                        let date = data
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|index| prompt.dates.get(index))
+                            .ok_or_else(unexpected)?;
+                        // Responses cycle through yes, tentative when allowed, if needed and no;
+                        // blackouts are on or off
+                        if prompt.tentative
+                            && response.dates.contains(date)
+                            && !response.tentative.contains(date)
+                        {
+                            response.tentative.insert(*date);
+                        } else if response.dates.remove(date) {
+                            response.tentative.remove(date);
+                            if resp_type == ResponseType::Normal {
+                                response.maybe.insert(*date);
+                            }
+                        } else if !response.maybe.remove(date) {
+                            response.dates.insert(*date);
+                        }
+                    }
+                    "quick" => {
+                        let action = data
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|index| prompt.quick.get(index))
+                            .ok_or_else(unexpected)?;
+                        // Blackouts being edited are not off limits to themselves
+                        let blocked = match resp_type {
+                            ResponseType::Normal => prompt.blackout_dates.clone(),
+                            ResponseType::Blackout => HashSet::new(),
+                        };
+                        action.apply(&mut response, &prompt.dates, &blocked);
+                        quick_shown = false;
+                    }
+                    _ => return Err(unexpected()),
+                }
+            }
+        }
+        component
+            .edit_response(
+                ctx,
+                EditInteractionResponse::new().components(if quick_shown {
+                    create_quick_buttons(&prompt.quick, prompt.locale)
+                } else {
+                    create_dm_buttons(&prompt, &response, page, resp_type)
+                }),
+            )
+            .await?;
+    }
+}
+
+/// The ranking so far, with a menu of the dates left for the next choice until there are
+/// `RANKED_CHOICES`
+fn create_ranking_menu(prompt: &Prompt, ranking: &[Slot]) -> Vec<CreateActionRow> {
+    let remaining: Vec<usize> = (0..prompt.dates.len())
+        .filter(|i| {
+            let date = &prompt.dates[*i];
+            !prompt.blackout_dates.contains(date) && !ranking.contains(date)
+        })
+        .take(limits::SELECT_OPTIONS)
+        .collect();
+    let mut rows = vec![];
+    if ranking.len() < RANKED_CHOICES && !remaining.is_empty() {
+        let options = remaining
+            .into_iter()
+            .map(|i| CreateSelectMenuOption::new(&prompt.labels[i], i.to_string()))
+            .collect();
+        rows.push(CreateActionRow::SelectMenu(
+            CreateSelectMenu::new("rank_choice", CreateSelectMenuKind::String { options })
+                .placeholder(
+                    prompt
+                        .locale
+                        .fill(Text::ChoiceNumber, &[&(ranking.len() + 1)]),
+                ),
+        ));
+    }
+    rows.push(CreateActionRow::Buttons(vec![
+        CreateButton::new("submit")
+            .style(ButtonStyle::Success)
+            .label(prompt.locale.text(Text::Submit))
+            .disabled(ranking.is_empty()),
+        CreateButton::new("rank_reset")
+            .style(ButtonStyle::Secondary)
+            .label(prompt.locale.text(Text::StartOver)),
+    ]));
+    rows
+}
+
+fn ranking_content(prompt: &Prompt, ranking: &[Slot]) -> String {
+    let mut lines = vec![prompt.locale.fill(Text::PickTopDates, &[&RANKED_CHOICES])];
+    lines.extend(prompt.note.clone());
+    lines.extend(ranking.iter().enumerate().filter_map(|(n, date)| {
+        let i = prompt.dates.iter().position(|d| d == date)?;
+        Some(format!("{}. {}", n + 1, prompt.labels[i]))
+    }));
+    lines.join("\n")
+}
+
+/// Like `get_response`, for ranked schedulers: the user picks up to `RANKED_CHOICES` dates in
+/// order of preference, one select menu at a time
+async fn get_ranking(
+    ctx: &Context,
+    component: &ComponentInteraction,
+    mut response: Response,
+    prompt: Prompt,
+) -> Result<Option<Response>> {
+    let mut ranking: Vec<Slot> = response
+        .ranking
+        .iter()
+        .filter(|date| prompt.dates.contains(date) && !prompt.blackout_dates.contains(date))
+        .copied()
+        .collect();
+    component
+        .create_response(
+            ctx,
+            CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .ephemeral(true)
+                    .content(ranking_content(&prompt, &ranking))
+                    .components(create_ranking_menu(&prompt, &ranking)),
+            ),
+        )
+        .await?;
+    let _picker = shutdown::track(ctx, component.id, &component.token, prompt.locale).await;
+
+    let expiration = Instant::now() + RESP_TIMEOUT;
+    let message = component.get_response(ctx).await?;
+    loop {
+        let interaction = message
+            .await_component_interaction(&ctx.shard)
+            .timeout(expiration - Instant::now())
+            .await;
+        let interaction = match interaction {
+            Some(i) => i,
+            None => {
+                info!("Response timed out");
+                component
+                    .edit_response(
+                        ctx,
+                        EditInteractionResponse::new()
+                            .content(prompt.locale.text(Text::ResponseTimedOut))
+                            .components(vec![]),
+                    )
+                    .await?;
+                return Ok(None);
+            }
+        };
+        interaction.defer(ctx).await?;
+        let unexpected = || Error::Unexpected(format!("button: {}", interaction.data.custom_id));
+        match interaction.data.custom_id.as_str() {
+            "submit" => {
+                component
+                    .edit_response(
+                        ctx,
+                        EditInteractionResponse::new()
+                            .content(prompt.locale.text(Text::ResponseSubmitted))
+                            .components(vec![]),
+                    )
+                    .await?;
+                // The ranked dates are the ones the user can make
+                for date in prompt
+                    .dates
+                    .iter()
+                    .filter(|date| !prompt.blackout_dates.contains(date))
+                {
+                    let picked = ranking.contains(date);
+                    response.set_state(*date, (picked, false, false));
+                }
+                response.ranking = ranking;
+                return Ok(Some(response));
+            }
+            "rank_reset" => ranking.clear(),
+            "rank_choice" => {
+                let values = match &interaction.data.kind {
+                    ComponentInteractionDataKind::StringSelect { values } => values.as_slice(),
+                    _ => &[],
+                };
+                let date = values
+                    .first()
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .and_then(|i| prompt.dates.get(i))
+                    .ok_or_else(unexpected)?;
+                if !ranking.contains(date) && ranking.len() < RANKED_CHOICES {
+                    ranking.push(*date);
+                }
+            }
+            _ => return Err(unexpected()),
+        }
+        component
+            .edit_response(
+                ctx,
+                EditInteractionResponse::new()
+                    .content(ranking_content(&prompt, &ranking))
+                    .components(create_ranking_menu(&prompt, &ranking)),
+            )
+            .await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::harness::{self, Recorder};
+    use chrono::TimeZone;
+
+    const OWNER: UserId = UserId::new(100);
+    const MESSAGE: MessageId = MessageId::new(200);
+
+    fn options() -> Options {
+        Options {
+            title: "Game night".to_owned(),
+            body: None,
+            group: None,
+            weeks: 2,
+            skip: None,
+            days: default_days(),
+            times: vec![],
+            dates: vec![],
+            details_layout: Default::default(),
+            blackout_policy: Default::default(),
+            embargo: None,
+            locale: Locale::En,
+            date_format: None,
+            layout: render::default_layout(),
+            notes: None,
+            mention_limit: None,
+            quorum: None,
+            threshold: None,
+            deadline: None,
+            remind_after: None,
+            firm_up: None,
+            venue: None,
+            reactions: false,
+            announcement: None,
+            timezone: None,
+            read_only: false,
+            anonymous: false,
+            thread: false,
+            recurring: false,
+            ranked: false,
+        }
+    }
+
+    fn response(dates: &[Slot], maybe: &[Slot]) -> Response {
+        Response {
+            dates: dates.iter().copied().collect(),
+            maybe: maybe.iter().copied().collect(),
+            ..Default::default()
+        }
+    }
+
+    fn scheduler() -> Scheduler {
+        Scheduler::new(OWNER, None, harness::message(MESSAGE.get()), options())
+    }
+
+    #[tokio::test]
+    async fn create_renders_response_buttons() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let scheduler = scheduler();
+        assert!(scheduler.get_dates().len() >= 2);
+
+        assert!(scheduler.update_message(&ctx).await);
+        let body = recorder.last_edit(MESSAGE).unwrap();
+        assert!(harness::text(&body).contains("Game night"));
+        assert_eq!(
+            harness::buttons(&body),
+            ["response", "my_response", "details", "close"]
+        );
+    }
+
+    #[tokio::test]
+    async fn responses_rank_dates() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = scheduler();
+        let dates = scheduler.get_dates();
+
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[dates[0], dates[1]], &[]))
+            .await;
+        scheduler
+            .add_response(&ctx, UserId::new(2), response(&[dates[1]], &[dates[0]]))
+            .await;
+
+        // Both dates suit everyone, but only the second firmly
+        assert_eq!(scheduler.best_dates(), [dates[1]]);
+        let body = recorder.last_edit(MESSAGE).unwrap();
+        let text = harness::text(&body);
+        assert!(text.contains("<@1>") && text.contains("<@2>"));
+    }
+
+    #[tokio::test]
+    async fn blackouts_remove_dates_from_results() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = scheduler();
+        let dates = scheduler.get_dates();
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[dates[0], dates[1]], &[]))
+            .await;
+        scheduler
+            .add_response(&ctx, UserId::new(2), response(&[dates[1]], &[]))
+            .await;
+
+        scheduler
+            .set_blackout(&ctx, HashSet::from([dates[1]]).into())
+            .await;
+
+        assert_eq!(scheduler.best_dates(), [dates[0]]);
+        assert!(scheduler.results().iter().all(|r| r.slot != dates[1]));
+    }
+
+    #[tokio::test]
+    async fn shrinking_prunes_answers_outside_the_window() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = scheduler();
+        let dates = scheduler.get_dates();
+        let last = *dates.last().unwrap();
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[dates[0]], &[last]))
+            .await;
+
+        let resized = scheduler.resized_dates(1).unwrap();
+        assert!(!resized.contains(&last));
+        scheduler
+            .edit(
+                &ctx,
+                Changes {
+                    dates: Some(resized.clone()),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        assert_eq!(scheduler.get_dates(), resized);
+        let answer = scheduler.get_user_response(&UserId::new(1)).unwrap();
+        assert!(answer.dates.contains(&dates[0]));
+        assert!(answer.maybe.is_empty());
+    }
+
+    #[tokio::test]
+    async fn close_sends_digest_to_owner() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = scheduler();
+        let dates = scheduler.get_dates();
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[dates[0]], &[]))
+            .await;
+
+        scheduler.close(&ctx).await;
+
+        assert!(scheduler.is_closed());
+        let body = recorder.last_edit(MESSAGE).unwrap();
+        assert_eq!(harness::buttons(&body), ["details"]);
+        let notifications = recorder.notifications();
+        assert_eq!(notifications.len(), 1);
+        let digest = &notifications[0];
+        assert!(matches!(digest.recipient, Recipient::User(OWNER)));
+        assert!(digest.content.contains("**Game night** has closed"));
+        assert!(digest
+            .buttons
+            .iter()
+            .any(|b| b.custom_id == format!("digest_finalize {}", MESSAGE)));
+    }
+
+    /// A scheduler over two fixed weekends, so rendered dates don't depend on today
+    fn weekends() -> Scheduler {
+        let mut scheduler = scheduler();
+        scheduler.dates = ["2024-07-13", "2024-07-14", "2024-07-20", "2024-07-21"]
+            .iter()
+            .map(|d| Slot::day(d.parse().unwrap()))
+            .collect();
+        scheduler
+    }
+
+    /// Three respondents, all of whom can make the second date
+    fn answered() -> Scheduler {
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler
+            .responses
+            .insert(UserId::new(1).into(), response(&[d[0], d[1]], &[]));
+        scheduler
+            .responses
+            .insert(UserId::new(2).into(), response(&[d[1]], &[]));
+        scheduler
+            .responses
+            .insert(UserId::new(3).into(), response(&[d[1]], &[d[2]]));
+        scheduler
+    }
+
+    #[tokio::test]
+    async fn quorum_closes_and_pings_group() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = answered();
+        scheduler.group = Some(RoleId::new(300));
+        let now = Utc::now();
+        scheduler.quorum = Some(4);
+        assert!(!scheduler.due_to_close(now));
+        scheduler.quorum = Some(3);
+        assert!(scheduler.due_to_close(now));
+
+        scheduler.auto_close(&ctx).await;
+
+        assert!(scheduler.is_closed());
+        assert!(!scheduler.due_to_close(now));
+        let announcement = recorder
+            .notifications()
+            .into_iter()
+            .find(|n| matches!(n.recipient, Recipient::Channel(_)))
+            .unwrap();
+        assert!(announcement.content.contains("**Sun 2024-07-14**"));
+        assert!(announcement.content.contains("<@&300>"));
+        assert_eq!(announcement.roles, [RoleId::new(300)]);
+    }
+
+    #[tokio::test]
+    async fn finalized_date_is_announced_from_template() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = answered();
+        scheduler.announcement = Some("🎲 {title} is ON for {date}! {attendees}".to_owned());
+        scheduler.finalized = Some(scheduler.get_dates()[0]);
+
+        scheduler.announce_final(&ctx).await;
+
+        let notifications = recorder.notifications();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(
+            notifications[0].content,
+            "🎲 Game night is ON for Sat 2024-07-13! <@1>"
+        );
+        assert_eq!(notifications[0].mentions, [UserId::new(1)]);
+    }
+
+    #[test]
+    fn calendar_export_has_best_dates_until_finalized() {
+        let mut scheduler = answered();
+        let ics = scheduler.ics().unwrap();
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 3);
+        assert!(ics.contains("SUMMARY:Game night\r\n"));
+        // The date everyone can make comes first
+        let first = ics.find("DTSTART;VALUE=DATE:20240714").unwrap();
+        assert!(first < ics.find("DTSTART;VALUE=DATE:20240713").unwrap());
+
+        scheduler.finalized = Some(scheduler.get_dates()[1]);
+        let ics = scheduler.ics().unwrap();
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ics.contains("DTEND;VALUE=DATE:20240715\r\n"));
+
+        assert!(weekends().ics().is_none());
+    }
+
+    #[tokio::test]
+    async fn calendar_export_waits_for_the_embargo() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = Scheduler::new(
+            OWNER,
+            None,
+            harness::message(MESSAGE.get()),
+            Options {
+                embargo: Some(Embargo::Count(2)),
+                ..options()
+            },
+        );
+        let dates = scheduler.get_dates();
+        let (player, other) = (UserId::new(1), UserId::new(2));
+        scheduler
+            .add_response(&ctx, player, response(&[dates[0]], &[]))
+            .await;
+        assert!(scheduler.hides_results_from(&ctx, player).await);
+        assert!(!scheduler.hides_results_from(&ctx, OWNER).await);
+
+        scheduler
+            .add_response(&ctx, other, response(&[dates[0]], &[]))
+            .await;
+        assert!(!scheduler.hides_results_from(&ctx, player).await);
+    }
+
+    #[tokio::test]
+    async fn alert_is_sent_once_a_date_is_popular() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler.add_alert(&ctx, 2).await;
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[0]], &[]))
+            .await;
+        assert!(recorder.notifications().is_empty());
+
+        scheduler
+            .add_response(&ctx, UserId::new(2), response(&[], &[d[0]]))
+            .await;
+        scheduler
+            .add_response(&ctx, UserId::new(3), response(&[d[0]], &[]))
+            .await;
+
+        let notifications = recorder.notifications();
+        assert_eq!(notifications.len(), 1);
+        assert!(matches!(notifications[0].recipient, Recipient::User(OWNER)));
+        assert!(notifications[0].content.contains("2 available"));
+        assert_eq!(
+            notifications[0].buttons[0].custom_id,
+            format!("alert_finalize {}", MESSAGE)
+        );
+    }
+
+    #[tokio::test]
+    async fn response_matrix_marks_blackouts() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        let d = scheduler.get_dates();
+        scheduler.blackout_dates.insert(d[3]);
+
+        let csv = scheduler.availability_matrix(&ctx).await;
+
+        assert_eq!(
+            csv,
+            "user,2024-07-13,2024-07-14,2024-07-20,2024-07-21\n\
+             1,1,1,0,x\n\
+             2,0,1,0,x\n\
+             3,0,1,maybe,x\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn reminder_goes_out_once() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = answered();
+        scheduler.respondents = HashSet::from([UserId::new(1), UserId::new(4)]);
+        let now = Utc::now();
+        assert!(!scheduler.due_to_remind(now));
+        scheduler.remind_after = Some(1);
+        assert!(scheduler.due_to_remind(now));
+
+        scheduler.auto_remind(&ctx).await;
+
+        assert!(!scheduler.due_to_remind(now));
+        let notifications = recorder.notifications();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].mentions, [UserId::new(4)]);
+    }
+
+    #[tokio::test]
+    async fn tentative_picks_are_asked_about_once() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = answered();
+        let d = scheduler.get_dates();
+        scheduler.firm_up = Some(2);
+        scheduler
+            .responses
+            .get_mut(&Responder::from(UserId::new(1)))
+            .unwrap()
+            .tentative
+            .insert(d[0]);
+        let before = Utc.from_utc_datetime(&d[0].date.and_hms_opt(0, 0, 0).unwrap());
+        assert!(!scheduler.due_to_firm_up(before - Duration::days(3)));
+        assert!(scheduler.due_to_firm_up(before - Duration::days(1)));
+
+        scheduler.ask_firm_up(&ctx, before).await;
+
+        assert!(!scheduler.due_to_firm_up(before));
+        let notifications = recorder.notifications();
+        assert_eq!(notifications.len(), 1);
+        assert!(matches!(
+            notifications[0].recipient,
+            Recipient::User(user) if user == UserId::new(1)
+        ));
+        assert!(notifications[0].content.contains("Sat 2024-07-13"));
+    }
+
+    #[tokio::test]
+    async fn concurrent_sessions_merge() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        let user = UserId::new(1);
+        let base = response(&[d[0]], &[]);
+        scheduler.add_response(&ctx, user, base.clone()).await;
+
+        // Another session adds one date and takes the first off
+        let other = response(&[d[1]], &[]);
+        assert_eq!(scheduler.merge_response(&ctx, user, &base, &other).await, 0);
+        // This one only marks the third date if needed and the first a no, which it already is
+        let edited = response(&[], &[d[2]]);
+        assert_eq!(
+            scheduler.merge_response(&ctx, user, &base, &edited).await,
+            0
+        );
+        let merged = scheduler.get_user_response(&user).unwrap();
+        assert_eq!(merged.dates, HashSet::from([d[1]]));
+        assert_eq!(merged.maybe, HashSet::from([d[2]]));
+
+        // Both changing the same date differently is a conflict, which the later one wins
+        let base = merged;
+        let other = response(&[d[1], d[3]], &[d[2]]);
+        scheduler.merge_response(&ctx, user, &base, &other).await;
+        let edited = response(&[d[1]], &[d[2], d[3]]);
+        assert_eq!(
+            scheduler.merge_response(&ctx, user, &base, &edited).await,
+            1
+        );
+        let merged = scheduler.get_user_response(&user).unwrap();
+        assert!(merged.maybe.contains(&d[3]) && !merged.dates.contains(&d[3]));
+    }
+
+    #[tokio::test]
+    async fn owner_availability_blacks_out_the_rest() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler
+            .add_response(&ctx, OWNER, response(&[d[0]], &[d[3]]))
+            .await;
+
+        scheduler.black_out_unavailable(&ctx, OWNER).await;
+
+        assert_eq!(scheduler.get_blackout_dates(), HashSet::from([d[1], d[2]]));
+    }
+
+    #[tokio::test]
+    async fn group_is_only_mentioned_when_posted() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = weekends();
+        scheduler.group = Some(RoleId::new(300));
+        scheduler.set_pending(true);
+        assert_eq!(scheduler.render(&ctx).await.content, "<@&300>");
+        scheduler.set_pending(false);
+        assert_eq!(scheduler.render(&ctx).await.content, "");
+        scheduler.edit_pings = true;
+        assert_eq!(scheduler.render(&ctx).await.content, "<@&300>");
+    }
+
+    #[test]
+    fn many_dates_are_paged_by_week() {
+        let start: NaiveDate = "2024-07-13".parse().unwrap();
+        // Three slots a day on weekends for six weeks
+        let dates: Vec<Slot> = (0..6)
+            .flat_map(|week| [0, 1].map(|day| start + Duration::weeks(week) + Duration::days(day)))
+            .flat_map(|date| {
+                Slot::on(
+                    date,
+                    &[18, 19, 20].map(|h| NaiveTime::from_hms_opt(h, 0, 0).unwrap()),
+                )
+            })
+            .collect();
+        assert_eq!(pages(&dates[..BUTTON_SLOTS]).len(), 1);
+        assert_eq!(pages(&dates[..BUTTON_SLOTS])[0], 0..BUTTON_SLOTS);
+        assert_eq!(pages(&dates), [0..12, 12..24, 24..36]);
+    }
+
+    #[test]
+    fn listed_dates_replace_the_weekly_window() {
+        let mut options = options();
+        options.dates = crate::slot::parse_dates("2024-12-31, 2024-12-24,2024-12-24").unwrap();
+        let today: NaiveDate = "2024-12-01".parse().unwrap();
+        let dates = slots(&options, today);
+        assert_eq!(
+            dates,
+            ["2024-12-24", "2024-12-31"].map(|d| Slot::day(d.parse().unwrap()))
+        );
+        let scheduler = Scheduler::new(OWNER, None, harness::message(MESSAGE.get()), options);
+        assert_eq!(scheduler.resized_dates(4), None);
+        assert!(!scheduler.can_extend());
+    }
+
+    #[tokio::test]
+    async fn reactions_count_as_responses() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = weekends();
+        scheduler.group = Some(RoleId::new(300));
+        let d = scheduler.get_dates();
+        scheduler.date_messages =
+            HashMap::from([(MessageId::new(10), d[0]), (MessageId::new(11), d[1])]);
+        scheduler
+            .react(
+                &ctx,
+                MessageId::new(10),
+                UserId::new(1),
+                &[RoleId::new(300)],
+            )
+            .await;
+        scheduler
+            .react(
+                &ctx,
+                MessageId::new(11),
+                UserId::new(1),
+                &[RoleId::new(300)],
+            )
+            .await;
+        // Not in the group, and not a date message
+        scheduler
+            .react(&ctx, MessageId::new(10), UserId::new(2), &[])
+            .await;
+        scheduler
+            .react(
+                &ctx,
+                MessageId::new(12),
+                UserId::new(3),
+                &[RoleId::new(300)],
+            )
+            .await;
+        scheduler
+            .unreact(&ctx, MessageId::new(10), UserId::new(1))
+            .await;
+        assert_eq!(scheduler.responses.len(), 1);
+        assert_eq!(
+            scheduler.responses[&Responder::from(UserId::new(1))].dates,
+            HashSet::from([d[1]])
+        );
+    }
+
+    #[test]
+    fn weeknights_can_be_offered() {
+        let mut options = options();
+        options.days = crate::slot::parse_days("mon+Wed+ friday").unwrap();
+        let today: NaiveDate = "2024-07-10".parse().unwrap();
+        let dates: Vec<String> = slots(&options, today)
+            .iter()
+            .map(|slot| slot.to_string())
+            .collect();
+        assert_eq!(
+            dates,
+            [
+                "2024-07-15",
+                "2024-07-17",
+                "2024-07-19",
+                "2024-07-22",
+                "2024-07-24",
+                "2024-07-26"
+            ]
+        );
+        assert_eq!(crate::slot::parse_days("Mon+Someday"), None);
+        assert_eq!(crate::slot::parse_days(""), None);
+    }
+
+    #[tokio::test]
+    async fn long_results_continue_across_fields() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = scheduler();
+        let start: NaiveDate = "2024-07-13".parse().unwrap();
+        scheduler.dates = (0..MAX_SLOTS as i64)
+            .flat_map(|day| {
+                Slot::on(
+                    start + Duration::days(day),
+                    &[NaiveTime::from_hms_opt(18, 0, 0).unwrap()],
+                )
+            })
+            .collect();
+        let rendered = scheduler.render(&ctx).await;
+        let results: Vec<_> = rendered
+            .fields
+            .iter()
+            .skip_while(|f| f.name != "Results")
+            .take_while(|f| f.name == "Results" || f.name == "\u{200b}")
+            .collect();
+        assert!(results.len() > 1);
+        assert!(results
+            .iter()
+            .all(|f| f.value.len() <= limits::EMBED_FIELD_VALUE_LENGTH));
+        let lines = results
+            .iter()
+            .flat_map(|f| f.value.lines())
+            .filter(|line| !line.starts_with("**Week of"))
+            .count();
+        assert_eq!(lines, MAX_SLOTS);
+        assert!(results[0].value.starts_with("**Week of Mon Jul 08**\n"));
+    }
+
+    #[tokio::test]
+    async fn long_results_fit_within_the_embed_limit() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = scheduler();
+        scheduler.body = Some("x".repeat(limits::EMBED_DESCRIPTION_LENGTH));
+        let start: NaiveDate = "2024-07-13".parse().unwrap();
+        scheduler.dates = (0..MAX_SLOTS as i64)
+            .flat_map(|day| {
+                Slot::on(
+                    start + Duration::days(day),
+                    &[NaiveTime::from_hms_opt(18, 0, 0).unwrap()],
+                )
+            })
+            .collect();
+        let rendered = scheduler.render(&ctx).await;
+        assert!(rendered.embed_length() <= limits::EMBED_TOTAL_LENGTH);
+        let results = rendered
+            .fields
+            .iter()
+            .find(|f| f.name == "Results")
+            .unwrap();
+        assert!(rendered
+            .fields
+            .iter()
+            .any(|f| f.value.ends_with("*More dates under Show details*")));
+        assert!(results.value.starts_with("**Week of"));
+    }
+
+    #[tokio::test]
+    async fn venue_is_linked_where_players_look() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = answered();
+        scheduler.venue = Some(Venue {
+            channel: ChannelId::new(400),
+            stage: false,
+        });
+        scheduler.respondents = HashSet::from([UserId::new(4)]);
+
+        assert!(scheduler.render(&ctx).await.description.contains("<#400>"));
+        scheduler.auto_remind(&ctx).await;
+        assert!(recorder.notifications()[0].content.contains("<#400>"));
+    }
+
+    #[tokio::test]
+    async fn anonymous_schedulers_only_show_counts() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        scheduler.anonymous = true;
+
+        let rendered = scheduler.render(&ctx).await;
+
+        let responded = rendered.fields.iter().find(|f| f.name == "Responded");
+        assert_eq!(responded.unwrap().value, "**3**");
+        assert!(rendered.fields.iter().all(|f| !f.value.contains("<@")));
+    }
+
+    #[tokio::test]
+    async fn copies_are_kept_in_sync() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = answered();
+        scheduler.group = Some(RoleId::new(300));
+        scheduler.edit_pings = true;
+        scheduler.add_mirror(&ctx, harness::message(201)).await;
+        let d = scheduler.get_dates();
+        scheduler
+            .add_response(&ctx, UserId::new(4), response(&[d[0]], &[]))
+            .await;
+
+        let original = recorder.last_edit(MESSAGE).unwrap();
+        let copy = recorder.last_edit(MessageId::new(201)).unwrap();
+        assert_eq!(original["embeds"], copy["embeds"]);
+        assert_eq!(copy["content"], "");
+        assert_eq!(scheduler.mirror_ids(), [MessageId::new(201)]);
+
+        scheduler.remove_mirror(MessageId::new(201));
+        assert!(scheduler.mirror_ids().is_empty());
+    }
+
+    #[tokio::test]
+    async fn respondents_can_see_their_own_response() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        let d = scheduler.get_dates();
+        assert_eq!(
+            scheduler.my_response(UserId::new(3), Locale::En),
+            "**Your response**\n`Sun 2024-07-14`\n`Sat 2024-07-20` if needed"
+        );
+        assert_eq!(
+            scheduler.my_response(UserId::new(4), Locale::En),
+            "You haven't responded yet"
+        );
+
+        scheduler
+            .add_response(&ctx, UserId::new(4), response(&[d[3]], &[]))
+            .await;
+
+        assert!(scheduler
+            .my_response(UserId::new(4), Locale::En)
+            .starts_with("**Your response**, updated <t:"));
+    }
+
+    #[tokio::test]
+    async fn recent_activity_is_shown_when_laid_out() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = weekends();
+        scheduler.layout = vec![Section::Activity];
+        let d = scheduler.get_dates();
+        assert!(scheduler.render(&ctx).await.fields.is_empty());
+
+        for user in 1..=4 {
+            scheduler
+                .add_response(&ctx, UserId::new(user), response(&[d[0]], &[]))
+                .await;
+        }
+        scheduler
+            .set_blackout(&ctx, HashSet::from([d[2]]).into())
+            .await;
+
+        let activity = |scheduler: &Scheduler| scheduler.recent_activity().unwrap();
+        assert_eq!(
+            activity(&scheduler),
+            "Owner blacked out Sat Jul 20\n<@4> responded\n<@3> responded"
+        );
+        scheduler.anonymous = true;
+        assert!(activity(&scheduler).ends_with("Someone responded\nSomeone responded"));
+    }
+
+    #[tokio::test]
+    async fn blackouts_hide_or_prune_answers() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        let d = scheduler.get_dates();
+        scheduler
+            .set_blackout(&ctx, HashSet::from([d[1]]).into())
+            .await;
+        assert!(!scheduler
+            .my_response(UserId::new(2), Locale::En)
+            .contains("2024-07-14"));
+        // Hidden answers come back with the date
+        scheduler.set_blackout(&ctx, HashSet::new().into()).await;
+        assert!(scheduler
+            .my_response(UserId::new(2), Locale::En)
+            .contains("2024-07-14"));
+
+        scheduler.blackout_policy = BlackoutPolicy::Prune;
+        scheduler
+            .set_blackout(&ctx, HashSet::from([d[1]]).into())
+            .await;
+        scheduler.set_blackout(&ctx, HashSet::new().into()).await;
+        assert_eq!(scheduler.count(&d[1]), (0, 0));
+        assert_eq!(scheduler.count(&d[0]), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn respondents_can_be_listed_newest_first() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        let d = scheduler.get_dates();
+        scheduler
+            .add_response(&ctx, UserId::new(2), response(&[d[0]], &[]))
+            .await;
+        assert_eq!(
+            scheduler.respondents_listed(),
+            [UserId::new(1), UserId::new(2), UserId::new(3)].map(Responder::from)
+        );
+
+        scheduler.by_recency = true;
+
+        // Responses from before timestamps were kept come last
+        assert_eq!(
+            scheduler.respondents_listed(),
+            [UserId::new(2), UserId::new(1), UserId::new(3)].map(Responder::from)
+        );
+        assert!(scheduler
+            .named_details(&HashMap::new(), &HashMap::new(), Locale::En)
+            .iter()
+            .any(|l| l.starts_with("<@2> <t:")));
+    }
+
+    #[test]
+    fn deadline_closes_once_passed() {
+        let mut scheduler = weekends();
+        let now = Utc::now();
+        scheduler.deadline = Some(now + Duration::hours(1));
+        assert!(!scheduler.due_to_close(now));
+        assert!(scheduler.due_to_close(now + Duration::hours(2)));
+    }
+
+    #[test]
+    fn quick_actions_answer_many_slots() {
+        let scheduler = weekends();
+        let d = scheduler.get_dates();
+        let actions = scheduler.quick_actions();
+        let labels: Vec<&str> = actions.iter().map(QuickAction::label).collect();
+        assert_eq!(labels, ["All Sat", "All Sun", "Copy week 1 to all"]);
+
+        let mut answer = response(&[d[1]], &[d[0]]);
+        actions[2].apply(&mut answer, &d, &HashSet::new());
+        assert_eq!(answer.dates, HashSet::from([d[1], d[3]]));
+        assert_eq!(answer.maybe, HashSet::from([d[0], d[2]]));
+
+        // Blocked slots keep their answer
+        actions[0].apply(&mut answer, &d, &HashSet::from([d[2]]));
+        assert!(answer.dates.contains(&d[0]));
+        assert!(answer.maybe.contains(&d[2]));
+    }
+
+    #[tokio::test]
+    async fn render_empty() {
+        let ctx = harness::context(&Recorder::default());
+        insta::assert_json_snapshot!("empty", weekends().render(&ctx).await);
+    }
+
+    #[tokio::test]
+    async fn render_quorum_met() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        scheduler.quorum = Some(2);
+        insta::assert_json_snapshot!("quorum_met", scheduler.render(&ctx).await);
+    }
+
+    #[tokio::test]
+    async fn render_closed() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        scheduler.closed = true;
+        insta::assert_json_snapshot!("closed", scheduler.render(&ctx).await);
+    }
+
+    #[tokio::test]
+    async fn render_finalized() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        scheduler.closed = true;
+        scheduler.finalized = Some(scheduler.get_dates()[1]);
+        insta::assert_json_snapshot!("finalized", scheduler.render(&ctx).await);
+    }
+
+    #[tokio::test]
+    async fn render_oversized() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = weekends();
+        scheduler.title = "x".repeat(300);
+        let first = scheduler.get_dates()[0];
+        for id in 1..=50 {
+            scheduler
+                .responses
+                .insert(UserId::new(id).into(), response(&[first], &[]));
+        }
+        insta::assert_json_snapshot!("oversized", scheduler.render(&ctx).await);
+    }
+
+    #[tokio::test]
+    async fn rebound_scheduler_edits_its_new_message() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = answered();
+        scheduler.rebind(harness::message(201));
+        let d = scheduler.get_dates();
+        scheduler
+            .add_response(&ctx, UserId::new(4), response(&[d[0]], &[]))
+            .await;
+
+        assert!(recorder.last_edit(MESSAGE).is_none());
+        assert!(recorder.last_edit(MessageId::new(201)).is_some());
+        assert_eq!(scheduler.responses.len(), 4);
+    }
+
+    #[test]
+    fn listing_links_to_the_scheduler() {
+        let mut scheduler = answered();
+        scheduler.guild = Some(GuildId::new(1));
+        assert_eq!(
+            scheduler.listing(),
+            format!(
+                "[{}](https://discord.com/channels/1/{}/200) by <@100>: 3 responded",
+                scheduler.title,
+                scheduler.channel_id()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn activity_is_posted_to_the_thread() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[0]], &[]))
+            .await;
+        scheduler.thread = Some(ChannelId::new(400));
+        scheduler
+            .add_response(&ctx, UserId::new(2), response(&[d[0]], &[]))
+            .await;
+        scheduler.close(&ctx).await;
+        let posted: Vec<String> = recorder
+            .notifications()
+            .into_iter()
+            .filter(|n| matches!(n.recipient, Recipient::Channel(c) if c == ChannelId::new(400)))
+            .map(|n| n.content)
+            .collect();
+        assert_eq!(
+            posted,
+            [
+                "<@2> responded".to_owned(),
+                format!("**{}** has closed", scheduler.title)
+            ]
+        );
+    }
+
+    #[test]
+    fn recurring_schedulers_roll_over_once_their_dates_pass() {
+        let mut scheduler = weekends();
+        scheduler.group = Some(RoleId::new(300));
+        scheduler.blackout_policy = BlackoutPolicy::Prune;
+        let before = NaiveDate::from_ymd_opt(2024, 7, 21).unwrap();
+        let after = NaiveDate::from_ymd_opt(2024, 7, 22).unwrap();
+        assert!(!scheduler.due_to_roll_over(after));
+        scheduler.recurring = true;
+        assert!(!scheduler.due_to_roll_over(before));
+        assert!(scheduler.due_to_roll_over(after));
+
+        let next = scheduler.roll_over(harness::message(201));
+        assert!(!scheduler.due_to_roll_over(after));
+        assert_eq!(scheduler.next, Some(MessageId::new(201)));
+        assert_eq!(next.previous, Some(MESSAGE));
+        assert!(next.recurring);
+        assert_eq!(next.title, scheduler.title);
+        assert_eq!(next.group, scheduler.group);
+        assert!(next.blackout_policy == BlackoutPolicy::Prune);
+        assert_eq!(next.dates.len(), 4);
+        assert!(next
+            .dates
+            .iter()
+            .all(|d| matches!(d.date.weekday(), Weekday::Sat | Weekday::Sun)));
+    }
+
+    #[tokio::test]
+    async fn past_dates_stop_being_offered_but_keep_answers() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        let d = scheduler.get_dates();
+        let now = Utc.with_ymd_and_hms(2024, 7, 14, 12, 0, 0).unwrap();
+        assert!(scheduler.due_to_expire(now));
+        scheduler.expire_past(&ctx, now).await;
+        assert!(!scheduler.due_to_expire(now));
+        assert_eq!(scheduler.offered_dates(), &d[1..]);
+        assert!(scheduler.tally().iter().all(|(slot, _, _)| *slot != d[0]));
+
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[2]], &[]))
+            .await;
+        let kept = &scheduler.responses[&Responder::from(UserId::new(1))];
+        assert!(kept.dates.contains(&d[0]));
+        assert!(!kept.dates.contains(&d[1]));
+    }
+
+    #[tokio::test]
+    async fn dates_required_players_cant_make_are_never_best() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        let d = scheduler.get_dates();
+        assert_eq!(scheduler.best_dates(), [d[1]]);
+
+        scheduler.toggle_required(&ctx, UserId::new(2)).await;
+        for user in 4..=6 {
+            scheduler
+                .add_response(&ctx, UserId::new(user), response(&[d[0]], &[]))
+                .await;
+        }
+        // Four can make the first date, but not the required player
+        assert_eq!(scheduler.best_dates(), [d[1]]);
+        let lines = render::results(&scheduler.results(), false, &scheduler.date_style());
+        assert!(lines[0].starts_with("~~"));
+        assert!(lines[1].starts_with("__"));
+
+        scheduler.toggle_required(&ctx, UserId::new(2)).await;
+        assert_eq!(scheduler.best_dates(), [d[0]]);
+    }
+
+    #[tokio::test]
+    async fn final_results_only_list_dates_meeting_the_threshold() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        let changes = Changes {
+            threshold: Some(2),
+            ..Default::default()
+        };
+        scheduler.edit(&ctx, changes).await;
+        let lines = render::results(&scheduler.results(), false, &scheduler.date_style());
+        assert!(lines[0].starts_with("-# "));
+        assert!(lines[1].starts_with("✅ "));
+
+        scheduler.closed = true;
+        let rendered = scheduler.render(&ctx).await;
+        let results = &rendered
+            .fields
+            .iter()
+            .find(|f| f.name == "Results")
+            .unwrap()
+            .value;
+        assert_eq!(results.lines().count(), 1);
+        assert!(results.starts_with("✅ "));
+    }
+
+    #[tokio::test]
+    async fn events_are_sent_to_the_guild_webhook() {
+        let recorder = Recorder::default();
+        let config = crate::guild_config::GuildConfig {
+            webhook: Some("https://example.com/hook".to_owned()),
+            ..Default::default()
+        };
+        let ctx = harness::guild_context(&recorder, HashMap::from([(GuildId::new(1), config)]));
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[0]], &[d[1]]))
+            .await;
+        assert!(recorder.webhooks().is_empty());
+
+        scheduler.guild = Some(GuildId::new(1));
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[0]], &[d[1]]))
+            .await;
+        scheduler.close(&ctx).await;
+        let sent = recorder.webhooks();
+        assert_eq!(sent[0]["event"], "response");
+        assert_eq!(sent[0]["dates"], serde_json::json!(["2024-07-13"]));
+        assert_eq!(sent[0]["maybe"], serde_json::json!(["2024-07-14"]));
+        assert_eq!(sent[1]["event"], "closed");
+        assert_eq!(sent[1]["best"], serde_json::json!(["2024-07-13"]));
+    }
+
+    #[tokio::test]
+    async fn bursts_of_responses_edit_the_message_once() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let debouncer = Debouncer::default();
+        ctx.data
+            .write()
+            .await
+            .insert::<Debouncer>(debouncer.clone());
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        for user in 1..=5 {
+            scheduler
+                .add_response(&ctx, UserId::new(user), response(&[d[0]], &[]))
+                .await;
+        }
+        assert_eq!(recorder.edit_count(MESSAGE), 0);
+
+        debouncer.flush().await;
+        assert_eq!(recorder.edit_count(MESSAGE), 1);
+        let text = harness::text(&recorder.last_edit(MESSAGE).unwrap());
+        assert!(text.contains("<@5>"));
+    }
+
+    #[tokio::test]
+    async fn notes_show_next_to_the_responder() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        assert_eq!(
+            scheduler.set_note(UserId::new(1), "late"),
+            Text::RespondBeforeNote
+        );
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[0]], &[]))
+            .await;
+
+        scheduler.set_note(UserId::new(1), "  Can only stay until 10pm ");
+        let details = scheduler.named_details(&HashMap::new(), &HashMap::new(), Locale::En);
+        assert!(details.contains(&"<@1>: Can only stay until 10pm".to_owned()));
+
+        scheduler.set_note(UserId::new(1), "");
+        assert!(!scheduler
+            .named_details(&HashMap::new(), &HashMap::new(), Locale::En)
+            .contains(&"**Notes**".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn blackout_dates_show_struck_through_with_their_reason() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler
+            .set_blackout(&ctx, HashSet::from([d[0], d[1]]).into())
+            .await;
+        assert_eq!(scheduler.blackouts_to_label(), [d[0], d[1]]);
+
+        scheduler
+            .set_blackout_reasons(
+                &ctx,
+                vec![
+                    (d[0], "DM on vacation".to_owned()),
+                    (d[2], "nope".to_owned()),
+                ],
+            )
+            .await;
+        assert_eq!(scheduler.blackouts_to_label(), [d[1], d[0]]);
+        let rendered = scheduler.render(&ctx).await;
+        let field = rendered
+            .fields
+            .iter()
+            .find(|f| f.name == "Unavailable dates")
+            .unwrap();
+        assert!(field.value.contains("DM on vacation"));
+        let changes = Changes {
+            show_blackouts: Some(false),
+            ..Default::default()
+        };
+        scheduler.edit(&ctx, changes).await;
+        let rendered = scheduler.render(&ctx).await;
+        assert!(rendered
+            .fields
+            .iter()
+            .all(|f| f.name != "Unavailable dates"));
+        let lines = scheduler.blackout_lines();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("~~") && lines[0].ends_with("~~ DM on vacation"));
+        assert!(!scheduler.blackout_reasons.contains_key(&d[2]));
+
+        scheduler
+            .set_blackout(&ctx, HashSet::from([d[1]]).into())
+            .await;
+        assert!(scheduler.blackout_reasons.is_empty());
+    }
+
+    #[test]
+    fn added_owners_can_manage_the_scheduler() {
+        let mut scheduler = weekends();
+        let owner = scheduler.owner();
+        assert!(scheduler.is_owner(owner));
+        assert!(!scheduler.is_owner(UserId::new(7)));
+
+        scheduler.add_owner(UserId::new(7));
+        assert!(scheduler.is_owner(UserId::new(7)));
+        assert!(scheduler.add_owner(owner).contains("already"));
+        let next = scheduler.roll_over(harness::message(300));
+        assert!(next.is_owner(UserId::new(7)));
+    }
+
+    #[tokio::test]
+    async fn proxied_responses_are_marked_until_the_user_answers() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        let (user, owner) = (UserId::new(1), scheduler.owner());
+        let edited = response(&[d[0]], &[]);
+        scheduler
+            .merge_proxied_response(&ctx, user.into(), owner, &Response::default(), &edited)
+            .await;
+        let marker = format!("(entered by <@{}>)", owner);
+        let details = scheduler.named_details(&HashMap::new(), &HashMap::new(), Locale::En);
+        assert!(details
+            .iter()
+            .any(|line| line.starts_with("<@1>") && line.ends_with(&marker)));
+
+        let base = scheduler.get_user_response(&user).unwrap();
+        let edited = response(&[d[0], d[1]], &[]);
+        scheduler.merge_response(&ctx, user, &base, &edited).await;
+        let details = scheduler.named_details(&HashMap::new(), &HashMap::new(), Locale::En);
+        assert!(!details.iter().any(|line| line.ends_with(&marker)));
+    }
+
+    #[tokio::test]
+    async fn guest_responses_count_and_show_by_name() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        let d = scheduler.get_dates();
+        let guest = Responder::Guest("Dave's friend".to_owned());
+        let owner = scheduler.owner();
+        let edited = response(&[d[0]], &[]);
+        scheduler
+            .merge_proxied_response(&ctx, guest.clone(), owner, &Response::default(), &edited)
+            .await;
+
+        assert_eq!(scheduler.count(&d[0]), (2, 0));
+        assert!(scheduler.response_of(&guest).unwrap().dates.contains(&d[0]));
+        let details = scheduler.named_details(&HashMap::new(), &HashMap::new(), Locale::En);
+        assert!(details
+            .iter()
+            .any(|line| line.starts_with("*Dave's friend* <t:")));
+        let saved = serde_json::to_string(&scheduler).unwrap();
+        let loaded: Scheduler = serde_json::from_str(&saved).unwrap();
+        assert_eq!(
+            loaded.response_of(&guest).unwrap().dates,
+            HashSet::from([d[0]])
+        );
+    }
+
+    #[tokio::test]
+    async fn owners_break_ties_and_pick_the_final_date() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler
+            .responses
+            .insert(UserId::new(1).into(), response(&[d[0], d[1], d[2]], &[]));
+        scheduler
+            .responses
+            .insert(UserId::new(2).into(), response(&[d[1]], &[d[0]]));
+        let dates = |ties: Vec<(Slot, usize, usize)>| -> Vec<Slot> {
+            ties.into_iter().map(|(date, _, _)| date).collect()
+        };
+        assert_eq!(
+            dates(scheduler.ranked_ties(TieBreak::Earliest)),
+            [d[0], d[1]]
+        );
+        assert_eq!(dates(scheduler.ranked_ties(TieBreak::Maybe)), [d[1], d[0]]);
+
+        scheduler.finalize(&ctx, d[0]).await;
+
+        assert!(scheduler.closed);
+        assert_eq!(scheduler.finalized(), Some(d[0]));
+        assert_eq!(
+            scheduler.finalize(&ctx, d[1]).await,
+            Text::SchedulerAlreadyClosed.into()
+        );
+    }
+
+    #[tokio::test]
+    async fn ranked_schedulers_score_dates_by_borda_count() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        let changes = Changes {
+            ranked: Some(true),
+            ..Default::default()
+        };
+        scheduler.edit(&ctx, changes).await;
+        for (id, ranking) in [
+            (1, [d[0], d[1], d[2]]),
+            (2, [d[2], d[1], d[0]]),
+            (3, [d[1], d[0], d[2]]),
+        ] {
+            let mut response = response(&ranking, &[]);
+            response.ranking = ranking.to_vec();
+            scheduler.responses.insert(UserId::new(id).into(), response);
+        }
+
+        // Everyone can make all three, but the second date is nobody's last choice
+        assert_eq!(scheduler.best_dates(), [d[1]]);
+        let lines = render::results(&scheduler.results(), false, &scheduler.date_style());
+        assert!(lines[0].ends_with(" 6 pts"));
+        assert!(lines[1].starts_with("__") && lines[1].ends_with(" 7 pts"));
+    }
+
+    #[tokio::test]
+    async fn waitlisted_players_get_spots_that_open_up() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = answered();
+        let d = scheduler.get_dates();
+        scheduler.capacity = Some(3);
+        scheduler.finalize(&ctx, d[1]).await;
+        let waiting = recorder.notifications().len();
+
+        let reply = scheduler.toggle_waitlist(&ctx, UserId::new(9)).await;
+        assert_eq!(reply, Phrase::new(Text::OnWaitlist, &[&1]));
+        scheduler.toggle_waitlist(&ctx, UserId::new(8)).await;
+        scheduler.withdraw(&ctx, UserId::new(1)).await;
+
+        assert!(scheduler
+            .get_user_response(&UserId::new(9))
+            .unwrap()
+            .available(&d[1]));
+        assert!(scheduler.get_user_response(&UserId::new(8)).is_none());
+        assert_eq!(scheduler.waitlists[&d[1]], [UserId::new(8)]);
+        let notifications = recorder.notifications();
+        assert_eq!(notifications.len(), waiting + 1);
+        assert!(matches!(
+            notifications[waiting].recipient,
+            Recipient::User(user) if user == UserId::new(9)
+        ));
+    }
+
+    #[tokio::test]
+    async fn owners_can_be_dmed_about_each_response() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler.notify_owners.insert(OWNER);
+
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[0]], &[d[1]]))
+            .await;
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[1]], &[]))
+            .await;
+        scheduler
+            .add_response(&ctx, OWNER, response(&[d[1]], &[]))
+            .await;
+
+        let notifications = recorder.notifications();
+        assert_eq!(notifications.len(), 2);
+        assert!(notifications
+            .iter()
+            .all(|n| matches!(n.recipient, Recipient::User(OWNER))));
+        assert!(notifications[0]
+            .content
+            .starts_with("<@1> responded to **Game night**: "));
+        assert!(notifications[0].content.contains("(maybe)"));
+        assert!(notifications[1]
+            .content
+            .starts_with("<@1> changed their response to **Game night**: "));
+    }
+
+    #[tokio::test]
+    async fn changed_responses_are_described_by_what_changed() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler.notify_owners.insert(OWNER);
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[0], d[1]], &[]))
+            .await;
+        scheduler
+            .add_response(&ctx, UserId::new(2), response(&[d[0]], &[]))
+            .await;
+
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[1]], &[d[2]]))
+            .await;
+
+        assert!(scheduler
+            .recent_activity()
+            .unwrap()
+            .starts_with("<@1> removed Sat Jul 13, added Sat Jul 20\n"));
+        let notifications = recorder.notifications();
+        assert!(notifications
+            .last()
+            .unwrap()
+            .content
+            .contains("**Game night**: removed Sat Jul 13, added Sat Jul 20"));
+    }
+
+    #[tokio::test]
+    async fn buttons_and_own_responses_are_translated() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler.locale = Locale::De;
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[0]], &[d[1]]))
+            .await;
+
+        let rendered = scheduler.render(&ctx).await;
+        assert_eq!(
+            rendered.buttons.iter().map(|b| b.label).collect::<Vec<_>>(),
+            [
+                "Antworten",
+                "Meine Antwort",
+                "Details anzeigen",
+                "Schließen"
+            ]
+        );
+        assert_eq!(rendered.fields[0].name, "Geantwortet");
+        let details = scheduler.named_details(&HashMap::new(), &HashMap::new(), Locale::Fr);
+        assert!(details.contains(&"**Dernières modifications**".to_owned()));
+        let own = scheduler.my_response(UserId::new(1), Locale::Fr);
+        assert!(own.starts_with("**Votre réponse**"));
+        assert!(own.contains("`dim. 2024-07-14` si besoin"));
+        assert_eq!(
+            scheduler.my_response(UserId::new(2), Locale::Es),
+            "You haven't responded yet"
+        );
+    }
+
+    #[tokio::test]
+    async fn dates_follow_the_guild_date_format() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[0]], &[]))
+            .await;
+        assert!(DateFormat::parse("no pattern").is_none());
+
+        scheduler.locale = Locale::De;
+        scheduler.date_format = DateFormat::parse("%a %d.%m.");
+        assert_eq!(scheduler.slot_labels(None)[0], "Sa 13.07.");
+        let lines = render::results(&scheduler.results(), false, &scheduler.date_style());
+        assert_eq!(lines[0], "__`Sa 13.07.:`__ 1");
+        assert!(scheduler
+            .availability_matrix(&ctx)
+            .await
+            .starts_with("user,Sa 13.07.,"));
+
+        scheduler.date_format = DateFormat::parse("discord");
+        let lines = render::results(&scheduler.results(), false, &scheduler.date_style());
+        assert_eq!(lines[0], "__<t:1720872000:D>:__ 1");
+        assert_eq!(scheduler.slot_labels(None)[0], "Sa 13. Jul");
+    }
+
+    #[tokio::test]
+    async fn thresholds_can_be_set_when_created() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let options = Options {
+            threshold: Some(2),
+            ..options()
+        };
+        let mut scheduler = Scheduler::new(OWNER, None, harness::message(MESSAGE.get()), options);
+        let d = scheduler.get_dates();
+        for user in 1..=2 {
+            scheduler
+                .add_response(&ctx, UserId::new(user), response(&[d[0]], &[d[1]]))
+                .await;
+        }
+        scheduler
+            .add_response(&ctx, UserId::new(3), response(&[d[1]], &[]))
+            .await;
+
+        let met: Vec<_> = scheduler
+            .results()
+            .iter()
+            .map(|r| r.meets_threshold)
+            .take(3)
+            .collect();
+        assert_eq!(met, [Some(true), Some(true), Some(false)]);
+    }
+
+    #[test]
+    fn ranking_can_be_chosen_when_created() {
+        let options = Options {
+            ranked: true,
+            ..options()
+        };
+        let scheduler = Scheduler::new(OWNER, None, harness::message(MESSAGE.get()), options);
+        assert!(scheduler.ranked());
+    }
+
+    #[tokio::test]
+    async fn availability_lists_picked_dates_unless_anonymous() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[0]], &[d[2]]))
+            .await;
+
+        let line = scheduler
+            .availability_of(UserId::new(1), UserId::new(2))
+            .unwrap();
+        assert!(line.starts_with("[Game night]("));
+        assert!(line.ends_with("): Sat Jul 13, Sat Jul 20 (maybe)"));
+        assert!(scheduler
+            .availability_of(UserId::new(2), UserId::new(1))
+            .is_none());
+
+        scheduler.anonymous = true;
+        assert!(scheduler
+            .availability_of(UserId::new(1), UserId::new(2))
+            .is_none());
+        assert!(scheduler
+            .availability_of(UserId::new(1), UserId::new(1))
+            .is_some());
+        assert!(scheduler.availability_of(UserId::new(1), OWNER).is_some());
+    }
+
+    #[test]
+    fn intersections_keep_dates_both_schedulers_rank_highly() {
+        let day = |d: &str| Slot::day(d.parse().unwrap());
+        let at = |d: &str| Slot {
+            time: Some(NaiveTime::from_hms_opt(18, 0, 0).unwrap()),
+            ..day(d)
+        };
+        let a = [(day("2024-07-20"), 3, 0), (day("2024-07-13"), 2, 1)];
+        let b = [
+            (at("2024-07-13"), 4, 0),
+            (day("2024-07-14"), 2, 0),
+            (day("2024-07-20"), 1, 1),
+        ];
+
+        assert_eq!(
+            intersect(&a, &b),
+            [
+                (at("2024-07-13"), (2, 1), (4, 0)),
+                (day("2024-07-20"), (3, 0), (1, 1)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn details_show_conflicts_with_personal_calendars() {
+        let recorder = Recorder::default();
+        let ctx = harness::context(&recorder);
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler
+            .add_response(&ctx, UserId::new(1), response(&[d[0], d[1]], &[d[2]]))
+            .await;
+        scheduler
+            .add_response(&ctx, UserId::new(2), response(&[d[1]], &[]))
+            .await;
+        let calendar = |weekdays: &[Weekday], dates: &[&str]| Calendar {
+            weekdays: weekdays.iter().copied().collect(),
+            dates: dates.iter().map(|d| d.parse().unwrap()).collect(),
+        };
+        let calendars = HashMap::from([
+            (UserId::new(1), calendar(&[Weekday::Sun], &["2024-07-20"])),
+            (UserId::new(2), calendar(&[Weekday::Mon], &[])),
+        ]);
+
+        let details = scheduler.named_details(&HashMap::new(), &calendars, Locale::En);
+        let at = details
+            .iter()
+            .position(|line| line == "**Conflicts with personal calendars**")
+            .unwrap();
+        assert_eq!(details[at + 1], "<@1>: Sun Jul 14, Sat Jul 20");
+        assert!(!details.iter().any(|line| line.starts_with("<@2>:")));
+    }
+
+    #[tokio::test]
+    async fn details_flag_slots_at_night_for_some_respondents() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = scheduler();
+        scheduler.timezone = Some(chrono_tz::Europe::Berlin);
+        let at = |hour| Slot {
+            time: NaiveTime::from_hms_opt(hour, 0, 0),
+            ..Slot::day("2024-07-13".parse().unwrap())
+        };
+        scheduler.dates = vec![at(10), at(18)];
+        for user in [1, 2] {
+            scheduler
+                .add_response(&ctx, UserId::new(user), response(&[at(18)], &[]))
+                .await;
+        }
+        let timezones = HashMap::from([
+            (UserId::new(1), chrono_tz::Europe::Berlin),
+            (UserId::new(2), chrono_tz::America::Los_Angeles),
+        ]);
+
+        let details = scheduler.named_details(&timezones, &HashMap::new(), Locale::En);
+        let at = details
+            .iter()
+            .position(|line| line == "**Outside 08:00–23:00 for some**")
+            .unwrap();
+        assert_eq!(details[at + 1..], ["Sat Jul 13 10:00: <@2> at 01:00"]);
+    }
+
+    #[tokio::test]
+    async fn final_date_links_its_google_calendar_event() {
+        let ctx = harness::context(&Recorder::default());
+        let mut scheduler = answered();
+        let date = scheduler.get_dates()[1];
+        scheduler.closed = true;
+        scheduler.finalized = Some(date);
+        scheduler.calendar_event = Some(CalendarEvent {
+            id: "abc".to_owned(),
+            link: "https://www.google.com/calendar/event?eid=abc".to_owned(),
+            slot: date,
+        });
+
+        let rendered = scheduler.render(&ctx).await;
+        assert!(rendered
+            .description
+            .contains("[Google Calendar](https://www.google.com/calendar/event?eid=abc)"));
+    }
+
+    #[test]
+    fn heatmap_cells_follow_each_answer() {
+        let mut scheduler = weekends();
+        let d = scheduler.get_dates();
+        scheduler.blackout_dates.insert(d[3]);
+        let answer = response(&[d[0], d[3]], &[d[1]]);
+
+        assert_eq!(
+            scheduler.cells(&answer),
+            [
+                Cell::Available,
+                Cell::Maybe,
+                Cell::Unavailable,
+                Cell::BlackedOut
+            ]
+        );
     }
 }
@@ -0,0 +1,94 @@
+//! Stopping on SIGINT or SIGTERM without losing responses in flight
+
+use crate::locale::{Locale, Text};
+
+use serenity::client::Context;
+use serenity::http::Http;
+use serenity::model::id::InteractionId;
+use serenity::prelude::TypeMapKey;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::{error, info};
+
+/// Whether the bot is stopping, and the response pickers still open
+#[derive(Default)]
+pub struct Shutdown {
+    stopping: AtomicBool,
+    /// Interaction tokens of open pickers and the language they are in, to edit them when
+    /// stopping
+    pickers: Mutex<HashMap<InteractionId, (String, Locale)>>,
+}
+
+impl TypeMapKey for Shutdown {
+    type Value = Arc<Shutdown>;
+}
+
+/// Removes a picker from those tracked once it is dropped
+pub struct Picker {
+    shutdown: Arc<Shutdown>,
+    id: InteractionId,
+}
+
+impl Drop for Picker {
+    fn drop(&mut self) {
+        self.shutdown.pickers.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl Shutdown {
+    pub fn stopping(&self) -> bool {
+        self.stopping.load(Ordering::SeqCst)
+    }
+
+    /// Stop accepting interactions and tell open pickers to resubmit
+    pub async fn begin(&self, http: &Http) {
+        self.stopping.store(true, Ordering::SeqCst);
+        let pickers: Vec<(String, Locale)> =
+            self.pickers.lock().unwrap().values().cloned().collect();
+        info!("Closing {} open pickers", pickers.len());
+        for (token, locale) in pickers {
+            let edit = serde_json::json!({
+                "content": locale.text(Text::Restarting),
+                "components": [],
+            });
+            if let Err(e) = http
+                .edit_original_interaction_response(&token, &edit, vec![])
+                .await
+            {
+                error!("Cannot close picker: {}", e);
+            }
+        }
+    }
+}
+
+/// Track the picker opened in response to interaction `id` until the returned guard is dropped
+pub async fn track(
+    ctx: &Context,
+    id: InteractionId,
+    token: &str,
+    locale: Locale,
+) -> Option<Picker> {
+    let shutdown = ctx.data.read().await.get::<Shutdown>()?.clone();
+    shutdown
+        .pickers
+        .lock()
+        .unwrap()
+        .insert(id, (token.to_owned(), locale));
+    Some(Picker { shutdown, id })
+}
+
+/// Wait for SIGINT, or SIGTERM on Unix
+pub async fn signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let mut terminate = signal(SignalKind::terminate()).expect("Cannot listen for SIGTERM");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await.ok();
+}
@@ -0,0 +1,189 @@
+//! A date that can be answered for, optionally narrowed down to a time of day
+
+use chrono::{NaiveDate, NaiveTime, TimeZone, Weekday};
+use chrono_tz::Tz;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const TIME_FORMAT: &str = "%H:%M";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Slot {
+    pub date: NaiveDate,
+    /// `None` for the whole day
+    pub time: Option<NaiveTime>,
+}
+
+impl Slot {
+    pub fn day(date: NaiveDate) -> Self {
+        Slot { date, time: None }
+    }
+
+    /// The slots on `date`: one per time, or the whole day if there are no times
+    pub fn on(date: NaiveDate, times: &[NaiveTime]) -> Vec<Self> {
+        if times.is_empty() {
+            return vec![Slot::day(date)];
+        }
+        times
+            .iter()
+            .map(|time| Slot {
+                date,
+                time: Some(*time),
+            })
+            .collect()
+    }
+
+    /// Parse "2024-07-13" or "2024-07-13 18:00"
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let (date, time) = match s.split_once([' ', 'T']) {
+            Some((date, time)) => (date, Some(time)),
+            None => (s, None),
+        };
+        let date = NaiveDate::parse_from_str(date, DATE_FORMAT).ok()?;
+        let time = match time {
+            Some(time) => Some(NaiveTime::parse_from_str(time.trim(), TIME_FORMAT).ok()?),
+            None => None,
+        };
+        Some(Slot { date, time })
+    }
+
+    /// Whether this slot rules out `other`: the same slot, or a whole day containing it
+    pub fn covers(&self, other: &Slot) -> bool {
+        self == other || (self.time.is_none() && self.date == other.date)
+    }
+
+    /// When the slot starts as a Unix timestamp, if it has a time of day, taking it to be in `tz`
+    pub fn timestamp(&self, tz: Tz) -> Option<i64> {
+        let time = self.time?;
+        let start = tz
+            .from_local_datetime(&self.date.and_time(time))
+            .earliest()?;
+        Some(start.timestamp())
+    }
+
+    /// The same moment in `to` when the time is in `from`; whole days stay as they are
+    pub fn in_timezone(&self, from: Tz, to: Tz) -> Slot {
+        let time = match self.time {
+            Some(time) => time,
+            None => return *self,
+        };
+        match from
+            .from_local_datetime(&self.date.and_time(time))
+            .earliest()
+        {
+            Some(start) => {
+                let local = start.with_timezone(&to).naive_local();
+                Slot {
+                    date: local.date(),
+                    time: Some(local.time()),
+                }
+            }
+            None => *self,
+        }
+    }
+
+    /// The time of day in 24-hour format, if any
+    pub fn time_label(&self) -> Option<String> {
+        self.time.map(|time| time.format(TIME_FORMAT).to_string())
+    }
+}
+
+/// Parse times of day like "18:00+20:00", in order
+pub fn parse_times(s: &str) -> Option<Vec<NaiveTime>> {
+    let mut times: Vec<NaiveTime> = s
+        .split('+')
+        .map(|t| NaiveTime::parse_from_str(t.trim(), TIME_FORMAT).ok())
+        .collect::<Option<_>>()?;
+    times.sort();
+    times.dedup();
+    Some(times)
+}
+
+/// Parse dates like "2024-06-01,2024-06-08", in order
+pub fn parse_dates(s: &str) -> Option<Vec<NaiveDate>> {
+    let mut dates: Vec<NaiveDate> = s
+        .split(',')
+        .map(|d| NaiveDate::parse_from_str(d.trim(), DATE_FORMAT).ok())
+        .collect::<Option<_>>()?;
+    dates.sort();
+    dates.dedup();
+    Some(dates)
+}
+
+/// Parse weekdays like "Mon+Wed+Fri", in any case and spelled out or not
+pub fn parse_days(s: &str) -> Option<HashSet<Weekday>> {
+    let days: HashSet<Weekday> = s
+        .split('+')
+        .map(|d| Weekday::from_str(d.trim()).ok())
+        .collect::<Option<_>>()?;
+    (!days.is_empty()).then_some(days)
+}
+
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.date.format(DATE_FORMAT))?;
+        if let Some(time) = self.time_label() {
+            write!(f, " {}", time)?;
+        }
+        Ok(())
+    }
+}
+
+// Whole days serialize the same way as a plain date, so data from before time slots still loads
+impl Serialize for Slot {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Slot {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Slot::parse(&s).ok_or_else(|| D::Error::custom(format!("invalid slot: {}", s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, TIME_FORMAT).unwrap()
+    }
+
+    #[test]
+    fn slots_parse_with_or_without_a_time() {
+        let date = NaiveDate::from_ymd_opt(2024, 7, 13).unwrap();
+        assert_eq!(Slot::parse("2024-07-13"), Some(Slot::day(date)));
+        let evening = Slot {
+            date,
+            time: Some(time("18:00")),
+        };
+        assert_eq!(Slot::parse(" 2024-07-13 18:00 "), Some(evening));
+        assert_eq!(Slot::parse("2024-07-13T18:00"), Some(evening));
+        assert_eq!(Slot::parse("2024-07-13 25:00"), None);
+        assert_eq!(Slot::parse("13.07.2024"), None);
+    }
+
+    #[test]
+    fn times_are_sorted_and_deduplicated() {
+        assert_eq!(
+            parse_times("20:00 + 18:00+20:00"),
+            Some(vec![time("18:00"), time("20:00")])
+        );
+        assert_eq!(parse_times("18"), None);
+        assert_eq!(parse_times("18:00+"), None);
+    }
+
+    #[test]
+    fn slots_round_trip_through_their_text() {
+        for s in ["2024-07-13", "2024-07-13 09:30"] {
+            assert_eq!(Slot::parse(s).unwrap().to_string(), s);
+        }
+    }
+}
@@ -0,0 +1,493 @@
+//! Where schedulers are kept between runs. SQLite is used; the JSON files of earlier versions are
+//! imported into it on first run.
+
+use crate::scheduler::Scheduler;
+use crate::DATA_DIR;
+
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serenity::model::id::{GuildId, MessageId};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+const DATABASE_FILE: &str = "schedulers.db";
+const BACKUP_FILE: &str = "schedulers.backup.db";
+const ARCHIVE_DIR: &str = "archive";
+/// Schedulers parsed per task on the blocking pool at startup
+const LOAD_CHUNK: usize = 256;
+const LOAD_PROGRESS_INTERVAL: Duration = Duration::from_secs(2);
+
+/// What operators need to spot hosting problems before data is lost
+pub struct StorageStats {
+    /// Every file under the data directory, including settings and backups
+    pub files: usize,
+    pub bytes: u64,
+    pub live: usize,
+    pub archived: usize,
+    /// The oldest scheduler that is closed but still loaded at every start
+    pub oldest_closed: Option<MessageId>,
+    pub last_backup: Option<DateTime<Utc>>,
+    pub last_failed_write: Option<(DateTime<Utc>, String)>,
+}
+
+fn parse(data: &str) -> Scheduler {
+    serde_json::from_str(data).expect("Cannot parse data")
+}
+
+/// Run `f` against `storage` on the blocking pool, since SQLite holds up the thread it runs on
+pub async fn blocking<T, F>(storage: &Arc<dyn Storage>, f: F) -> T
+where
+    T: Send + 'static,
+    F: FnOnce(&dyn Storage) -> T + Send + 'static,
+{
+    let storage = storage.clone();
+    tokio::task::spawn_blocking(move || f(&*storage))
+        .await
+        .unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic()))
+}
+
+/// Save `scheduler` on the blocking pool, handing it back along with the outcome
+pub async fn save(
+    storage: &Arc<dyn Storage>,
+    id: MessageId,
+    scheduler: Scheduler,
+) -> (Scheduler, Result<(), String>) {
+    blocking(storage, move |storage| {
+        let result = storage.save(id, &scheduler);
+        (scheduler, result)
+    })
+    .await
+}
+
+/// The live schedulers that are closed, or open ones, parsed in chunks on the blocking pool so
+/// startup doesn't grow with the number of schedulers as fast, logging progress while it takes a
+/// while
+pub async fn load_parallel(storage: Arc<dyn Storage>, closed: bool) -> Vec<(MessageId, Scheduler)> {
+    let unparsed = tokio::task::spawn_blocking(move || storage.load_unparsed_closed(closed))
+        .await
+        .expect("Cannot load schedulers");
+    let total = unparsed.len();
+    let tasks: Vec<_> = unparsed
+        .into_iter()
+        .chunks(LOAD_CHUNK)
+        .into_iter()
+        .map(|chunk| {
+            let chunk: Vec<(MessageId, String)> = chunk.collect();
+            tokio::task::spawn_blocking(move || {
+                chunk
+                    .into_iter()
+                    .map(|(id, data)| (id, parse(&data)))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let mut loaded = Vec::with_capacity(total);
+    let mut logged = Instant::now();
+    for task in tasks {
+        loaded.extend(task.await.expect("Cannot parse schedulers"));
+        if logged.elapsed() >= LOAD_PROGRESS_INTERVAL {
+            info!("{}/{} schedulers loaded", loaded.len(), total);
+            logged = Instant::now();
+        }
+    }
+    loaded
+}
+
+/// Write `value` as JSON to `path`. It is written aside and renamed over the old file, so a crash
+/// mid-write leaves the old file intact.
+pub fn write_json(path: &Path, value: &impl Serialize) -> std::io::Result<()> {
+    let partial = path.with_extension("json.partial");
+    let file = File::create(&partial)?;
+    serde_json::to_writer(&file, value)?;
+    file.sync_all()?;
+    std::fs::rename(&partial, path)
+}
+
+/// Files and their total size under `dir`, recursively
+fn data_files(dir: &Path) -> (usize, u64) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return (0, 0),
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .fold((0, 0), |(files, bytes), entry| match entry.metadata() {
+            Ok(m) if m.is_dir() => {
+                let (f, b) = data_files(&entry.path());
+                (files + f, bytes + b)
+            }
+            Ok(m) => (files + 1, bytes + m.len()),
+            Err(_) => (files, bytes),
+        })
+}
+
+pub trait Storage: Send + Sync {
+    /// The data of every live scheduler, as saved
+    fn load_unparsed(&self) -> Vec<(MessageId, String)>;
+    /// The data of the live schedulers that are closed, or open ones
+    fn load_unparsed_closed(&self, closed: bool) -> Vec<(MessageId, String)> {
+        self.load_unparsed()
+            .into_iter()
+            .filter(|(_, data)| parse(data).is_closed() == closed)
+            .collect()
+    }
+    /// Every live scheduler
+    fn load(&self) -> Vec<(MessageId, Scheduler)> {
+        self.load_unparsed()
+            .into_iter()
+            .map(|(id, data)| (id, parse(&data)))
+            .collect()
+    }
+    fn save(&self, id: MessageId, scheduler: &Scheduler) -> Result<(), String>;
+    fn delete(&self, id: MessageId) -> Result<(), String>;
+    /// Move a scheduler out of the live set, keeping it for turnout history
+    fn archive(&self, id: MessageId) -> Result<(), String>;
+    /// Archived schedulers, from one guild or all of them
+    fn archived(&self, guild: Option<GuildId>) -> Vec<(MessageId, Scheduler)>;
+    fn delete_archived(&self, id: MessageId) -> Result<(), String>;
+    /// Copy everything into a backup alongside the data
+    fn backup(&self) -> Result<(), String>;
+    fn stats(&self) -> StorageStats;
+}
+
+/// One file per scheduler, with archived schedulers in a subdirectory
+pub struct JsonStorage {
+    dir: PathBuf,
+}
+
+impl JsonStorage {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        JsonStorage { dir: dir.into() }
+    }
+
+    fn archive_dir(&self) -> PathBuf {
+        self.dir.join(ARCHIVE_DIR)
+    }
+
+    fn file_path(dir: &Path, id: MessageId) -> PathBuf {
+        let mut path = dir.join(id.get().to_string());
+        path.set_extension("json");
+        path
+    }
+
+    fn read_dir(dir: &Path) -> Vec<(MessageId, String)> {
+        if !dir.is_dir() {
+            return vec![];
+        }
+        std::fs::read_dir(dir)
+            .expect("Cannot read data dir")
+            .filter_map(|f| read_file(&f.unwrap().path()))
+            .collect()
+    }
+}
+
+fn read_file(path: &Path) -> Option<(MessageId, String)> {
+    let extension = path.extension().and_then(|e| e.to_str());
+    if !matches!(extension, Some("json")) {
+        return None;
+    }
+    // Other data files live alongside the schedulers
+    let id: u64 = path.file_stem()?.to_str()?.parse().ok()?;
+    Some((
+        MessageId::new(id),
+        std::fs::read_to_string(path).expect("Cannot read file"),
+    ))
+}
+
+impl Storage for JsonStorage {
+    fn load_unparsed(&self) -> Vec<(MessageId, String)> {
+        Self::read_dir(&self.dir)
+    }
+
+    fn save(&self, id: MessageId, scheduler: &Scheduler) -> Result<(), String> {
+        write_json(&Self::file_path(&self.dir, id), scheduler).map_err(|e| e.to_string())
+    }
+
+    fn delete(&self, id: MessageId) -> Result<(), String> {
+        std::fs::remove_file(Self::file_path(&self.dir, id)).map_err(|e| e.to_string())
+    }
+
+    fn archive(&self, id: MessageId) -> Result<(), String> {
+        std::fs::create_dir_all(self.archive_dir()).map_err(|e| e.to_string())?;
+        std::fs::rename(
+            Self::file_path(&self.dir, id),
+            Self::file_path(&self.archive_dir(), id),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    fn archived(&self, guild: Option<GuildId>) -> Vec<(MessageId, Scheduler)> {
+        Self::read_dir(&self.archive_dir())
+            .into_iter()
+            .map(|(id, data)| (id, parse(&data)))
+            .filter(|(_, s)| guild.is_none() || s.guild() == guild)
+            .collect()
+    }
+
+    fn delete_archived(&self, id: MessageId) -> Result<(), String> {
+        std::fs::remove_file(Self::file_path(&self.archive_dir(), id)).map_err(|e| e.to_string())
+    }
+
+    fn backup(&self) -> Result<(), String> {
+        Err("Only the database can be backed up".to_owned())
+    }
+
+    fn stats(&self) -> StorageStats {
+        let live = self.load();
+        let (files, bytes) = data_files(&self.dir);
+        StorageStats {
+            files,
+            bytes,
+            live: live.len(),
+            archived: self.archived(None).len(),
+            oldest_closed: live
+                .iter()
+                .filter(|(_, s)| s.is_closed())
+                .map(|(id, _)| *id)
+                .min(),
+            last_backup: None,
+            last_failed_write: None,
+        }
+    }
+}
+
+/// Schedulers as JSON in a single table, with the columns needed to query them
+pub struct SqliteStorage {
+    conn: Mutex<Connection>,
+    /// Kept in memory, since a failing database can't be relied on to record it
+    last_failed_write: Mutex<Option<(DateTime<Utc>, String)>>,
+}
+
+// Each entry brings the schema up to the next version
+const MIGRATIONS: &[&str] = &["CREATE TABLE schedulers (
+        id INTEGER PRIMARY KEY,
+        guild_id INTEGER,
+        closed INTEGER NOT NULL,
+        archived INTEGER NOT NULL DEFAULT 0,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX schedulers_guild ON schedulers (guild_id, archived);"];
+
+// SQLite integers are signed, so ids are stored with the same bits
+fn to_sql(id: u64) -> i64 {
+    id as i64
+}
+
+fn from_sql(id: i64) -> u64 {
+    id as u64
+}
+
+impl SqliteStorage {
+    pub fn open() -> Self {
+        let path = Path::new(DATA_DIR).join(DATABASE_FILE);
+        let conn = Connection::open(path).expect("Cannot open database");
+        Self::migrate(conn, Some(&JsonStorage::new(DATA_DIR)))
+    }
+
+    /// A database that lives as long as the storage, for tests
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        let conn = Connection::open_in_memory().expect("Cannot open database");
+        Self::migrate(conn, None)
+    }
+
+    /// Bring `conn` up to the current schema, importing the schedulers of `files` if it is new
+    fn migrate(mut conn: Connection, files: Option<&JsonStorage>) -> Self {
+        let version: i64 = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .expect("Cannot read schema version");
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let tx = conn.transaction().expect("Cannot start transaction");
+            tx.execute_batch(migration)
+                .expect("Cannot migrate database");
+            tx.pragma_update(None, "user_version", (i + 1) as i64)
+                .expect("Cannot update schema version");
+            // Schedulers saved as files by earlier versions move in with the first schema
+            if let (0, Some(files)) = (i, files) {
+                import(&tx, files);
+            }
+            tx.commit().expect("Cannot commit migration");
+        }
+        SqliteStorage {
+            conn: Mutex::new(conn),
+            last_failed_write: Default::default(),
+        }
+    }
+
+    fn query_unparsed(&self, sql: &str, params: impl rusqlite::Params) -> Vec<(MessageId, String)> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare_cached(sql).expect("Cannot prepare query");
+        let rows = stmt
+            .query_map(params, |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .expect("Cannot query schedulers");
+        rows.map(|row| {
+            let (id, data) = row.expect("Cannot read scheduler");
+            (MessageId::new(from_sql(id)), data)
+        })
+        .collect()
+    }
+
+    /// Run `sql` on the scheduler `id`, remembering the failure for the stats
+    fn write(&self, sql: &str, id: MessageId) -> Result<(), String> {
+        let result = self
+            .conn
+            .lock()
+            .unwrap()
+            .execute(sql, [to_sql(id.get())])
+            .map(|_| ())
+            .map_err(|e| e.to_string());
+        if let Err(e) = &result {
+            *self.last_failed_write.lock().unwrap() = Some((Utc::now(), e.clone()));
+        }
+        result
+    }
+
+    fn query(&self, sql: &str, params: impl rusqlite::Params) -> Vec<(MessageId, Scheduler)> {
+        self.query_unparsed(sql, params)
+            .into_iter()
+            .map(|(id, data)| (id, parse(&data)))
+            .collect()
+    }
+}
+
+fn upsert(
+    conn: &Connection,
+    id: MessageId,
+    scheduler: &Scheduler,
+    archived: bool,
+) -> Result<(), String> {
+    let data = serde_json::to_string(scheduler).map_err(|e| e.to_string())?;
+    conn.prepare_cached(
+        "INSERT INTO schedulers (id, guild_id, closed, archived, data)
+        VALUES (?1, ?2, ?3, ?4, ?5)
+        ON CONFLICT (id) DO UPDATE SET
+            guild_id = excluded.guild_id, closed = excluded.closed, data = excluded.data",
+    )
+    .and_then(|mut stmt| {
+        stmt.execute(params![
+            to_sql(id.get()),
+            scheduler.guild().map(|g| to_sql(g.get())),
+            scheduler.is_closed(),
+            archived,
+            data
+        ])
+    })
+    .map(|_| ())
+    .map_err(|e| e.to_string())
+}
+
+fn import(conn: &Connection, json: &JsonStorage) {
+    let live = json.load();
+    let archived = json.archived(None);
+    for (id, scheduler) in live.iter() {
+        upsert(conn, *id, scheduler, false).expect("Cannot import scheduler");
+    }
+    for (id, scheduler) in archived.iter() {
+        upsert(conn, *id, scheduler, true).expect("Cannot import scheduler");
+    }
+    info!(
+        "imported {} schedulers and {} archived from files",
+        live.len(),
+        archived.len()
+    );
+}
+
+impl Storage for SqliteStorage {
+    fn load_unparsed(&self) -> Vec<(MessageId, String)> {
+        self.query_unparsed("SELECT id, data FROM schedulers WHERE NOT archived", [])
+    }
+
+    fn load_unparsed_closed(&self, closed: bool) -> Vec<(MessageId, String)> {
+        self.query_unparsed(
+            "SELECT id, data FROM schedulers WHERE NOT archived AND closed = ?1",
+            [closed],
+        )
+    }
+
+    fn save(&self, id: MessageId, scheduler: &Scheduler) -> Result<(), String> {
+        let result = upsert(&self.conn.lock().unwrap(), id, scheduler, false);
+        if let Err(e) = &result {
+            *self.last_failed_write.lock().unwrap() = Some((Utc::now(), e.clone()));
+        }
+        result
+    }
+
+    fn delete(&self, id: MessageId) -> Result<(), String> {
+        self.write("DELETE FROM schedulers WHERE id = ?1", id)
+    }
+
+    fn archive(&self, id: MessageId) -> Result<(), String> {
+        self.write("UPDATE schedulers SET archived = 1 WHERE id = ?1", id)
+    }
+
+    fn archived(&self, guild: Option<GuildId>) -> Vec<(MessageId, Scheduler)> {
+        match guild {
+            Some(guild) => self.query(
+                "SELECT id, data FROM schedulers WHERE archived AND guild_id = ?1",
+                [to_sql(guild.get())],
+            ),
+            None => self.query("SELECT id, data FROM schedulers WHERE archived", []),
+        }
+    }
+
+    fn delete_archived(&self, id: MessageId) -> Result<(), String> {
+        self.write("DELETE FROM schedulers WHERE id = ?1 AND archived", id)
+    }
+
+    fn backup(&self) -> Result<(), String> {
+        let path = Path::new(DATA_DIR).join(BACKUP_FILE);
+        let partial = path.with_extension("partial");
+        // VACUUM INTO won't overwrite, and the last good backup is kept until the new one is done
+        if partial.exists() {
+            std::fs::remove_file(&partial).map_err(|e| e.to_string())?;
+        }
+        self.conn
+            .lock()
+            .unwrap()
+            .execute("VACUUM INTO ?1", [partial.to_string_lossy()])
+            .map_err(|e| e.to_string())?;
+        std::fs::rename(&partial, &path).map_err(|e| e.to_string())
+    }
+
+    fn stats(&self) -> StorageStats {
+        let (files, bytes) = data_files(Path::new(DATA_DIR));
+        // The backup's age survives restarts, unlike anything kept in memory
+        let last_backup = std::fs::metadata(Path::new(DATA_DIR).join(BACKUP_FILE))
+            .and_then(|m| m.modified())
+            .ok()
+            .map(DateTime::<Utc>::from);
+        let conn = self.conn.lock().unwrap();
+        let count = |archived: bool| -> usize {
+            conn.query_row(
+                "SELECT COUNT(*) FROM schedulers WHERE archived = ?1",
+                [archived],
+                |row| row.get::<_, i64>(0),
+            )
+            .expect("Cannot count schedulers") as usize
+        };
+        let oldest_closed = conn
+            .query_row(
+                "SELECT MIN(id) FROM schedulers WHERE closed AND NOT archived",
+                [],
+                |row| row.get::<_, Option<i64>>(0),
+            )
+            .expect("Cannot query schedulers")
+            .map(|id| MessageId::new(from_sql(id)));
+        StorageStats {
+            files,
+            bytes,
+            live: count(false),
+            archived: count(true),
+            oldest_closed,
+            last_backup,
+            last_failed_write: self.last_failed_write.lock().unwrap().clone(),
+        }
+    }
+}
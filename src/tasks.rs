@@ -0,0 +1,203 @@
+//! Periodic background work, started once the gateway is ready
+
+use crate::actor::SchedulerHandle;
+use crate::guild_config::{self, GuildConfig};
+use crate::notify::Notifier;
+use crate::posts::Posts;
+use crate::storage::{self, Storage};
+use crate::{DateMessages, Schedulers};
+
+use chrono::{Local, Utc};
+use serenity::client::Context;
+use serenity::model::id::GuildId;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use tracing::{error, info};
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const NOTIFY_INTERVAL: Duration = Duration::from_secs(60);
+const POST_INTERVAL: Duration = Duration::from_secs(60);
+const DUE_INTERVAL: Duration = Duration::from_secs(60);
+const ROLL_OVER_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const EXPIRE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const BACKUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+pub fn spawn(
+    ctx: &Context,
+    schedulers: Arc<Schedulers>,
+    date_messages: Arc<DateMessages>,
+    storage: Arc<dyn Storage>,
+    guild_configs: Arc<RwLock<HashMap<GuildId, GuildConfig>>>,
+    posts: Posts,
+) {
+    tokio::spawn(post_scheduled(
+        ctx.clone(),
+        schedulers.clone(),
+        date_messages,
+        storage.clone(),
+        posts.clone(),
+    ));
+    tokio::spawn(run_due(ctx.clone(), schedulers.clone()));
+    tokio::spawn(roll_over(ctx.clone(), schedulers.clone(), storage.clone()));
+    tokio::spawn(expire_dates(ctx.clone(), schedulers.clone()));
+    tokio::spawn(back_up(storage.clone()));
+    tokio::spawn(purge_removed_guilds(
+        schedulers,
+        storage,
+        posts,
+        guild_configs,
+    ));
+    tokio::spawn(send_deferred_notifications(ctx.clone()));
+}
+
+async fn post_scheduled(
+    ctx: Context,
+    schedulers: Arc<Schedulers>,
+    date_messages: Arc<DateMessages>,
+    storage: Arc<dyn Storage>,
+    posts: Posts,
+) {
+    let mut interval = tokio::time::interval(POST_INTERVAL);
+    loop {
+        interval.tick().await;
+        for post in posts.take_due().await {
+            crate::post_scheduled(&ctx, &schedulers, &date_messages, &storage, post).await;
+        }
+    }
+}
+
+/// Back up the database daily, logging how storage is doing for whoever watches the logs
+async fn back_up(storage: Arc<dyn Storage>) {
+    let mut interval = tokio::time::interval(BACKUP_INTERVAL);
+    loop {
+        interval.tick().await;
+        let (backup, stats) =
+            storage::blocking(&storage, |storage| (storage.backup(), storage.stats())).await;
+        if let Err(e) = backup {
+            error!("Cannot back up storage: {}", e);
+        }
+        info!(
+            "storage: {} files, {} bytes, {} live, {} archived, last failed write {:?}",
+            stats.files,
+            stats.bytes,
+            stats.live,
+            stats.archived,
+            stats.last_failed_write.map(|(at, _)| at)
+        );
+    }
+}
+
+/// Close schedulers that reached their quorum or deadline, and send automatic reminders and
+/// requests to firm up tentative picks
+async fn run_due(ctx: Context, schedulers: Arc<Schedulers>) {
+    let mut interval = tokio::time::interval(DUE_INTERVAL);
+    loop {
+        interval.tick().await;
+        let now = Utc::now();
+        let handles: Vec<SchedulerHandle> = schedulers.iter().map(|h| h.clone()).collect();
+        for handle in handles {
+            let due = handle
+                .get(move |s| s.due_to_close(now) || s.due_to_remind(now) || s.due_to_firm_up(now));
+            if due.await != Some(true) {
+                continue;
+            }
+            let c = ctx.clone();
+            handle
+                .update(move |s| {
+                    Box::pin(async move {
+                        // It may have been closed by hand in the meantime
+                        if s.due_to_close(now) {
+                            s.auto_close(&c).await;
+                            return;
+                        }
+                        if s.due_to_remind(now) {
+                            s.auto_remind(&c).await;
+                        }
+                        if s.due_to_firm_up(now) {
+                            s.ask_firm_up(&c, now).await;
+                        }
+                    })
+                })
+                .await;
+        }
+    }
+}
+
+/// Post the next generation of recurring schedulers whose dates have all passed
+async fn roll_over(ctx: Context, schedulers: Arc<Schedulers>, storage: Arc<dyn Storage>) {
+    let mut interval = tokio::time::interval(ROLL_OVER_INTERVAL);
+    loop {
+        interval.tick().await;
+        let today = Local::now().date_naive();
+        let handles: Vec<SchedulerHandle> = schedulers.iter().map(|h| h.clone()).collect();
+        for handle in handles {
+            if handle.get(move |s| s.due_to_roll_over(today)).await == Some(true) {
+                crate::roll_over(&ctx, &schedulers, &storage, handle).await;
+            }
+        }
+    }
+}
+
+/// Stop offering dates that have passed, daily and once at startup
+async fn expire_dates(ctx: Context, schedulers: Arc<Schedulers>) {
+    let mut interval = tokio::time::interval(EXPIRE_INTERVAL);
+    loop {
+        // The first tick completes immediately
+        interval.tick().await;
+        let now = Utc::now();
+        let handles: Vec<SchedulerHandle> = schedulers.iter().map(|h| h.clone()).collect();
+        for handle in handles {
+            if handle.get(move |s| s.due_to_expire(now)).await != Some(true) {
+                continue;
+            }
+            let c = ctx.clone();
+            handle
+                .update(move |s| Box::pin(async move { s.expire_past(&c, now).await }))
+                .await;
+        }
+    }
+}
+
+async fn send_deferred_notifications(ctx: Context) {
+    let notifier = ctx
+        .data
+        .read()
+        .await
+        .get::<Notifier>()
+        .cloned()
+        .expect("Cannot find notifier");
+    let mut interval = tokio::time::interval(NOTIFY_INTERVAL);
+    loop {
+        interval.tick().await;
+        notifier.flush(&ctx).await;
+    }
+}
+
+async fn purge_removed_guilds(
+    schedulers: Arc<Schedulers>,
+    storage: Arc<dyn Storage>,
+    posts: Posts,
+    guild_configs: Arc<RwLock<HashMap<GuildId, GuildConfig>>>,
+) {
+    let mut interval = tokio::time::interval(PURGE_INTERVAL);
+    loop {
+        interval.tick().await;
+        let now = Utc::now();
+        let mut configs = guild_configs.write().await;
+        let expired: Vec<GuildId> = configs
+            .iter()
+            .filter_map(|(id, config)| {
+                let removed_at = config.removed_at?;
+                let days = config
+                    .retention_days
+                    .unwrap_or(guild_config::DEFAULT_RETENTION_DAYS);
+                (now - removed_at >= chrono::Duration::days(days.into())).then_some(*id)
+            })
+            .collect();
+        for guild in expired {
+            crate::purge_guild(&schedulers, &storage, &posts, &mut configs, guild).await;
+        }
+    }
+}
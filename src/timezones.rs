@@ -0,0 +1,39 @@
+use crate::storage;
+use crate::DATA_DIR;
+
+use chrono_tz::Tz;
+use serenity::model::id::UserId;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::PathBuf;
+use tracing::info;
+
+const TIMEZONE_FILE: &str = "timezones.json";
+
+fn file_path() -> PathBuf {
+    let mut path: PathBuf = DATA_DIR.into();
+    path.push(TIMEZONE_FILE);
+    path
+}
+
+pub fn load() -> HashMap<UserId, Tz> {
+    let path = file_path();
+    if !path.exists() {
+        return HashMap::new();
+    }
+    let file = File::open(path).expect("Cannot open file");
+    let timezones: HashMap<UserId, Tz> =
+        serde_json::from_reader(file).expect("Cannot parse timezones");
+    info!("{} user timezones loaded", timezones.len());
+    timezones
+}
+
+pub fn save(timezones: &HashMap<UserId, Tz>) -> std::io::Result<()> {
+    storage::write_json(&file_path(), timezones)
+}
+
+/// The broad region of a timezone, e.g. "Europe" for Europe/Berlin
+pub fn region(tz: &Tz) -> &'static str {
+    let name = tz.name();
+    name.split_once('/').map_or(name, |(region, _)| region)
+}
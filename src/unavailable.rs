@@ -0,0 +1,68 @@
+//! Personal calendars of when users can't play, applied to every scheduler they respond to
+
+use crate::slot::Slot;
+use crate::storage;
+use crate::DATA_DIR;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serenity::model::id::UserId;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::File;
+use std::path::PathBuf;
+use tracing::info;
+
+const UNAVAILABLE_FILE: &str = "unavailable.json";
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Calendar {
+    /// Weekdays the user is never available on
+    #[serde(default)]
+    pub weekdays: HashSet<Weekday>,
+    #[serde(default)]
+    pub dates: BTreeSet<NaiveDate>,
+}
+
+impl Calendar {
+    /// Whether the user can't make `slot`
+    pub fn blocks(&self, slot: &Slot) -> bool {
+        self.weekdays.contains(&slot.date.weekday()) || self.dates.contains(&slot.date)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weekdays.is_empty() && self.dates.is_empty()
+    }
+
+    /// The weekdays and dates, e.g. "Mon, Wed, 2024-07-13"
+    pub fn summary(&self) -> String {
+        self.weekdays
+            .iter()
+            .sorted_by_key(|d| d.num_days_from_monday())
+            .map(|d| d.to_string())
+            .chain(self.dates.iter().map(|d| d.to_string()))
+            .join(", ")
+    }
+}
+
+fn file_path() -> PathBuf {
+    let mut path: PathBuf = DATA_DIR.into();
+    path.push(UNAVAILABLE_FILE);
+    path
+}
+
+pub fn load() -> HashMap<UserId, Calendar> {
+    let path = file_path();
+    if !path.exists() {
+        return HashMap::new();
+    }
+    let file = File::open(path).expect("Cannot open file");
+    let calendars: HashMap<UserId, Calendar> =
+        serde_json::from_reader(file).expect("Cannot parse personal calendars");
+    info!("{} personal calendars loaded", calendars.len());
+    calendars
+}
+
+pub fn save(calendars: &HashMap<UserId, Calendar>) -> std::io::Result<()> {
+    storage::write_json(&file_path(), calendars)
+}
@@ -0,0 +1,66 @@
+//! Checks on the text owners give their schedulers, shared by every command that takes it
+
+use crate::limits;
+use crate::locale::{Phrase, Text};
+
+/// Room kept in the embed description for the scheduler's status lines
+const DESCRIPTION_RESERVED_LENGTH: usize = 512;
+/// The longest body that still leaves room for the status lines
+pub const MAX_BODY_LENGTH: usize = limits::EMBED_DESCRIPTION_LENGTH - DESCRIPTION_RESERVED_LENGTH;
+
+/// Check a title against `max`, which is capped at what an embed title can hold
+pub fn title(title: &str, max: usize) -> Result<(), Phrase> {
+    let max = max.min(limits::EMBED_TITLE_LENGTH);
+    if title.trim().is_empty() {
+        Err(Text::DescriptionEmpty.into())
+    } else if title.len() > max {
+        Err(Phrase::new(Text::DescriptionTooLong, &[&max]))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check a markdown body shown under the title
+pub fn body(body: &str) -> Result<(), Phrase> {
+    if body.len() > MAX_BODY_LENGTH {
+        Err(Phrase::new(Text::BodyTooLong, &[&MAX_BODY_LENGTH]))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn titles_must_have_text() {
+        assert_eq!(title("Game night", 100), Ok(()));
+        assert_eq!(title("  ", 100), Err(Text::DescriptionEmpty.into()));
+    }
+
+    #[test]
+    fn titles_are_capped_at_the_embed_limit() {
+        assert_eq!(
+            title("Game night", 4),
+            Err(Phrase::new(Text::DescriptionTooLong, &[&4]))
+        );
+        let long = "x".repeat(limits::EMBED_TITLE_LENGTH + 1);
+        assert_eq!(
+            title(&long, 1000),
+            Err(Phrase::new(
+                Text::DescriptionTooLong,
+                &[&limits::EMBED_TITLE_LENGTH]
+            ))
+        );
+    }
+
+    #[test]
+    fn bodies_leave_room_for_the_status_lines() {
+        assert_eq!(body(&"x".repeat(MAX_BODY_LENGTH)), Ok(()));
+        assert_eq!(
+            body(&"x".repeat(MAX_BODY_LENGTH + 1)),
+            Err(Phrase::new(Text::BodyTooLong, &[&MAX_BODY_LENGTH]))
+        );
+    }
+}
@@ -0,0 +1,70 @@
+//! JSON posted to a guild's webhook as its schedulers change, for tools outside Discord
+
+#[cfg(any(test, feature = "test-harness"))]
+use crate::harness::{Call, Recorder};
+use crate::notify::Notifier;
+
+use serde::Serialize;
+use serenity::client::Context;
+use serenity::model::id::{GuildId, MessageId, UserId};
+use std::time::Duration;
+use tracing::error;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Payload {
+    /// Someone added or changed their response; who is left out on anonymous schedulers
+    Response {
+        scheduler: MessageId,
+        title: String,
+        user: Option<UserId>,
+        dates: Vec<String>,
+        maybe: Vec<String>,
+    },
+    /// The owner changed the blacked out dates
+    Blackout {
+        scheduler: MessageId,
+        title: String,
+        dates: Vec<String>,
+    },
+    Closed {
+        scheduler: MessageId,
+        title: String,
+        best: Vec<String>,
+    },
+}
+
+/// Post `payload` to `guild`'s webhook, if it set one. Delivery happens in the background and
+/// failures are only logged, so a slow endpoint never holds up the scheduler.
+pub async fn send(ctx: &Context, guild: Option<GuildId>, payload: Payload) {
+    let notifier = ctx
+        .data
+        .read()
+        .await
+        .get::<Notifier>()
+        .cloned()
+        .expect("Cannot find notifier");
+    let url = match notifier.webhook(guild).await {
+        Some(url) => url,
+        None => return,
+    };
+    #[cfg(any(test, feature = "test-harness"))]
+    if let Some(recorder) = Recorder::get(ctx).await {
+        recorder.record(Call::Webhook { url, payload });
+        return;
+    }
+    tokio::spawn(async move {
+        let sent = reqwest::Client::new()
+            .post(&url)
+            .json(&payload)
+            .timeout(TIMEOUT)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = sent {
+            error!("Cannot send webhook: {}", e);
+        }
+    });
+}